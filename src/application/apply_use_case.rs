@@ -0,0 +1,306 @@
+use crate::domain::{
+    DomainError, EditorManifest, ExtensionLock, ExtensionRepository, InstalledExtension,
+    ManifestChange, PlannedAction, VersionSelector, compare_versions,
+};
+use std::cmp::Ordering;
+
+/// Use case for converging an editor's extensions to a manifest
+pub struct ApplyManifestUseCase<E: ExtensionRepository> {
+    extension_repo: E,
+}
+
+impl<E: ExtensionRepository> ApplyManifestUseCase<E> {
+    pub fn new(extension_repo: E) -> Self {
+        Self { extension_repo }
+    }
+
+    /// Works out the changes that bring the installed extensions in line with
+    /// an editor's manifest
+    ///
+    /// Every listed extension is resolved in the marketplace with its version
+    /// constraints. Extensions that are missing are installed, ones at another
+    /// version are upgraded or downgraded and ones built for another platform
+    /// than the requested one are reinstalled. With `prune`, installed
+    /// extensions the manifest does not list are uninstalled.
+    ///
+    /// With a `lock`, extensions are not looked up in the marketplace: the
+    /// locked versions are used and installed from the locked packages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a listed extension, or the version it is pinned to,
+    /// is not found in the marketplace, or the lock has no matching entry for it
+    pub async fn execute(
+        &self,
+        manifest: &EditorManifest,
+        installed: &[InstalledExtension],
+        prune: bool,
+        lock: Option<&ExtensionLock>,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<ManifestChange>, DomainError> {
+        let mut changes = Vec::new();
+
+        for requirement in &manifest.extensions {
+            let locked = lock
+                .map(|lock| lock.entry_for(&manifest.editor, requirement))
+                .transpose()?;
+            let extension = match locked {
+                Some(entry) => entry.to_extension(),
+                None => {
+                    self.extension_repo
+                        .resolve_extension(&requirement.id, &requirement.selector, marketplace_url)
+                        .await?
+                }
+            };
+            let current = installed
+                .iter()
+                .filter(|ext| ext.id.eq_ignore_ascii_case(&requirement.id))
+                .max_by(|a, b| compare_versions(&a.version, &b.version));
+
+            let action = match current {
+                None => PlannedAction::Install,
+                Some(current) => match compare_versions(&extension.version, &current.version) {
+                    Ordering::Greater => PlannedAction::Update,
+                    Ordering::Less => PlannedAction::Downgrade,
+                    Ordering::Equal
+                        if requirement.selector.platform.is_some()
+                            && current.target_platform != extension.target_platform =>
+                    {
+                        PlannedAction::Update
+                    }
+                    Ordering::Equal => continue,
+                },
+            };
+
+            changes.push(ManifestChange {
+                editor: manifest.editor.clone(),
+                action,
+                extension_id: extension.unique_identifier(),
+                installed_version: current.map(|ext| ext.version.clone()),
+                version: Some(extension.version),
+                selector: requirement.selector.clone(),
+                locked: locked.cloned(),
+                profile: None,
+            });
+        }
+
+        if prune {
+            for ext in installed {
+                let listed = manifest
+                    .extensions
+                    .iter()
+                    .any(|requirement| requirement.id.eq_ignore_ascii_case(&ext.id));
+                let seen = changes
+                    .iter()
+                    .any(|change| change.extension_id.eq_ignore_ascii_case(&ext.id));
+                if !listed && !seen {
+                    changes.push(ManifestChange {
+                        editor: manifest.editor.clone(),
+                        action: PlannedAction::Uninstall,
+                        extension_id: ext.id.clone(),
+                        installed_version: Some(ext.version.clone()),
+                        version: None,
+                        selector: VersionSelector::default(),
+                        locked: None,
+                        profile: None,
+                    });
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Extension, ExtensionRequirement, LockedExtension, ReleaseChannel, SearchResult,
+    };
+    use async_trait::async_trait;
+
+    struct MockExtensionRepo;
+
+    #[async_trait]
+    impl ExtensionRepository for MockExtensionRepo {
+        async fn search(
+            &self,
+            _query: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<SearchResult, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_extension(
+            &self,
+            id: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            let (publisher, name) = id.split_once('.').unwrap();
+            Ok(Extension {
+                id: id.to_string(),
+                name: name.to_string(),
+                publisher: publisher.to_string(),
+                version: "2.0.0".to_string(),
+                display_name: name.to_string(),
+                description: None,
+                downloads: 0,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
+            })
+        }
+
+        async fn get_extensions(
+            &self,
+            _ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn resolve_extension(
+            &self,
+            id: &str,
+            selector: &VersionSelector,
+            marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            let mut extension = self.get_extension(id, marketplace_url).await?;
+            if let Some(version) = &selector.version {
+                extension.version.clone_from(version);
+            }
+            extension.target_platform.clone_from(&selector.platform);
+            Ok(extension)
+        }
+
+        async fn download(
+            &self,
+            _extension: &Extension,
+            _target_platform: Option<&str>,
+        ) -> Result<Vec<u8>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn installed_extension(id: &str, version: &str) -> InstalledExtension {
+        InstalledExtension {
+            id: id.to_string(),
+            version: version.to_string(),
+            target_platform: None,
+            size: None,
+            installed_at: None,
+            location: None,
+            disabled: false,
+            pre_release: false,
+        }
+    }
+
+    fn requirement(
+        id: &str,
+        version: Option<&str>,
+        platform: Option<&str>,
+    ) -> ExtensionRequirement {
+        ExtensionRequirement {
+            id: id.to_string(),
+            selector: VersionSelector {
+                version: version.map(ToString::to_string),
+                channel: ReleaseChannel::Stable,
+                platform: platform.map(ToString::to_string),
+                engine: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_plans_changes() {
+        let use_case = ApplyManifestUseCase::new(MockExtensionRepo);
+        let manifest = EditorManifest {
+            editor: "vscode".to_string(),
+            extensions: vec![
+                requirement("rust-lang.rust-analyzer", None, None),
+                requirement("ms-python.python", None, None),
+                requirement("golang.go", Some("1.0.0"), None),
+                requirement("tamasfe.even-better-toml", Some("2.0.0"), None),
+                requirement("ms-vscode.cpptools", None, Some("linux-arm64")),
+            ],
+            prune: false,
+        };
+        let installed = vec![
+            installed_extension("ms-python.python", "1.0.0"),
+            installed_extension("Golang.Go", "1.5.0"),
+            installed_extension("tamasfe.even-better-toml", "2.0.0"),
+            installed_extension("ms-vscode.cpptools", "2.0.0"),
+            installed_extension("eamodio.gitlens", "15.0.0"),
+        ];
+
+        let changes = use_case
+            .execute(&manifest, &installed, false, None, None)
+            .await
+            .unwrap();
+        let summary: Vec<(PlannedAction, &str)> = changes
+            .iter()
+            .map(|change| (change.action, change.extension_id.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (PlannedAction::Install, "rust-lang.rust-analyzer"),
+                (PlannedAction::Update, "ms-python.python"),
+                (PlannedAction::Downgrade, "golang.go"),
+                (PlannedAction::Update, "ms-vscode.cpptools"),
+            ]
+        );
+        assert_eq!(changes[2].installed_version.as_deref(), Some("1.5.0"));
+        assert_eq!(changes[2].version.as_deref(), Some("1.0.0"));
+
+        let changes = use_case
+            .execute(&manifest, &installed, true, None, None)
+            .await
+            .unwrap();
+        let last = changes.last().unwrap();
+        assert_eq!(last.action, PlannedAction::Uninstall);
+        assert_eq!(last.extension_id, "eamodio.gitlens");
+        assert_eq!(changes.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_apply_uses_locked_versions() {
+        let use_case = ApplyManifestUseCase::new(MockExtensionRepo);
+        let manifest = EditorManifest {
+            editor: "vscode".to_string(),
+            extensions: vec![requirement("golang.go", None, None)],
+            prune: false,
+        };
+        let entry = LockedExtension {
+            editor: "vscode".to_string(),
+            id: "golang.go".to_string(),
+            version: "1.0.0".to_string(),
+            target_platform: None,
+            url: "https://example.com/golang.go-1.0.0.vsix".to_string(),
+            sha256: "00".to_string(),
+        };
+        let lock = ExtensionLock {
+            extensions: vec![entry.clone()],
+            ..ExtensionLock::default()
+        };
+
+        let changes = use_case
+            .execute(&manifest, &[], false, Some(&lock), None)
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].version.as_deref(), Some("1.0.0"));
+        assert_eq!(changes[0].locked, Some(entry));
+
+        let unlocked = EditorManifest {
+            extensions: vec![requirement("ms-python.python", None, None)],
+            ..manifest
+        };
+        let result = use_case
+            .execute(&unlocked, &[], false, Some(&lock), None)
+            .await;
+        assert!(matches!(result, Err(DomainError::StaleLock(_))));
+    }
+}
@@ -1,7 +1,9 @@
 use crate::domain::{
-    DomainError, ExtensionRepository, IdeType, InstallationDetector, InstallationExecutor,
-    InstallationStrategy,
+    DomainError, Extension, ExtensionRepository, IdeType, InstallOptions, InstallationDetector,
+    InstallationExecutor, InstallationMethod, InstallationStrategy, LockedExtension, PlannedAction,
+    PlannedChange, ReleaseChannel, VersionSelector, VsixPackage,
 };
+use std::path::PathBuf;
 
 /// Use case for installing extensions with improved architecture
 pub struct ImprovedInstallExtensionUseCase<E, D, X>
@@ -13,6 +15,9 @@ where
     extension_repo: E,
     detector: D,
     executor: X,
+    options: InstallOptions,
+    selector: VersionSelector,
+    locked: Option<LockedExtension>,
 }
 
 impl<E, D, X> ImprovedInstallExtensionUseCase<E, D, X>
@@ -26,46 +31,102 @@ where
             extension_repo,
             detector,
             executor,
+            options: InstallOptions::default(),
+            selector: VersionSelector::default(),
+            locked: None,
         }
     }
 
+    /// Sets the install options (`--force`, `--pre-release`)
+    #[must_use]
+    pub fn with_options(mut self, options: InstallOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Installs the version `selector` picks instead of the latest one
+    #[must_use]
+    pub fn with_selector(mut self, selector: VersionSelector) -> Self {
+        self.selector = selector;
+        self
+    }
+
+    /// Installs the package a lockfile entry records instead of looking the
+    /// extension up, rejecting downloads whose SHA-256 differs from the entry
+    #[must_use]
+    pub fn with_locked(mut self, locked: Option<LockedExtension>) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Looks up the extension, or takes it from the lockfile entry
+    ///
+    /// `--pre-release` moves the selector to the pre-release channel, so the
+    /// newest release of any kind is picked whichever method installs it.
+    async fn resolve(
+        &self,
+        extension_id: &str,
+        marketplace_url: Option<&str>,
+    ) -> Result<Extension, DomainError> {
+        if let Some(locked) = &self.locked {
+            return Ok(locked.to_extension());
+        }
+
+        let selector = if self.options.pre_release {
+            VersionSelector {
+                channel: ReleaseChannel::PreRelease,
+                ..self.selector.clone()
+            }
+        } else {
+            self.selector.clone()
+        };
+        self.extension_repo
+            .resolve_extension(extension_id, &selector, marketplace_url)
+            .await
+    }
+
     /// Executes the install use case with automatic method detection
     ///
+    /// The extension is installed into the given profile, or the default profile
+    /// if `profile` is `None`.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Extension ID format is invalid
+    /// - The profile does not exist
     /// - Extension is not found
+    /// - The package does not match the lockfile entry's SHA-256
     /// - Installation method cannot be detected
     /// - Installation fails
     pub async fn execute(
         &self,
         extension_id: &str,
-        use_cursor: bool,
+        ide_type: &IdeType,
+        profile: Option<&str>,
         marketplace_url: Option<&str>,
     ) -> Result<(), DomainError> {
         // Validate extension ID format
-        self.validate_extension_id(extension_id)?;
-
-        // Determine IDE type
-        let ide_type = if use_cursor {
-            IdeType::Cursor
-        } else {
-            IdeType::VsCode
-        };
+        validate_extension_id(extension_id)?;
 
         // Detect installation method
-        let method = self.detector.detect_method(&ide_type).await?;
-        let strategy = InstallationStrategy::new(ide_type, method);
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method)
+            .with_profile(profile)
+            .with_options(self.options);
 
         // Get extension metadata
-        let extension = self
-            .extension_repo
-            .get_extension(extension_id, marketplace_url)
-            .await?;
+        let extension = self.resolve(extension_id, marketplace_url).await?;
 
         // Download VSIX data
         let vsix_data = self.extension_repo.download(&extension, None).await?;
+        if let Some(locked) = &self.locked {
+            locked.verify(&vsix_data)?;
+        }
 
         // Execute installation
         self.executor
@@ -73,22 +134,216 @@ where
             .await
     }
 
-    /// Validates the extension ID format
-    fn validate_extension_id(&self, extension_id: &str) -> Result<(), DomainError> {
-        let parts: Vec<&str> = extension_id.split('.').collect();
-        if parts.len() != 2 {
-            return Err(DomainError::InvalidExtensionFormat(
-                "Extension ID must be in format 'publisher.name'".to_string(),
-            ));
+    /// Resolves what [`execute`](Self::execute) would do without doing it
+    ///
+    /// The extension metadata is looked up in the marketplace, but nothing is
+    /// downloaded or written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension ID format is invalid, the profile does not
+    /// exist, the extension is not found or the installation method cannot be
+    /// detected
+    pub async fn plan(
+        &self,
+        extension_id: &str,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<PlannedChange, DomainError> {
+        validate_extension_id(extension_id)?;
+
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method)
+            .with_profile(profile)
+            .with_options(self.options);
+
+        let extension = self.resolve(extension_id, marketplace_url).await?;
+        let id = extension.unique_identifier();
+        let destination = install_destination(&self.detector, &strategy, &id, &extension.version)?;
+
+        Ok(PlannedChange {
+            action: PlannedAction::Install,
+            download_url: Some(extension.download_url(None)),
+            version: Some(extension.version),
+            installed_version: None,
+            target_platform: extension.target_platform,
+            dependencies: extension.dependencies,
+            extension_id: id,
+            strategy,
+            destination: Some(destination),
+        })
+    }
+}
+
+/// Use case for installing a VSIX package that was not taken from a marketplace
+pub struct InstallPackageUseCase<D, X>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+{
+    detector: D,
+    executor: X,
+    options: InstallOptions,
+}
+
+impl<D, X> InstallPackageUseCase<D, X>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+{
+    pub fn new(detector: D, executor: X) -> Self {
+        Self {
+            detector,
+            executor,
+            options: InstallOptions::default(),
+        }
+    }
+
+    /// Sets the install options (`--force`, `--pre-release`)
+    #[must_use]
+    pub fn with_options(mut self, options: InstallOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Installs a VSIX package with automatic method detection
+    ///
+    /// `extension_id` is the identity declared in the package's manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Extension ID format is invalid
+    /// - The profile does not exist
+    /// - Installation method cannot be detected
+    /// - Installation fails
+    pub async fn execute(
+        &self,
+        extension_id: &str,
+        vsix_data: &[u8],
+        ide_type: &IdeType,
+        profile: Option<&str>,
+    ) -> Result<(), DomainError> {
+        validate_extension_id(extension_id)?;
+
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method)
+            .with_profile(profile)
+            .with_options(self.options);
+
+        self.executor
+            .execute(&strategy, extension_id, vsix_data)
+            .await
+    }
+
+    /// Resolves what [`execute`](Self::execute) would do without doing it
+    ///
+    /// `package` is `None` for packages that are only known after downloading them
+    /// from `download_url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist or the installation method
+    /// cannot be detected
+    pub async fn plan(
+        &self,
+        package: Option<&VsixPackage>,
+        download_url: Option<&str>,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+    ) -> Result<PlannedChange, DomainError> {
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method)
+            .with_profile(profile)
+            .with_options(self.options);
+
+        let destination = package
+            .map(|package| {
+                install_destination(&self.detector, &strategy, &package.id(), &package.version)
+            })
+            .transpose()?;
+
+        Ok(PlannedChange {
+            action: PlannedAction::Install,
+            extension_id: package.map_or_else(
+                || download_url.unwrap_or_default().to_string(),
+                VsixPackage::id,
+            ),
+            version: package.map(|package| package.version.clone()),
+            installed_version: None,
+            target_platform: package.and_then(|package| package.target_platform.clone()),
+            download_url: download_url.map(ToString::to_string),
+            dependencies: package
+                .map(|package| {
+                    package
+                        .dependencies
+                        .iter()
+                        .chain(&package.pack)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default(),
+            strategy,
+            destination,
+        })
+    }
+}
+
+/// Returns the folder an extension would be installed into
+///
+/// Direct installs use a folder named after the extension ID, while the CLI names
+/// it `<id>-<version>` inside the IDE's extensions directory.
+fn install_destination<D: InstallationDetector>(
+    detector: &D,
+    strategy: &InstallationStrategy,
+    extension_id: &str,
+    version: &str,
+) -> Result<PathBuf, DomainError> {
+    match &strategy.method {
+        InstallationMethod::FileSystem { extensions_dir }
+        | InstallationMethod::RemoteServer { extensions_dir } => {
+            Ok(extensions_dir.join(extension_id))
+        }
+        InstallationMethod::CliCommand { extensions_dir, .. } => {
+            let extensions_dir = match extensions_dir {
+                Some(dir) => dir.clone(),
+                None => detector.extensions_directory(&strategy.ide_type)?,
+            };
+            Ok(extensions_dir.join(format!("{}-{version}", extension_id.to_lowercase())))
         }
-        Ok(())
     }
 }
 
+/// Validates the extension ID format
+pub(crate) fn validate_extension_id(extension_id: &str) -> Result<(), DomainError> {
+    let parts: Vec<&str> = extension_id.split('.').collect();
+    if parts.len() != 2 {
+        return Err(DomainError::InvalidExtensionFormat(
+            "Extension ID must be in format 'publisher.name'".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{Extension, InstallationMethod, SearchResult};
+    use crate::domain::{
+        Extension, InstallationMethod, InstalledExtension, ProfileTarget, SearchResult,
+    };
     use async_trait::async_trait;
     use std::path::PathBuf;
 
@@ -122,10 +377,35 @@ mod tests {
                     display_name: "Test Extension".to_string(),
                     description: Some("Test description".to_string()),
                     downloads: 100,
+                    package_url: None,
+                    target_platform: None,
+                    dependencies: Vec::new(),
                 })
             }
         }
 
+        async fn get_extensions(
+            &self,
+            _ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn resolve_extension(
+            &self,
+            id: &str,
+            selector: &VersionSelector,
+            marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            let mut extension = self.get_extension(id, marketplace_url).await?;
+            if selector.channel == ReleaseChannel::PreRelease {
+                extension.version = "1.1.0".to_string();
+            }
+            Ok(extension)
+        }
+
         async fn download(
             &self,
             _extension: &Extension,
@@ -152,6 +432,7 @@ mod tests {
             if self.use_cli {
                 Ok(InstallationMethod::CliCommand {
                     command_path: PathBuf::from("/usr/local/bin/code"),
+                    extensions_dir: None,
                 })
             } else {
                 Ok(InstallationMethod::FileSystem {
@@ -159,6 +440,23 @@ mod tests {
                 })
             }
         }
+
+        fn extensions_directory(&self, _ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+            Ok(PathBuf::from("/home/user/.vscode/extensions"))
+        }
+
+        fn resolve_profile(
+            &self,
+            _ide_type: &IdeType,
+            name: &str,
+        ) -> Result<Option<ProfileTarget>, DomainError> {
+            Ok(Some(ProfileTarget {
+                name: name.to_string(),
+                registry_path: PathBuf::from(
+                    "/home/user/.config/Code/User/profiles/1/extensions.json",
+                ),
+            }))
+        }
     }
 
     struct MockExecutor {
@@ -181,6 +479,21 @@ mod tests {
                 Ok(())
             }
         }
+
+        async fn uninstall(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_installed(
+            &self,
+            _strategy: &InstallationStrategy,
+        ) -> Result<Vec<InstalledExtension>, DomainError> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test]
@@ -192,7 +505,9 @@ mod tests {
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
         // Test with invalid ID (no dot separator)
-        let result = use_case.execute("invalidid", false, None).await;
+        let result = use_case
+            .execute("invalidid", &IdeType::vscode(), None, None)
+            .await;
         assert!(result.is_err());
 
         match result {
@@ -211,7 +526,9 @@ mod tests {
 
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
-        let result = use_case.execute("publisher.extension", false, None).await;
+        let result = use_case
+            .execute("publisher.extension", &IdeType::vscode(), None, None)
+            .await;
         assert!(result.is_ok());
     }
 
@@ -223,8 +540,67 @@ mod tests {
 
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
-        let result = use_case.execute("publisher.extension", true, None).await;
+        let result = use_case
+            .execute("publisher.extension", &IdeType::cursor(), None, None)
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pre_release_option_selects_pre_release_version() {
+        let use_case = |pre_release| {
+            ImprovedInstallExtensionUseCase::new(
+                MockExtensionRepo { should_fail: false },
+                MockDetector { use_cli: false },
+                MockExecutor { should_fail: false },
+            )
+            .with_options(InstallOptions {
+                pre_release,
+                ..InstallOptions::default()
+            })
+        };
+
+        let stable = use_case(false)
+            .plan("publisher.extension", &IdeType::vscode(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(stable.version.as_deref(), Some("1.0.0"));
+
+        let pre_release = use_case(true)
+            .plan("publisher.extension", &IdeType::vscode(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(pre_release.version.as_deref(), Some("1.1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_locked_installation_verifies_checksum() {
+        let locked = |sha256: String| LockedExtension {
+            editor: "vscode".to_string(),
+            id: "publisher.extension".to_string(),
+            version: "1.0.0".to_string(),
+            target_platform: None,
+            url: "https://example.com/publisher.extension-1.0.0.vsix".to_string(),
+            sha256,
+        };
+        let use_case = |locked| {
+            ImprovedInstallExtensionUseCase::new(
+                MockExtensionRepo { should_fail: false },
+                MockDetector { use_cli: false },
+                MockExecutor { should_fail: false },
+            )
+            .with_locked(Some(locked))
+        };
+
+        let result = use_case(locked(crate::domain::sha256_hex(&[1, 2, 3, 4])))
+            .execute("publisher.extension", &IdeType::vscode(), None, None)
+            .await;
         assert!(result.is_ok());
+
+        let result = use_case(locked(crate::domain::sha256_hex(b"other")))
+            .execute("publisher.extension", &IdeType::vscode(), None, None)
+            .await;
+        assert!(matches!(result, Err(DomainError::ChecksumMismatch(_))));
     }
 
     #[tokio::test]
@@ -235,7 +611,9 @@ mod tests {
 
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
-        let result = use_case.execute("publisher.extension", false, None).await;
+        let result = use_case
+            .execute("publisher.extension", &IdeType::vscode(), None, None)
+            .await;
         assert!(result.is_err());
 
         match result {
@@ -252,7 +630,9 @@ mod tests {
 
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
-        let result = use_case.execute("publisher.extension", false, None).await;
+        let result = use_case
+            .execute("publisher.extension", &IdeType::vscode(), None, None)
+            .await;
         assert!(result.is_err());
 
         match result {
@@ -261,6 +641,70 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_install_package() {
+        let detector = MockDetector { use_cli: false };
+        let executor = MockExecutor { should_fail: false };
+        let use_case = InstallPackageUseCase::new(detector, executor);
+
+        let result = use_case
+            .execute("publisher.extension", &[1, 2, 3], &IdeType::vscode(), None)
+            .await;
+        assert!(result.is_ok());
+
+        let result = use_case
+            .execute("extension", &[1, 2, 3], &IdeType::vscode(), None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_install() {
+        let repo = MockExtensionRepo { should_fail: false };
+        let detector = MockDetector { use_cli: false };
+        let executor = MockExecutor { should_fail: true };
+        let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
+
+        let plan = use_case
+            .plan("publisher.test", &IdeType::vscode(), Some("Rust"), None)
+            .await
+            .unwrap();
+        assert_eq!(plan.action, PlannedAction::Install);
+        assert_eq!(plan.version.as_deref(), Some("1.0.0"));
+        assert_eq!(
+            plan.destination,
+            Some(PathBuf::from(
+                "/home/user/.vscode/extensions/publisher.test"
+            ))
+        );
+        assert_eq!(
+            plan.download_url.as_deref(),
+            Some(
+                "https://marketplace.visualstudio.com/_apis/public/gallery/publishers/publisher/vsextensions/test/1.0.0/vspackage"
+            )
+        );
+        assert_eq!(plan.strategy.profile.unwrap().name, "Rust");
+
+        let use_case = ImprovedInstallExtensionUseCase::new(
+            MockExtensionRepo { should_fail: false },
+            MockDetector { use_cli: true },
+            MockExecutor { should_fail: true },
+        );
+        let plan = use_case
+            .plan("Publisher.Test", &IdeType::vscode(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.destination,
+            Some(PathBuf::from(
+                "/home/user/.vscode/extensions/publisher.test-1.0.0"
+            ))
+        );
+    }
+
     #[tokio::test]
     async fn test_cursor_installation() {
         let repo = MockExtensionRepo { should_fail: false };
@@ -270,7 +714,9 @@ mod tests {
         let use_case = ImprovedInstallExtensionUseCase::new(repo, detector, executor);
 
         // Test with use_cursor = true
-        let result = use_case.execute("publisher.extension", true, None).await;
+        let result = use_case
+            .execute("publisher.extension", &IdeType::cursor(), None, None)
+            .await;
         assert!(result.is_ok());
     }
 }
@@ -0,0 +1,205 @@
+use crate::domain::{
+    DomainError, ExtensionStateRepository, IdeType, InstallationDetector, InstallationExecutor,
+    InstallationMethod, InstallationStrategy, InstalledExtension,
+};
+
+/// Use case for listing installed extensions
+pub struct ListInstalledExtensionsUseCase<D, X, S>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+    S: ExtensionStateRepository,
+{
+    detector: D,
+    executor: X,
+    state: S,
+}
+
+impl<D, X, S> ListInstalledExtensionsUseCase<D, X, S>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+    S: ExtensionStateRepository,
+{
+    pub fn new(detector: D, executor: X, state: S) -> Self {
+        Self {
+            detector,
+            executor,
+            state,
+        }
+    }
+
+    /// Executes the list use case
+    ///
+    /// The extensions directory is read directly when it exists, since it carries
+    /// sizes, target platforms and install dates the CLI cannot report. The
+    /// detected installation method is used otherwise. With a profile, only the
+    /// extensions installed in that profile are listed. Extensions the editor has
+    /// disabled are marked as such.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile does not exist, the installation method
+    /// cannot be detected or the installed extensions or editor state cannot be
+    /// read
+    pub async fn execute(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+    ) -> Result<Vec<InstalledExtension>, DomainError> {
+        let extensions_dir = self.detector.extensions_directory(ide_type)?;
+        let method = if extensions_dir.is_dir() {
+            InstallationMethod::FileSystem { extensions_dir }
+        } else {
+            self.detector.detect_method(ide_type).await?
+        };
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let disabled = self.state.disabled_extensions(ide_type, profile.as_ref())?;
+        let strategy = InstallationStrategy::new(ide_type.clone(), method).with_profile(profile);
+
+        let mut extensions = self.executor.list_installed(&strategy).await?;
+        for extension in &mut extensions {
+            extension.disabled = disabled
+                .iter()
+                .any(|id| id.eq_ignore_ascii_case(&extension.id));
+        }
+        Ok(extensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ProfileTarget;
+    use async_trait::async_trait;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    struct MockDetector {
+        extensions_dir: PathBuf,
+    }
+
+    #[async_trait]
+    impl InstallationDetector for MockDetector {
+        async fn detect_method(
+            &self,
+            _ide_type: &IdeType,
+        ) -> Result<InstallationMethod, DomainError> {
+            Ok(InstallationMethod::CliCommand {
+                command_path: PathBuf::from("/usr/local/bin/code"),
+                extensions_dir: None,
+            })
+        }
+
+        fn extensions_directory(&self, _ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+            Ok(self.extensions_dir.clone())
+        }
+
+        fn resolve_profile(
+            &self,
+            _ide_type: &IdeType,
+            name: &str,
+        ) -> Result<Option<ProfileTarget>, DomainError> {
+            Ok(Some(ProfileTarget {
+                name: name.to_string(),
+                registry_path: PathBuf::from(
+                    "/home/user/.config/Code/User/profiles/1/extensions.json",
+                ),
+            }))
+        }
+    }
+
+    struct MockState;
+
+    impl ExtensionStateRepository for MockState {
+        fn disabled_extensions(
+            &self,
+            _ide_type: &IdeType,
+            _profile: Option<&ProfileTarget>,
+        ) -> Result<Vec<String>, DomainError> {
+            Ok(vec!["Publisher.Extension".to_string()])
+        }
+
+        fn set_disabled(
+            &self,
+            _ide_type: &IdeType,
+            _profile: Option<&ProfileTarget>,
+            _extension_id: &str,
+            _disabled: bool,
+            _force: bool,
+        ) -> Result<bool, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    struct MockExecutor;
+
+    #[async_trait]
+    impl InstallationExecutor for MockExecutor {
+        async fn execute(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+            _vsix_data: &[u8],
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn uninstall(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_installed(
+            &self,
+            strategy: &InstallationStrategy,
+        ) -> Result<Vec<InstalledExtension>, DomainError> {
+            let version = match strategy.method {
+                InstallationMethod::FileSystem { .. } | InstallationMethod::RemoteServer { .. } => {
+                    "filesystem"
+                }
+                InstallationMethod::CliCommand { .. } => "cli",
+            };
+            Ok(vec![InstalledExtension {
+                id: "publisher.extension".to_string(),
+                version: version.to_string(),
+                target_platform: None,
+                size: None,
+                installed_at: None,
+                location: None,
+                disabled: false,
+                pre_release: false,
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_prefers_existing_extensions_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let detector = MockDetector {
+            extensions_dir: temp_dir.path().to_path_buf(),
+        };
+        let use_case = ListInstalledExtensionsUseCase::new(detector, MockExecutor, MockState);
+
+        let extensions = use_case.execute(&IdeType::vscode(), None).await.unwrap();
+        assert_eq!(extensions[0].version, "filesystem");
+        assert!(extensions[0].disabled);
+    }
+
+    #[tokio::test]
+    async fn test_list_falls_back_to_detected_method() {
+        let detector = MockDetector {
+            extensions_dir: PathBuf::from("/nonexistent/extensions"),
+        };
+        let use_case = ListInstalledExtensionsUseCase::new(detector, MockExecutor, MockState);
+
+        let extensions = use_case.execute(&IdeType::cursor(), None).await.unwrap();
+        assert_eq!(extensions[0].version, "cli");
+    }
+}
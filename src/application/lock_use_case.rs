@@ -0,0 +1,223 @@
+use crate::domain::{
+    Architecture, DomainError, EditorManifest, Extension, ExtensionRepository,
+    ExtensionRequirement, LockedExtension, VersionSelector,
+};
+
+/// Use case for pinning a manifest's extensions to exact packages
+pub struct LockManifestUseCase<E: ExtensionRepository> {
+    extension_repo: E,
+}
+
+impl<E: ExtensionRepository> LockManifestUseCase<E> {
+    pub fn new(extension_repo: E) -> Self {
+        Self { extension_repo }
+    }
+
+    /// Resolves every extension of an editor's manifest and records the
+    /// package it resolves to
+    ///
+    /// Extensions built per platform are locked at the version resolved for this
+    /// machine for every platform that version is published for, so that the
+    /// lockfile works on every machine of a team and in CI. A requirement pinned
+    /// to a platform is only locked for that one. Each package is downloaded to
+    /// record its SHA-256.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a listed extension, or the version it is pinned to,
+    /// is not found in the marketplace or its package cannot be downloaded
+    pub async fn execute(
+        &self,
+        manifest: &EditorManifest,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<LockedExtension>, DomainError> {
+        let mut locked = Vec::with_capacity(manifest.extensions.len());
+
+        for requirement in &manifest.extensions {
+            for extension in self.resolve_packages(requirement, marketplace_url).await? {
+                let vsix_data = self
+                    .extension_repo
+                    .download(&extension, extension.target_platform.as_deref())
+                    .await?;
+                locked.push(LockedExtension::new(
+                    &manifest.editor,
+                    &extension,
+                    &vsix_data,
+                ));
+            }
+        }
+
+        Ok(locked)
+    }
+
+    /// Resolves the packages of a requirement, one per target platform
+    async fn resolve_packages(
+        &self,
+        requirement: &ExtensionRequirement,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<Extension>, DomainError> {
+        let extension = self
+            .extension_repo
+            .resolve_extension(&requirement.id, &requirement.selector, marketplace_url)
+            .await?;
+        if extension.target_platform.is_none() || requirement.selector.platform.is_some() {
+            return Ok(vec![extension]);
+        }
+
+        let mut packages = vec![extension];
+        for target in &Architecture::TARGETS {
+            let selector = VersionSelector {
+                version: Some(packages[0].version.clone()),
+                platform: target.to_platform_string().map(ToString::to_string),
+                ..requirement.selector.clone()
+            };
+            let package = match self
+                .extension_repo
+                .resolve_extension(&requirement.id, &selector, marketplace_url)
+                .await
+            {
+                Ok(package) => package,
+                // The version is not published for this platform
+                Err(DomainError::ExtensionNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+
+            // Platforms without a build of their own share the universal package
+            if !packages
+                .iter()
+                .any(|locked| locked.target_platform == package.target_platform)
+            {
+                packages.push(package);
+            }
+        }
+
+        Ok(packages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Extension, ExtensionRequirement, SearchResult, VersionSelector, sha256_hex,
+    };
+    use async_trait::async_trait;
+
+    /// Publishes acme.tools for three platforms, this machine being linux-x64
+    struct MockExtensionRepo;
+
+    const PLATFORMS: [&str; 3] = ["linux-x64", "win32-x64", "darwin-arm64"];
+
+    #[async_trait]
+    impl ExtensionRepository for MockExtensionRepo {
+        async fn search(
+            &self,
+            _query: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<SearchResult, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_extension(
+            &self,
+            id: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            let (publisher, name) = id.split_once('.').unwrap();
+            Ok(Extension {
+                id: id.to_string(),
+                name: name.to_string(),
+                publisher: publisher.to_string(),
+                version: "1.0.0".to_string(),
+                display_name: name.to_string(),
+                description: None,
+                downloads: 0,
+                package_url: Some(format!("https://example.com/{id}-1.0.0.vsix")),
+                target_platform: None,
+                dependencies: Vec::new(),
+            })
+        }
+
+        async fn resolve_extension(
+            &self,
+            id: &str,
+            selector: &VersionSelector,
+            marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            let platform = selector.platform.as_deref().unwrap_or("linux-x64");
+            if !PLATFORMS.contains(&platform) {
+                return Err(DomainError::ExtensionNotFound(id.to_string()));
+            }
+            let mut extension = self.get_extension(id, marketplace_url).await?;
+            extension.package_url = Some(format!("https://example.com/{id}-1.0.0@{platform}.vsix"));
+            extension.target_platform = Some(platform.to_string());
+            Ok(extension)
+        }
+
+        async fn get_extensions(
+            &self,
+            _ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn download(
+            &self,
+            extension: &Extension,
+            target_platform: Option<&str>,
+        ) -> Result<Vec<u8>, DomainError> {
+            Ok(format!(
+                "{}@{}",
+                extension.unique_identifier(),
+                target_platform.unwrap()
+            )
+            .into_bytes())
+        }
+    }
+
+    fn locked_entry(platform: &str) -> LockedExtension {
+        LockedExtension {
+            editor: "vscode".to_string(),
+            id: "acme.tools".to_string(),
+            version: "1.0.0".to_string(),
+            target_platform: Some(platform.to_string()),
+            url: format!("https://example.com/acme.tools-1.0.0@{platform}.vsix"),
+            sha256: sha256_hex(format!("acme.tools@{platform}").as_bytes()),
+        }
+    }
+
+    fn manifest(platform: Option<&str>) -> EditorManifest {
+        EditorManifest {
+            editor: "vscode".to_string(),
+            extensions: vec![ExtensionRequirement {
+                id: "acme.tools".to_string(),
+                selector: VersionSelector {
+                    platform: platform.map(ToString::to_string),
+                    ..VersionSelector::default()
+                },
+            }],
+            prune: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lock_records_packages_for_every_platform() {
+        let use_case = LockManifestUseCase::new(MockExtensionRepo);
+
+        let locked = use_case.execute(&manifest(None), None).await.unwrap();
+        assert_eq!(locked, PLATFORMS.map(locked_entry).to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_lock_records_pinned_platform_only() {
+        let use_case = LockManifestUseCase::new(MockExtensionRepo);
+
+        let locked = use_case
+            .execute(&manifest(Some("darwin-arm64")), None)
+            .await
+            .unwrap();
+        assert_eq!(locked, vec![locked_entry("darwin-arm64")]);
+    }
+}
@@ -1,7 +1,21 @@
+pub mod apply_use_case;
 pub mod install_use_case;
+pub mod list_use_case;
+pub mod lock_use_case;
+pub mod outdated_use_case;
 pub mod services;
+pub mod state_use_case;
+pub mod sync_use_case;
+pub mod uninstall_use_case;
 pub mod use_cases;
 
+pub use apply_use_case::*;
 pub use install_use_case::*;
+pub use list_use_case::*;
+pub use lock_use_case::*;
+pub use outdated_use_case::*;
 pub use services::*;
+pub use state_use_case::*;
+pub use sync_use_case::*;
+pub use uninstall_use_case::*;
 pub use use_cases::*;
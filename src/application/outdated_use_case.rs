@@ -0,0 +1,233 @@
+use crate::domain::{
+    DomainError, ExtensionRepository, InstalledExtension, OutdatedExtension, ReleaseChannel,
+    VersionSelector, compare_versions,
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Use case for finding installed extensions with newer marketplace versions
+pub struct OutdatedExtensionsUseCase<E: ExtensionRepository> {
+    extension_repo: E,
+}
+
+impl<E: ExtensionRepository> OutdatedExtensionsUseCase<E> {
+    pub fn new(extension_repo: E) -> Self {
+        Self { extension_repo }
+    }
+
+    /// Compares installed extensions with their latest marketplace versions
+    ///
+    /// All extensions are looked up in a single marketplace query, which finds
+    /// stable releases. Extensions installed from their pre-release channel are
+    /// then looked up one by one on that channel. Only versions whose engine range
+    /// accepts `engine`, the editor's VS Code version, are considered. Extensions
+    /// the marketplace does not know about (e.g. locally built ones) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the marketplace query fails
+    pub async fn execute(
+        &self,
+        installed: &[InstalledExtension],
+        engine: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<OutdatedExtension>, DomainError> {
+        // Several versions may be installed side by side; only the newest counts
+        let mut newest_installed: Vec<&InstalledExtension> = Vec::new();
+        for ext in installed {
+            match newest_installed
+                .iter_mut()
+                .find(|newest| newest.id.eq_ignore_ascii_case(&ext.id))
+            {
+                Some(newest) => {
+                    if compare_versions(&ext.version, &newest.version) == Ordering::Greater {
+                        *newest = ext;
+                    }
+                }
+                None => newest_installed.push(ext),
+            }
+        }
+
+        let ids: Vec<String> = newest_installed.iter().map(|ext| ext.id.clone()).collect();
+        let mut latest: HashMap<String, String> = self
+            .extension_repo
+            .get_extensions(&ids, engine, marketplace_url)
+            .await?
+            .into_iter()
+            .map(|ext| (ext.unique_identifier().to_lowercase(), ext.version))
+            .collect();
+
+        let pre_release = VersionSelector {
+            channel: ReleaseChannel::PreRelease,
+            engine: engine.map(ToString::to_string),
+            ..VersionSelector::default()
+        };
+        for ext in newest_installed.iter().filter(|ext| ext.pre_release) {
+            if let Ok(resolved) = self
+                .extension_repo
+                .resolve_extension(&ext.id, &pre_release, marketplace_url)
+                .await
+            {
+                latest.insert(ext.id.to_lowercase(), resolved.version);
+            }
+        }
+
+        Ok(newest_installed
+            .into_iter()
+            .filter_map(|ext| {
+                let latest_version = latest.get(&ext.id.to_lowercase())?;
+                (compare_versions(latest_version, &ext.version) == Ordering::Greater).then(|| {
+                    OutdatedExtension {
+                        id: ext.id.clone(),
+                        installed_version: ext.version.clone(),
+                        latest_version: latest_version.clone(),
+                        pre_release: ext.pre_release,
+                    }
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Extension, SearchResult};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockExtensionRepo {
+        queries: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ExtensionRepository for MockExtensionRepo {
+        async fn search(
+            &self,
+            _query: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<SearchResult, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_extension(
+            &self,
+            _id: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            unimplemented!()
+        }
+
+        async fn resolve_extension(
+            &self,
+            id: &str,
+            selector: &VersionSelector,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            assert_eq!(selector.channel, ReleaseChannel::PreRelease);
+            assert_eq!(selector.engine.as_deref(), Some("1.95.3"));
+            assert_eq!(id, "ms-python.python");
+            Ok(marketplace_extension("ms-python", "python", "2024.3.0"))
+        }
+
+        async fn get_extensions(
+            &self,
+            ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            self.queries.lock().unwrap().push(ids.to_vec());
+            Ok(vec![
+                marketplace_extension("ms-python", "python", "2024.2.0"),
+                marketplace_extension("rust-lang", "rust-analyzer", "0.3.0"),
+            ])
+        }
+
+        async fn download(
+            &self,
+            _extension: &Extension,
+            _target_platform: Option<&str>,
+        ) -> Result<Vec<u8>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn marketplace_extension(publisher: &str, name: &str, version: &str) -> Extension {
+        Extension {
+            id: format!("{publisher}.{name}"),
+            name: name.to_string(),
+            publisher: publisher.to_string(),
+            version: version.to_string(),
+            display_name: name.to_string(),
+            description: None,
+            downloads: 0,
+            package_url: None,
+            target_platform: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    fn installed_extension(id: &str, version: &str) -> InstalledExtension {
+        InstalledExtension {
+            id: id.to_string(),
+            version: version.to_string(),
+            target_platform: None,
+            size: None,
+            installed_at: None,
+            location: None,
+            disabled: false,
+            pre_release: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_outdated_uses_single_batched_query() {
+        let use_case = OutdatedExtensionsUseCase::new(MockExtensionRepo::default());
+        let installed = vec![
+            installed_extension("ms-python.python", "2024.1.0"),
+            installed_extension("rust-lang.rust-analyzer", "0.3.0"),
+            installed_extension("internal.tool", "1.0.0"),
+        ];
+
+        let outdated = use_case.execute(&installed, None, None).await.unwrap();
+
+        assert_eq!(
+            outdated,
+            vec![OutdatedExtension {
+                id: "ms-python.python".to_string(),
+                installed_version: "2024.1.0".to_string(),
+                latest_version: "2024.2.0".to_string(),
+                pre_release: false,
+            }]
+        );
+        assert_eq!(use_case.extension_repo.queries.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_outdated_ignores_side_by_side_current_version() {
+        let use_case = OutdatedExtensionsUseCase::new(MockExtensionRepo::default());
+        let installed = vec![
+            installed_extension("ms-python.python", "2024.1.0"),
+            installed_extension("ms-python.python", "2024.2.0"),
+        ];
+
+        let outdated = use_case.execute(&installed, None, None).await.unwrap();
+        assert!(outdated.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_outdated_follows_pre_release_channel() {
+        let use_case = OutdatedExtensionsUseCase::new(MockExtensionRepo::default());
+        let mut python = installed_extension("ms-python.python", "2024.2.1");
+        python.pre_release = true;
+
+        let outdated = use_case
+            .execute(&[python], Some("1.95.3"), None)
+            .await
+            .unwrap();
+        assert_eq!(outdated.len(), 1);
+        assert_eq!(outdated[0].latest_version, "2024.3.0");
+        assert!(outdated[0].pre_release);
+    }
+}
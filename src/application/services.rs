@@ -1,9 +1,34 @@
-use crate::application::install_use_case::ImprovedInstallExtensionUseCase;
+use crate::application::apply_use_case::ApplyManifestUseCase;
+use crate::application::install_use_case::{
+    ImprovedInstallExtensionUseCase, InstallPackageUseCase, validate_extension_id,
+};
+use crate::application::list_use_case::ListInstalledExtensionsUseCase;
+use crate::application::lock_use_case::LockManifestUseCase;
+use crate::application::outdated_use_case::OutdatedExtensionsUseCase;
+use crate::application::state_use_case::SetExtensionStateUseCase;
+use crate::application::sync_use_case::SyncExtensionsUseCase;
+use crate::application::uninstall_use_case::UninstallExtensionUseCase;
 use crate::application::use_cases::{InstallExtensionUseCase, SearchExtensionUseCase};
-use crate::domain::{DomainError, ExtensionRepository, InstallationRepository};
+use crate::domain::{
+    Architecture, CodeProfile, DirectoryAccess, DomainError, EditorDiagnosis, EnvironmentDiagnosis,
+    ExtensionLock, ExtensionRepository, ExtensionSync, IdeType, InstallOptions,
+    InstallationDetector, InstallationMethod, InstallationRepository, InstalledExtension,
+    ManifestChange, OutdatedExtension, PackageSource, PlannedAction, PlannedChange, ReleaseChannel,
+    StaleExtension, VsixInspection, VsixPackage,
+};
 use crate::infrastructure::{
-    FileSystemRepository, MarketplaceClient, SystemInstallationDetector, SystemInstallationExecutor,
+    FileSystemRepository, GlobalStateStore, MarketplaceClient, SystemInstallationDetector,
+    SystemInstallationExecutor, VsixConfig, config_path, detect_libc, directory_access,
+    find_stale_extensions, gallery_service_url, inspect_vsix, lock_file_path, pack_extension,
+    proxy_variables, read_code_profile, read_devcontainer_extensions, read_extension_lock,
+    read_extension_manifest, read_vsix_package, read_workspace_recommendations,
+    remove_stale_extensions, scan_extensions_directory, write_code_profile, write_extension_lock,
 };
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Time `vsix doctor` waits for an editor CLI or a gallery to answer
+const DIAGNOSTIC_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Main application service that provides high-level operations for extension management.
 ///
@@ -23,10 +48,10 @@ use crate::infrastructure::{
 ///     let results = service.search_extensions("python", None).await?;
 ///     
 ///     // Install the Python extension to VSCode
-///     service.install_extension("ms-python.python", false, None).await?;
+///     service.install_extension("ms-python.python", "vscode", None, None).await?;
 ///     
-///     // Install an extension to Cursor instead
-///     service.install_extension("rust-lang.rust-analyzer", true, None).await?;
+///     // Install an extension to VSCodium (from Open VSX) instead
+///     service.install_extension("rust-lang.rust-analyzer", "vscodium", None, None).await?;
 ///     
 ///     Ok(())
 /// }
@@ -36,6 +61,7 @@ pub struct ApplicationService {
     file_system_repo: FileSystemRepository,
     installation_detector: SystemInstallationDetector,
     installation_executor: SystemInstallationExecutor,
+    global_state: GlobalStateStore,
 }
 
 impl Default for ApplicationService {
@@ -52,6 +78,7 @@ impl ApplicationService {
     /// - File system repository for direct installation
     /// - Installation detector for finding CLI tools
     /// - Installation executor for running CLI commands
+    /// - Global state store for enabling and disabling extensions
     ///
     /// # Examples
     ///
@@ -67,9 +94,95 @@ impl ApplicationService {
             file_system_repo: FileSystemRepository::new(),
             installation_detector: SystemInstallationDetector::new(),
             installation_executor: SystemInstallationExecutor::new(),
+            global_state: GlobalStateStore::new(),
         }
     }
 
+    /// Targets the IDE's remote server instead of the local installation.
+    ///
+    /// On a machine reached through Remote-SSH or a dev container, extensions live
+    /// in the server's extensions directory (e.g. `~/.vscode-server/extensions` or
+    /// `~/.cursor-server/extensions`). That directory is picked automatically when
+    /// it exists and no local installation does; this option forces it. Extensions
+    /// whose `extensionKind` only allows running in the local editor are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new().with_server_target(true);
+    ///
+    /// service.install_extension("rust-lang.rust-analyzer", "vscode", None, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_server_target(mut self, server_target: bool) -> Self {
+        self.installation_detector = self.installation_detector.with_server_target(server_target);
+        self
+    }
+
+    /// Uses a custom extensions directory instead of the IDE's default one.
+    ///
+    /// The directory is used directly for filesystem installs and forwarded as
+    /// `--extensions-dir` when the IDE's CLI is used. Without it, the
+    /// `VSCODE_EXTENSIONS` environment variable and portable installs
+    /// (`VSCODE_PORTABLE`, or a `data` folder next to the IDE's binary) are honored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::PathBuf;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new()
+    ///     .with_extensions_dir(Some(PathBuf::from("/opt/team/extensions")));
+    ///
+    /// service.install_extension("ms-python.python", "vscode", None, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_extensions_dir(mut self, extensions_dir: Option<PathBuf>) -> Self {
+        self.installation_detector = self
+            .installation_detector
+            .with_extensions_dir(extensions_dir);
+        self
+    }
+
+    /// Looks up an IDE by name.
+    ///
+    /// The built-in IDEs (VSCode, Insiders, Cursor, VSCodium, Windsurf, code-server,
+    /// Positron and Trae) are combined with the `[[editors]]` entries of the user
+    /// configuration file, which can add new IDEs or override built-in ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the configuration file is invalid or no IDE with
+    /// that name exists
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    /// let ide = service.resolve_ide("vscodium")?;
+    ///
+    /// println!("Installing into {}", ide.display_name());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve_ide(&self, ide: &str) -> Result<IdeType, DomainError> {
+        VsixConfig::load()?
+            .into_ide_registry()
+            .resolve(ide)
+            .cloned()
+    }
+
     /// Searches for extensions in the marketplace.
     ///
     /// # Arguments
@@ -146,15 +259,16 @@ impl ApplicationService {
 
     /// Installs an extension using automatic method detection.
     ///
-    /// This method automatically detects if `code` or `cursor` CLI commands are available
-    /// in the system PATH and uses them for installation. If CLI tools are not available,
-    /// it falls back to direct filesystem extraction.
+    /// This method automatically detects if the IDE's CLI command (`code`, `cursor`,
+    /// `codium`, ...) is available in the system PATH and uses it for installation. If
+    /// the CLI is not available, it falls back to direct filesystem extraction.
     ///
     /// # Arguments
     ///
     /// * `extension_id` - The extension ID in format "publisher.name" (e.g., "ms-python.python")
-    /// * `use_cursor` - If `true`, installs to Cursor; if `false`, installs to VSCode
-    /// * `marketplace_url` - Optional custom marketplace URL
+    /// * `ide` - Name of the target IDE (e.g. "vscode", "cursor", "vscodium"), see [`IdeRegistry`](crate::domain::IdeRegistry)
+    /// * `profile` - Optional VS Code profile to install into (defaults to the default profile)
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to the IDE's gallery)
     ///
     /// # Installation Methods
     ///
@@ -164,6 +278,7 @@ impl ApplicationService {
     /// # Errors
     ///
     /// Returns a `DomainError` if:
+    /// - The IDE or profile is unknown
     /// - Extension ID format is invalid (must be "publisher.name")
     /// - Extension is not found in the marketplace
     /// - Download fails
@@ -178,131 +293,1532 @@ impl ApplicationService {
     /// let service = ApplicationService::new();
     ///
     /// // Install to VSCode
-    /// service.install_extension("rust-lang.rust-analyzer", false, None).await?;
+    /// service.install_extension("rust-lang.rust-analyzer", "vscode", None, None).await?;
     ///
     /// // Install to Cursor
-    /// service.install_extension("ms-python.python", true, None).await?;
+    /// service.install_extension("ms-python.python", "cursor", None, None).await?;
+    ///
+    /// // Install into the "Rust" profile of VSCode
+    /// service.install_extension("rust-lang.rust-analyzer", "vscode", Some("Rust"), None).await?;
     /// # Ok(())
     /// # }
     /// ```
     pub async fn install_extension(
         &self,
         extension_id: &str,
-        use_cursor: bool,
+        ide: &str,
+        profile: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<(), DomainError> {
+        self.install_extension_with_options(
+            extension_id,
+            ide,
+            profile,
+            marketplace_url,
+            InstallOptions::default(),
+        )
+        .await
+    }
+
+    /// Installs an extension like [`install_extension`](Self::install_extension), with
+    /// install options.
+    ///
+    /// `force` reinstalls an extension whose version is already installed, and
+    /// `pre_release` makes the IDE track pre-release versions of the extension (CLI
+    /// installs only).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` like [`install_extension`](Self::install_extension), and
+    /// in addition:
+    /// - `DomainError::AlreadyInstalled` if the version is already installed and
+    ///   `force` is not set
+    /// - `DomainError::Incompatible` if the IDE rejects the extension's engine range
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::InstallOptions;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    /// let options = InstallOptions { force: true, pre_release: false };
+    ///
+    /// service
+    ///     .install_extension_with_options("rust-lang.rust-analyzer", "vscode", None, None, options)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn install_extension_with_options(
+        &self,
+        extension_id: &str,
+        ide: &str,
+        profile: Option<&str>,
         marketplace_url: Option<&str>,
+        options: InstallOptions,
     ) -> Result<(), DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
         let use_case = ImprovedInstallExtensionUseCase::new(
             &self.marketplace_client,
             &self.installation_detector,
             &self.installation_executor,
-        );
+        )
+        .with_options(options);
         use_case
-            .execute(extension_id, use_cursor, marketplace_url)
+            .execute(
+                extension_id,
+                &ide_type,
+                profile,
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
             .await
     }
 
-    /// Downloads an extension as a VSIX file to the specified directory.
+    /// Resolves what [`install_extension_with_options`](Self::install_extension_with_options)
+    /// would do, without downloading or writing anything.
     ///
-    /// This method downloads the extension package without installing it, useful for:
-    /// - Offline installation
-    /// - Archiving extensions
-    /// - Manual distribution
+    /// The extension's metadata is looked up in the marketplace to resolve the
+    /// version, target platform and dependencies, and the installation method is
+    /// detected as for a real install.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The IDE or profile is unknown
+    /// - Extension ID format is invalid (must be "publisher.name")
+    /// - Extension is not found in the marketplace
+    /// - No installation method is available
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::InstallOptions;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let plan = service
+    ///     .plan_install("rust-lang.rust-analyzer", "vscode", None, None, InstallOptions::default())
+    ///     .await?;
+    /// println!("Would download {:?}", plan.download_url);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan_install(
+        &self,
+        extension_id: &str,
+        ide: &str,
+        profile: Option<&str>,
+        marketplace_url: Option<&str>,
+        options: InstallOptions,
+    ) -> Result<PlannedChange, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let use_case = ImprovedInstallExtensionUseCase::new(
+            &self.marketplace_client,
+            &self.installation_detector,
+            &self.installation_executor,
+        )
+        .with_options(options);
+        use_case
+            .plan(
+                extension_id,
+                &ide_type,
+                profile,
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
+            .await
+    }
+
+    /// Resolves what [`install_package`](Self::install_package) would do, without
+    /// installing anything.
+    ///
+    /// A local file is read to resolve its identity, version and dependencies. A
+    /// URL is not downloaded, so its plan only names the URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - `source` is a marketplace ID rather than a file or URL
+    /// - The IDE or profile is unknown
+    /// - The file cannot be read or has no valid manifest
+    /// - No installation method is available
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::{InstallOptions, PackageSource};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    /// let source = PackageSource::parse("./dist/acme-tools-2.1.0.vsix");
+    ///
+    /// let plan = service
+    ///     .plan_install_package(&source, "vscode", None, InstallOptions::default())
+    ///     .await?;
+    /// println!("Would write {:?}", plan.destination);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan_install_package(
+        &self,
+        source: &PackageSource,
+        ide: &str,
+        profile: Option<&str>,
+        options: InstallOptions,
+    ) -> Result<PlannedChange, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let (package, download_url) = match source {
+            PackageSource::Marketplace(id) => {
+                return Err(DomainError::InvalidExtensionFormat(format!(
+                    "{id} is not a VSIX file or URL"
+                )));
+            }
+            PackageSource::File(_) => {
+                let vsix_data = self.fetch_package(source, None).await?;
+                (Some(read_vsix_package(&vsix_data)?), None)
+            }
+            PackageSource::Url(url) => (None, Some(url.as_str())),
+        };
+
+        let use_case =
+            InstallPackageUseCase::new(&self.installation_detector, &self.installation_executor)
+                .with_options(options);
+        use_case
+            .plan(package.as_ref(), download_url, &ide_type, profile)
+            .await
+    }
+
+    /// Installs a VSIX package from a local file or a URL.
+    ///
+    /// The marketplace is not consulted: the extension ID and version are read from
+    /// the package's `extension/package.json`, and the package is installed with the
+    /// same method detection as [`install_extension`](Self::install_extension).
     ///
     /// # Arguments
     ///
-    /// * `extension_id` - The extension ID in format "publisher.name"
-    /// * `output_dir` - Directory path where the VSIX file will be saved
-    /// * `marketplace_url` - Optional custom marketplace URL
+    /// * `source` - The `.vsix` file or URL to install
+    /// * `ide` - Name of the target IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile to install into
+    /// * `options` - Install options (`force`, `pre_release`)
     ///
     /// # Returns
     ///
-    /// Returns the full path to the downloaded VSIX file.
+    /// Returns the metadata of the installed package.
     ///
     /// # Errors
     ///
     /// Returns a `DomainError` if:
-    /// - Extension is not found in the marketplace
-    /// - Download fails due to network issues
-    /// - Output directory cannot be created
-    /// - File cannot be written to disk
+    /// - `source` is a marketplace ID rather than a file or URL
+    /// - The IDE or profile is unknown
+    /// - The file cannot be read or the download fails
+    /// - The package has no valid manifest
+    /// - Installation fails
     ///
     /// # Examples
     ///
     /// ```no_run
     /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::{InstallOptions, PackageSource};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let service = ApplicationService::new();
+    /// let source = PackageSource::parse("./dist/acme-tools-2.1.0.vsix");
     ///
-    /// // Download extension to current directory
-    /// let path = service.download_extension("rust-lang.rust-analyzer", "./downloads", None).await?;
-    /// println!("Downloaded to: {}", path.display());
+    /// let package = service
+    ///     .install_package(&source, "vscode", None, InstallOptions::default())
+    ///     .await?;
+    /// println!("Installed {} {}", package.id(), package.version);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn download_extension(
+    pub async fn install_package(
         &self,
-        extension_id: &str,
-        output_dir: &str,
-        marketplace_url: Option<&str>,
-    ) -> Result<std::path::PathBuf, DomainError> {
-        use std::fs;
-        use std::path::Path;
+        source: &PackageSource,
+        ide: &str,
+        profile: Option<&str>,
+        options: InstallOptions,
+    ) -> Result<VsixPackage, DomainError> {
+        if let PackageSource::Marketplace(id) = source {
+            return Err(DomainError::InvalidExtensionFormat(format!(
+                "{id} is not a VSIX file or URL"
+            )));
+        }
 
-        let extension = self
-            .marketplace_client
-            .get_extension(extension_id, marketplace_url)
+        let ide_type = self.resolve_ide(ide)?;
+        let vsix_data = self.fetch_package(source, None).await?;
+        let package = read_vsix_package(&vsix_data)?;
+
+        let use_case =
+            InstallPackageUseCase::new(&self.installation_detector, &self.installation_executor)
+                .with_options(options);
+        use_case
+            .execute(&package.id(), &vsix_data, &ide_type, profile)
             .await?;
-        let vsix_data = self.marketplace_client.download(&extension, None).await?;
 
-        let output_path = Path::new(output_dir);
-        if !output_path.exists() {
-            fs::create_dir_all(output_path)?;
-        }
+        Ok(package)
+    }
 
-        let file_name = format!("{}-{}.vsix", extension_id, extension.version);
-        let file_path = output_path.join(file_name);
+    /// Reads a VSIX package without installing it.
+    ///
+    /// Lists the archive contents with their sizes, and the package metadata:
+    /// identity, engine range, activation events, contributions, bundled native
+    /// binaries and dependencies.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The `.vsix` file, URL or marketplace extension ID to inspect
+    /// * `marketplace_url` - Optional custom marketplace URL for extension IDs
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The file cannot be read, the extension is not found or the download fails
+    /// - The package has no valid manifest
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::PackageSource;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    /// let source = PackageSource::parse("ms-python.python");
+    ///
+    /// let inspection = service.inspect_package(&source, None).await?;
+    /// for binary in inspection.native_binaries() {
+    ///     println!("Native binary: {}", binary.path);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn inspect_package(
+        &self,
+        source: &PackageSource,
+        marketplace_url: Option<&str>,
+    ) -> Result<VsixInspection, DomainError> {
+        let vsix_data = self.fetch_package(source, marketplace_url).await?;
+        inspect_vsix(&vsix_data)
+    }
 
-        fs::write(&file_path, vsix_data)?;
+    /// Packages an extension folder into a VSIX file.
+    ///
+    /// Works like `vsce package` without needing Node: files matched by
+    /// `.vscodeignore` are left out, and `extension.vsixmanifest` and
+    /// `[Content_Types].xml` are generated from `package.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension_dir` - The folder containing the extension's `package.json`
+    /// * `target` - Optional target platform for a platform-specific package
+    /// * `output` - Optional output path (defaults to `<name>[-<target>]-<version>.vsix`
+    ///   inside `extension_dir`)
+    ///
+    /// # Returns
+    ///
+    /// Returns the metadata of the package and the path it was written to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - `package.json` is missing or lacks a publisher, name, version or engine
+    /// - `.vscodeignore` has an invalid pattern
+    /// - A file cannot be read or the package cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let (package, path) = service.pack_extension(Path::new("./my-extension"), None, None)?;
+    /// println!("Packaged {} to {}", package.id(), path.display());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pack_extension(
+        &self,
+        extension_dir: &Path,
+        target: Option<&Architecture>,
+        output: Option<&Path>,
+    ) -> Result<(VsixPackage, PathBuf), DomainError> {
+        let target = target.and_then(Architecture::to_platform_string);
+        let (package, vsix_data) = pack_extension(extension_dir, target)?;
 
-        Ok(file_path)
+        let output = output.map_or_else(
+            || {
+                let target = target
+                    .map(|target| format!("{target}-"))
+                    .unwrap_or_default();
+                extension_dir.join(format!("{}-{target}{}.vsix", package.name, package.version))
+            },
+            Path::to_path_buf,
+        );
+        std::fs::write(&output, vsix_data)?;
+
+        Ok((package, output))
     }
-}
 
-impl ExtensionRepository for &MarketplaceClient {
-    fn search<'life0, 'life1, 'life2, 'async_trait>(
-        &'life0 self,
-        query: &'life1 str,
-        marketplace_url: Option<&'life2 str>,
-    ) -> ::core::pin::Pin<
-        Box<
-            dyn ::core::future::Future<Output = Result<crate::domain::SearchResult, DomainError>>
-                + ::core::marker::Send
-                + 'async_trait,
-        >,
-    >
-    where
-        'life0: 'async_trait,
-        'life1: 'async_trait,
-        'life2: 'async_trait,
-        Self: 'async_trait,
-    {
-        (**self).search(query, marketplace_url)
+    /// Reads a VSIX package from a file, a URL or the marketplace
+    async fn fetch_package(
+        &self,
+        source: &PackageSource,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<u8>, DomainError> {
+        match source {
+            PackageSource::File(path) => std::fs::read(path).map_err(|e| {
+                DomainError::IoError(std::io::Error::new(
+                    e.kind(),
+                    format!("{}: {e}", path.display()),
+                ))
+            }),
+            PackageSource::Url(url) => self.marketplace_client.download_url(url).await,
+            PackageSource::Marketplace(id) => {
+                validate_extension_id(id)?;
+                let extension = self
+                    .marketplace_client
+                    .get_extension(id, marketplace_url)
+                    .await?;
+                self.marketplace_client.download(&extension, None).await
+            }
+        }
     }
 
-    fn get_extension<'life0, 'life1, 'life2, 'async_trait>(
-        &'life0 self,
-        id: &'life1 str,
-        marketplace_url: Option<&'life2 str>,
-    ) -> ::core::pin::Pin<
-        Box<
-            dyn ::core::future::Future<Output = Result<crate::domain::Extension, DomainError>>
-                + ::core::marker::Send
-                + 'async_trait,
-        >,
-    >
-    where
-        'life0: 'async_trait,
+    /// Uninstalls an extension using automatic method detection.
+    ///
+    /// Uses the IDE's CLI (e.g. `code --uninstall-extension`) when it is available,
+    /// and otherwise removes the extension folder and its `extensions.json` entry
+    /// directly. Uninstalling from a profile only removes the profile's entry, since
+    /// the extension folder is shared with the other profiles.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension_id` - The extension ID in format "publisher.name"
+    /// * `ide` - Name of the target IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile to uninstall from
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The IDE or profile is unknown
+    /// - Extension ID format is invalid (must be "publisher.name")
+    /// - Extension is not installed
+    /// - Removal fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// service.uninstall_extension("rust-lang.rust-analyzer", "vscode", None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn uninstall_extension(
+        &self,
+        extension_id: &str,
+        ide: &str,
+        profile: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let use_case = UninstallExtensionUseCase::new(
+            &self.installation_detector,
+            &self.installation_executor,
+        );
+        use_case.execute(extension_id, &ide_type, profile).await
+    }
+
+    /// Resolves what [`uninstall_extension`](Self::uninstall_extension) would remove,
+    /// without removing anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` like [`uninstall_extension`](Self::uninstall_extension),
+    /// including `DomainError::ExtensionNotFound` if the extension is not installed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let plan = service.plan_uninstall("rust-lang.rust-analyzer", "vscode", None).await?;
+    /// println!("Would remove {:?}", plan.destination);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan_uninstall(
+        &self,
+        extension_id: &str,
+        ide: &str,
+        profile: Option<&str>,
+    ) -> Result<PlannedChange, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let use_case = UninstallExtensionUseCase::new(
+            &self.installation_detector,
+            &self.installation_executor,
+        );
+        use_case.plan(extension_id, &ide_type, profile).await
+    }
+
+    /// Lists the extensions installed in an IDE.
+    ///
+    /// Reads the IDE's extensions directory (including `extensions.json`) when it
+    /// exists, and falls back to `<cli> --list-extensions --show-versions` otherwise.
+    /// Extensions disabled in the editor's global state are marked as disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `ide` - Name of the IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile; only its extensions are listed
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the IDE or profile is unknown or the extensions
+    /// directory, CLI output or editor state cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// for extension in service.list_extensions("vscode", None).await? {
+    ///     println!("{}@{}", extension.id, extension.version);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_extensions(
+        &self,
+        ide: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<InstalledExtension>, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let use_case = ListInstalledExtensionsUseCase::new(
+            &self.installation_detector,
+            &self.installation_executor,
+            &self.global_state,
+        );
+        use_case.execute(&ide_type, profile).await
+    }
+
+    /// Finds extension folders of an IDE that can be removed to reclaim space.
+    ///
+    /// Reports folders the editor marked for deletion in its `.obsolete` file,
+    /// older versions left behind by updates, folders that neither the editor's
+    /// `extensions.json` nor one of its profiles references, and broken installs
+    /// (folders without a `package.json`, or `extensions.json` entries whose
+    /// folder is gone). Nothing is removed; use
+    /// [`remove_stale_extensions`](Self::remove_stale_extensions) for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `ide` - Name of the IDE (e.g. "vscode", "cursor")
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the IDE is unknown, or its extensions directory,
+    /// registries or profiles cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let stale = service.find_stale_extensions("vscode")?;
+    /// let reclaimed = service.remove_stale_extensions("vscode", &stale)?;
+    /// println!("Freed {reclaimed} bytes");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_stale_extensions(&self, ide: &str) -> Result<Vec<StaleExtension>, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let extensions_dir = self.installation_detector.extensions_directory(&ide_type)?;
+        let profile_registries = self.installation_detector.profile_registries(&ide_type)?;
+        find_stale_extensions(&extensions_dir, &profile_registries)
+    }
+
+    /// Removes extension folders found by
+    /// [`find_stale_extensions`](Self::find_stale_extensions).
+    ///
+    /// `extensions.json` entries of the removed folders are dropped, and removed
+    /// obsolete folders are taken off the `.obsolete` file.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of bytes freed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the IDE is unknown, a folder lies outside its
+    /// extensions directory, or a folder or registry cannot be written
+    pub fn remove_stale_extensions(
+        &self,
+        ide: &str,
+        stale: &[StaleExtension],
+    ) -> Result<u64, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let extensions_dir = self.installation_detector.extensions_directory(&ide_type)?;
+        remove_stale_extensions(&extensions_dir, stale)
+    }
+
+    /// Diagnoses the environment vsix runs in and the editors it knows.
+    ///
+    /// For each editor, reports whether its CLI is in PATH and its version, how
+    /// extensions would be installed, the extensions directory with where it comes
+    /// from (`--extensions-dir`, `VSCODE_EXTENSIONS`, a portable install, the remote
+    /// server or the default location) and whether it is writable, the gallery and
+    /// whether it answers, and the folders [`find_stale_extensions`] reports. The
+    /// gallery is only queried for editors found on this machine, or the one
+    /// selected with `ide`. Also reports the detected target platform and C
+    /// library, proxy environment variables and the configuration file.
+    ///
+    /// [`find_stale_extensions`]: Self::find_stale_extensions
+    ///
+    /// # Arguments
+    ///
+    /// * `ide` - Only diagnose this editor
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to each
+    ///   editor's gallery)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the configuration file cannot be read or `ide`
+    /// is unknown. Problems with an editor are reported in its diagnosis instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let diagnosis = service.diagnose(Some("vscode"), None).await?;
+    /// for editor in &diagnosis.editors {
+    ///     for warning in editor.warnings() {
+    ///         println!("{}: {warning}", editor.display_name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn diagnose(
+        &self,
+        ide: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<EnvironmentDiagnosis, DomainError> {
+        let registry = VsixConfig::load()?.into_ide_registry();
+        let editors = match ide {
+            Some(ide) => vec![registry.resolve(ide)?.clone()],
+            None => registry.all().to_vec(),
+        };
+
+        let mut gallery_checks: Vec<(String, Result<u64, String>)> = Vec::new();
+        let mut diagnoses = Vec::new();
+        for ide_type in &editors {
+            let mut diagnosis = self.diagnose_editor(ide_type).await;
+            let gallery = marketplace_url.or(ide_type.gallery_url.as_deref());
+            diagnosis.gallery_url = gallery_service_url(gallery);
+
+            if ide.is_some() || diagnosis.is_present() {
+                let checked = gallery_checks
+                    .iter()
+                    .find(|(url, _)| *url == diagnosis.gallery_url)
+                    .map(|(_, result)| result.clone());
+                diagnosis.gallery_latency = match checked {
+                    Some(result) => result,
+                    None => {
+                        let result = tokio::time::timeout(
+                            DIAGNOSTIC_TIMEOUT,
+                            self.marketplace_client.check_gallery(gallery),
+                        )
+                        .await
+                        .map_err(|_| {
+                            format!("no answer within {} seconds", DIAGNOSTIC_TIMEOUT.as_secs())
+                        })
+                        .and_then(|result| result.map_err(|e| e.to_string()))
+                        .map(|elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+                        gallery_checks.push((diagnosis.gallery_url.clone(), result.clone()));
+                        result
+                    }
+                };
+            }
+            diagnoses.push(diagnosis);
+        }
+
+        let config_path = config_path();
+        Ok(EnvironmentDiagnosis {
+            platform: Architecture::detect()
+                .to_platform_string()
+                .map(ToString::to_string),
+            libc: detect_libc(),
+            proxies: proxy_variables(),
+            config_exists: config_path.as_ref().is_some_and(|path| path.is_file()),
+            config_path,
+            editors: diagnoses,
+        })
+    }
+
+    /// Collects everything [`diagnose`](Self::diagnose) reports about an editor,
+    /// except the gallery check
+    async fn diagnose_editor(&self, ide_type: &IdeType) -> EditorDiagnosis {
+        let cli_path = self.installation_detector.find_cli(ide_type);
+        let cli_version = match &cli_path {
+            Some(path) => tokio::time::timeout(
+                DIAGNOSTIC_TIMEOUT,
+                self.installation_executor.cli_version(path),
+            )
+            .await
+            .map_err(|_| format!("no answer within {} seconds", DIAGNOSTIC_TIMEOUT.as_secs()))
+            .and_then(|result| result.map_err(|e| e.to_string())),
+            None => Err("not in PATH".to_string()),
+        };
+
+        let install_method = self
+            .installation_detector
+            .detect_method(ide_type)
+            .await
+            .map(|method| match method {
+                InstallationMethod::CliCommand { command_path, .. } => {
+                    format!("CLI ({})", command_path.display())
+                }
+                InstallationMethod::FileSystem { .. } => "extensions directory".to_string(),
+                InstallationMethod::RemoteServer { .. } => "remote server".to_string(),
+            })
+            .map_err(|e| e.to_string());
+
+        let located = self
+            .installation_detector
+            .locate_extensions_directory(ide_type);
+        let (extensions_dir_access, installed_count, stale) = match &located {
+            Ok((dir, _)) => {
+                let profile_registries = self
+                    .installation_detector
+                    .profile_registries(ide_type)
+                    .unwrap_or_default();
+                (
+                    directory_access(dir),
+                    scan_extensions_directory(dir).map_or(0, |installed| installed.len()),
+                    find_stale_extensions(dir, &profile_registries).unwrap_or_default(),
+                )
+            }
+            Err(_) => (DirectoryAccess::Missing, 0, Vec::new()),
+        };
+
+        EditorDiagnosis {
+            name: ide_type.name.clone(),
+            display_name: ide_type.display_name.clone(),
+            cli_path,
+            cli_version,
+            install_method,
+            extensions_dir_source: located.as_ref().ok().map(|(_, source)| *source),
+            extensions_dir: located.map(|(dir, _)| dir).map_err(|e| e.to_string()),
+            extensions_dir_access,
+            gallery_url: String::new(),
+            gallery_latency: Err("not checked".to_string()),
+            installed_count,
+            stale,
+        }
+    }
+
+    /// Enables or disables an installed extension without uninstalling it.
+    ///
+    /// The editor records disabled extensions under `extensionsIdentifiers/disabled`
+    /// in its global state database (`User/globalStorage/state.vscdb`, or the
+    /// profile's own `globalStorage` folder). The editor rewrites that database when
+    /// it exits, so the change is refused while it is running unless `force` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `extension_id` - The extension ID in format "publisher.name"
+    /// * `ide` - Name of the IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile to change the state in
+    /// * `enabled` - Whether the extension should be enabled
+    /// * `force` - Write even while the editor is running
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the extension already was in the requested state.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The IDE or profile is unknown
+    /// - Extension ID format is invalid (must be "publisher.name")
+    /// - Extension is not installed
+    /// - `DomainError::Locked` if the editor is running or holds its state database
+    /// - The state database cannot be read or written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// service
+    ///     .set_extension_enabled("github.copilot", "vscode", None, false, false)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn set_extension_enabled(
+        &self,
+        extension_id: &str,
+        ide: &str,
+        profile: Option<&str>,
+        enabled: bool,
+        force: bool,
+    ) -> Result<bool, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let use_case = SetExtensionStateUseCase::new(
+            &self.installation_detector,
+            &self.installation_executor,
+            &self.global_state,
+        );
+        use_case
+            .execute(extension_id, &ide_type, profile, enabled, force)
+            .await
+    }
+
+    /// Finds installed extensions for which the marketplace has a newer version.
+    ///
+    /// All installed extensions are looked up in a single batched marketplace query.
+    /// Versions that need a newer editor than the installed one, going by the
+    /// VS Code version in its `product.json`, are not offered. Extensions that are
+    /// not published to the marketplace are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `ide` - Name of the IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile to check instead of the default one
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to the IDE's gallery)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the IDE is unknown, the installed extensions cannot
+    /// be read or the marketplace query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # use vsix::domain::ManifestChange;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// for outdated in service.outdated_extensions("cursor", None, None).await? {
+    ///     let update = ManifestChange::update("cursor", &outdated, None);
+    ///     service.apply_manifest_change(&update, None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn outdated_extensions(
+        &self,
+        ide: &str,
+        profile: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<OutdatedExtension>, DomainError> {
+        let ide_type = self.resolve_ide(ide)?;
+        let installed = self.list_extensions(ide, profile).await?;
+        let engine = self.installation_detector.engine_version(&ide_type);
+        let use_case = OutdatedExtensionsUseCase::new(&self.marketplace_client);
+        use_case
+            .execute(
+                &installed,
+                engine.as_deref(),
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
+            .await
+    }
+
+    /// Returns the extensions a workspace recommends that are not installed yet.
+    ///
+    /// Recommendations are read from the workspace's `.vscode/extensions.json` and
+    /// the `extensions` section of its `*.code-workspace` files (JSON with
+    /// comments). Entries listed in `unwantedRecommendations` are skipped, and so
+    /// are extensions that are already installed in the editor or profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace folder, or a single recommendations file
+    /// * `ide` - Name of the IDE (e.g. "vscode", "cursor")
+    /// * `profile` - Optional VS Code profile to check
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the IDE or profile is unknown, no
+    /// recommendations file is found, a file cannot be parsed or the installed
+    /// extensions cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// for id in service
+    ///     .missing_workspace_recommendations(Path::new("."), "vscode", None)
+    ///     .await?
+    /// {
+    ///     service.install_extension(&id, "vscode", None, None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn missing_workspace_recommendations(
+        &self,
+        workspace: &Path,
+        ide: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, DomainError> {
+        let recommendations = read_workspace_recommendations(workspace)?;
+        let installed = self.list_extensions(ide, profile).await?;
+        Ok(recommendations.missing(&installed))
+    }
+
+    /// Returns the extensions a dev container configuration lists that are not
+    /// installed yet.
+    ///
+    /// Reads `customizations.vscode.extensions` of a `devcontainer.json` (JSON
+    /// with comments); entries excluded with `-publisher.name` are skipped.
+    /// Combine with [`with_server_target`](Self::with_server_target) to compare
+    /// against, and install into, the editor's server extensions directory when
+    /// provisioning a container image.
+    ///
+    /// # Arguments
+    ///
+    /// * `devcontainer` - A `devcontainer.json` file, or a folder containing
+    ///   `.devcontainer/devcontainer.json` or `.devcontainer.json`
+    /// * `ide` - Name of the IDE to compare against
+    /// * `profile` - Optional VS Code profile to compare against
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if no `devcontainer.json` is found, it cannot be
+    /// parsed, or the installed extensions cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new().with_server_target(true);
+    ///
+    /// for id in service
+    ///     .missing_devcontainer_extensions(Path::new(".devcontainer/devcontainer.json"), "vscode", None)
+    ///     .await?
+    /// {
+    ///     service.install_extension(&id, "vscode", None, None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn missing_devcontainer_extensions(
+        &self,
+        devcontainer: &Path,
+        ide: &str,
+        profile: Option<&str>,
+    ) -> Result<Vec<String>, DomainError> {
+        let extensions = read_devcontainer_extensions(devcontainer)?;
+        let installed = self.list_extensions(ide, profile).await?;
+        Ok(extensions.missing(&installed))
+    }
+
+    /// Compares the extensions installed in two editors.
+    ///
+    /// Extensions installed in `from` but not in `to` are looked up in the target
+    /// editor's gallery (e.g. Open VSX for VSCodium) in a single batched query, so
+    /// the result tells which of them can be installed there and which cannot.
+    /// Extensions installed only in `to` are reported as extras. Nothing is
+    /// installed or removed; use [`install_extension`](Self::install_extension) and
+    /// [`uninstall_extension`](Self::uninstall_extension) to apply the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Name of the editor to copy the extension set from
+    /// * `to` - Name of the editor to bring in line with `from`
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to the gallery
+    ///   of `to`)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if either editor is unknown, its installed
+    /// extensions cannot be read or the marketplace query fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let sync = service.diff_extensions("vscode", "cursor", None).await?;
+    /// for extension in &sync.to_install {
+    ///     service
+    ///         .install_extension(&extension.unique_identifier(), "cursor", None, None)
+    ///         .await?;
+    /// }
+    /// for id in &sync.unavailable {
+    ///     println!("{id} is not available for Cursor");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn diff_extensions(
+        &self,
+        from: &str,
+        to: &str,
+        marketplace_url: Option<&str>,
+    ) -> Result<ExtensionSync, DomainError> {
+        let target = self.resolve_ide(to)?;
+        let source_extensions = self.list_extensions(from, None).await?;
+        let target_extensions = self.list_extensions(to, None).await?;
+
+        let use_case = SyncExtensionsUseCase::new(&self.marketplace_client);
+        use_case
+            .execute(
+                &source_extensions,
+                &target_extensions,
+                marketplace_url.or(target.gallery_url.as_deref()),
+            )
+            .await
+    }
+
+    /// Works out the changes that converge the installed extensions to a
+    /// `vsix.toml` manifest.
+    ///
+    /// Each editor listed in the manifest is compared with its installed
+    /// extensions, in the editor's default profile. Listed extensions are resolved
+    /// in the editor's gallery with their version, channel and platform
+    /// constraints, and become installs, upgrades or downgrades where the installed
+    /// version differs. Unlisted extensions are removed if `prune` is set or the
+    /// editor's section sets `prune = true`. Nothing is installed or removed; use
+    /// [`apply_manifest_change`](Self::apply_manifest_change) for that.
+    ///
+    /// If a `vsix.lock` exists next to the manifest, the locked versions are used
+    /// instead of marketplace lookups and their packages are installed from the
+    /// locked URLs, verified against the locked SHA-256.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest` - Path of the `vsix.toml` file
+    /// * `ide` - Only apply the section of this editor
+    /// * `prune` - Remove unlisted extensions from every editor
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to each
+    ///   editor's gallery)
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The manifest or its lockfile cannot be read or parsed
+    /// - The lockfile has no entry for a listed extension, or the entry no longer
+    ///   matches the manifest (`DomainError::StaleLock`)
+    /// - `ide` is given but the manifest has no section for it
+    /// - An editor is unknown or its installed extensions cannot be read
+    /// - A listed extension or pinned version is not found in the marketplace
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let changes = service
+    ///     .plan_manifest(Path::new("vsix.toml"), None, false, None)
+    ///     .await?;
+    /// for change in &changes {
+    ///     service.apply_manifest_change(change, None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan_manifest(
+        &self,
+        manifest: &Path,
+        ide: Option<&str>,
+        prune: bool,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<ManifestChange>, DomainError> {
+        let manifest_path = manifest;
+        let manifest = read_extension_manifest(manifest_path)?;
+        let editors: Vec<_> = manifest
+            .editors
+            .iter()
+            .filter(|editor| ide.is_none_or(|ide| editor.editor.eq_ignore_ascii_case(ide)))
+            .collect();
+        if let Some(ide) = ide
+            && editors.is_empty()
+        {
+            return Err(DomainError::ParseError(format!(
+                "{} has no [editors.{ide}] section",
+                manifest_path.display()
+            )));
+        }
+
+        let lock_path = lock_file_path(manifest_path);
+        let lock = if lock_path.is_file() {
+            Some(read_extension_lock(&lock_path)?)
+        } else {
+            None
+        };
+
+        let use_case = ApplyManifestUseCase::new(&self.marketplace_client);
+        let mut changes = Vec::new();
+        for editor in editors {
+            let ide_type = self.resolve_ide(&editor.editor)?;
+            let installed = self.list_extensions(&editor.editor, None).await?;
+            changes.extend(
+                use_case
+                    .execute(
+                        editor,
+                        &installed,
+                        prune || editor.prune,
+                        lock.as_ref(),
+                        marketplace_url.or(ide_type.gallery_url.as_deref()),
+                    )
+                    .await?,
+            );
+        }
+        Ok(changes)
+    }
+
+    /// Pins the extensions of a `vsix.toml` manifest in a `vsix.lock` next to it.
+    ///
+    /// Every listed extension is resolved in its editor's gallery with its
+    /// constraints, and its package is downloaded to record the exact version,
+    /// target platform, download URL and SHA-256. The lockfile is rewritten from
+    /// scratch; [`plan_manifest`](Self::plan_manifest) uses it from then on.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest` - Path of the `vsix.toml` file
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to each
+    ///   editor's gallery)
+    ///
+    /// # Returns
+    ///
+    /// Returns the path of the lockfile and its contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The manifest cannot be read or parsed, or names an unknown editor
+    /// - A listed extension or pinned version is not found in the marketplace
+    /// - A package cannot be downloaded
+    /// - The lockfile cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let (path, lock) = service.lock_manifest(Path::new("vsix.toml"), None).await?;
+    /// println!("Locked {} extensions in {}", lock.extensions.len(), path.display());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn lock_manifest(
+        &self,
+        manifest: &Path,
+        marketplace_url: Option<&str>,
+    ) -> Result<(PathBuf, ExtensionLock), DomainError> {
+        let editors = read_extension_manifest(manifest)?.editors;
+
+        let use_case = LockManifestUseCase::new(&self.marketplace_client);
+        let mut lock = ExtensionLock::default();
+        for editor in &editors {
+            let ide_type = self.resolve_ide(&editor.editor)?;
+            lock.extensions.extend(
+                use_case
+                    .execute(editor, marketplace_url.or(ide_type.gallery_url.as_deref()))
+                    .await?,
+            );
+        }
+
+        let path = lock_file_path(manifest);
+        write_extension_lock(&path, &lock)?;
+        Ok((path, lock))
+    }
+
+    /// Resolves what [`apply_manifest_change`](Self::apply_manifest_change) would
+    /// do, without downloading or writing anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` like [`plan_install`](Self::plan_install) or
+    /// [`plan_uninstall`](Self::plan_uninstall)
+    pub async fn plan_manifest_change(
+        &self,
+        change: &ManifestChange,
+        marketplace_url: Option<&str>,
+    ) -> Result<PlannedChange, DomainError> {
+        if change.action == PlannedAction::Uninstall {
+            return self
+                .plan_uninstall(
+                    &change.extension_id,
+                    &change.editor,
+                    change.profile.as_deref(),
+                )
+                .await;
+        }
+
+        let ide_type = self.resolve_ide(&change.editor)?;
+        let use_case = ImprovedInstallExtensionUseCase::new(
+            &self.marketplace_client,
+            &self.installation_detector,
+            &self.installation_executor,
+        )
+        .with_options(manifest_install_options(change))
+        .with_selector(change.selector.clone())
+        .with_locked(change.locked.clone());
+        let plan = use_case
+            .plan(
+                &change.extension_id,
+                &ide_type,
+                change.profile.as_deref(),
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
+            .await?;
+
+        Ok(PlannedChange {
+            action: change.action,
+            installed_version: change.installed_version.clone(),
+            ..plan
+        })
+    }
+
+    /// Applies one change found by [`plan_manifest`](Self::plan_manifest).
+    ///
+    /// Installs, upgrades and downgrades go through the regular install use case
+    /// with the change's version selector; upgrades and downgrades replace the
+    /// installed version. Locked changes are downloaded from the locked URL and
+    /// rejected with `DomainError::ChecksumMismatch` if the package's SHA-256
+    /// differs from the lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` like
+    /// [`install_extension_with_options`](Self::install_extension_with_options) or
+    /// [`uninstall_extension`](Self::uninstall_extension)
+    pub async fn apply_manifest_change(
+        &self,
+        change: &ManifestChange,
+        marketplace_url: Option<&str>,
+    ) -> Result<(), DomainError> {
+        if change.action == PlannedAction::Uninstall {
+            return self
+                .uninstall_extension(
+                    &change.extension_id,
+                    &change.editor,
+                    change.profile.as_deref(),
+                )
+                .await;
+        }
+
+        let ide_type = self.resolve_ide(&change.editor)?;
+        let use_case = ImprovedInstallExtensionUseCase::new(
+            &self.marketplace_client,
+            &self.installation_detector,
+            &self.installation_executor,
+        )
+        .with_options(manifest_install_options(change))
+        .with_selector(change.selector.clone())
+        .with_locked(change.locked.clone());
+        use_case
+            .execute(
+                &change.extension_id,
+                &ide_type,
+                change.profile.as_deref(),
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
+            .await
+    }
+
+    /// Exports the extensions of an editor to a VS Code `.code-profile` file.
+    ///
+    /// The file contains an `extensions` resource in the format the editor's
+    /// *Export Profile* command writes, with each extension's ID, disabled flag and
+    /// pre-release preference. It can be imported with
+    /// [`plan_profile_import`](Self::plan_profile_import) or the editor's *Import
+    /// Profile* command.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Path of the `.code-profile` file to write
+    /// * `ide` - The editor to export from
+    /// * `profile` - Optional VS Code profile to export instead of the default one
+    /// * `name` - Profile name recorded in the file
+    /// * `include_versions` - Record installed versions so imports pin them
+    ///
+    /// # Returns
+    ///
+    /// Returns the exported profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if the editor is unknown, its installed extensions
+    /// cannot be read or the file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let profile = service
+    ///     .export_profile(Path::new("rust.code-profile"), "vscode", None, "Rust", true)
+    ///     .await?;
+    /// println!("Exported {} extensions", profile.extensions.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn export_profile(
+        &self,
+        file: &Path,
+        ide: &str,
+        profile: Option<&str>,
+        name: &str,
+        include_versions: bool,
+    ) -> Result<CodeProfile, DomainError> {
+        let installed = self.list_extensions(ide, profile).await?;
+        let code_profile = CodeProfile::from_installed(name, &installed, include_versions);
+        write_code_profile(file, &code_profile)?;
+        Ok(code_profile)
+    }
+
+    /// Compares the extensions of a VS Code `.code-profile` file with an editor.
+    ///
+    /// Listed extensions that are missing become installs, and extensions
+    /// installed in another version than the profile records become upgrades or
+    /// downgrades. Entries without a version install the latest release, or the
+    /// latest pre-release if they have `preRelease` set, and leave installed
+    /// versions alone. Installed extensions the profile does not list are kept. Apply the changes with
+    /// [`apply_manifest_change`](Self::apply_manifest_change); extensions the
+    /// profile marks as disabled can then be disabled with
+    /// [`set_extension_enabled`](Self::set_extension_enabled).
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - Path of the `.code-profile` file
+    /// * `ide` - The editor to import into
+    /// * `profile` - Optional VS Code profile to import into instead of the
+    ///   default one
+    /// * `marketplace_url` - Optional custom marketplace URL (defaults to the
+    ///   editor's gallery)
+    ///
+    /// # Returns
+    ///
+    /// Returns the profile read from the file and the changes importing it makes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - The file cannot be read or parsed
+    /// - The editor is unknown or its installed extensions cannot be read
+    /// - A listed extension or pinned version is not found in the marketplace
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::path::Path;
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// let (_, changes) = service
+    ///     .plan_profile_import(Path::new("rust.code-profile"), "vscode", None, None)
+    ///     .await?;
+    /// for change in &changes {
+    ///     service.apply_manifest_change(change, None).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan_profile_import(
+        &self,
+        file: &Path,
+        ide: &str,
+        profile: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<(CodeProfile, Vec<ManifestChange>), DomainError> {
+        let code_profile = read_code_profile(file)?;
+        let ide_type = self.resolve_ide(ide)?;
+        let installed = self.list_extensions(ide, profile).await?;
+
+        let use_case = ApplyManifestUseCase::new(&self.marketplace_client);
+        let mut changes = use_case
+            .execute(
+                &code_profile.to_manifest(&ide_type.name),
+                &installed,
+                false,
+                None,
+                marketplace_url.or(ide_type.gallery_url.as_deref()),
+            )
+            .await?;
+        changes.retain(|change| {
+            change.action == PlannedAction::Install
+                || code_profile.extensions.iter().any(|ext| {
+                    ext.id.eq_ignore_ascii_case(&change.extension_id) && ext.version.is_some()
+                })
+        });
+        for change in &mut changes {
+            change.profile = profile.map(str::to_string);
+        }
+        Ok((code_profile, changes))
+    }
+
+    /// Downloads an extension as a VSIX file to the specified directory.
+    ///
+    /// This method downloads the extension package without installing it, useful for:
+    /// - Offline installation
+    /// - Archiving extensions
+    /// - Manual distribution
+    ///
+    /// # Arguments
+    ///
+    /// * `extension_id` - The extension ID in format "publisher.name"
+    /// * `output_dir` - Directory path where the VSIX file will be saved
+    /// * `marketplace_url` - Optional custom marketplace URL
+    ///
+    /// # Returns
+    ///
+    /// Returns the full path to the downloaded VSIX file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `DomainError` if:
+    /// - Extension is not found in the marketplace
+    /// - Download fails due to network issues
+    /// - Output directory cannot be created
+    /// - File cannot be written to disk
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use vsix::application::ApplicationService;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let service = ApplicationService::new();
+    ///
+    /// // Download extension to current directory
+    /// let path = service.download_extension("rust-lang.rust-analyzer", "./downloads", None).await?;
+    /// println!("Downloaded to: {}", path.display());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_extension(
+        &self,
+        extension_id: &str,
+        output_dir: &str,
+        marketplace_url: Option<&str>,
+    ) -> Result<std::path::PathBuf, DomainError> {
+        use std::fs;
+        use std::path::Path;
+
+        let extension = self
+            .marketplace_client
+            .get_extension(extension_id, marketplace_url)
+            .await?;
+        let vsix_data = self.marketplace_client.download(&extension, None).await?;
+
+        let output_path = Path::new(output_dir);
+        if !output_path.exists() {
+            fs::create_dir_all(output_path)?;
+        }
+
+        let file_name = format!("{}-{}.vsix", extension_id, extension.version);
+        let file_path = output_path.join(file_name);
+
+        fs::write(&file_path, vsix_data)?;
+
+        Ok(file_path)
+    }
+}
+
+/// Install options for a manifest change: changes of the installed version
+/// replace it, and the pre-release channel is tracked as with `--pre-release`
+fn manifest_install_options(change: &ManifestChange) -> InstallOptions {
+    InstallOptions {
+        force: matches!(
+            change.action,
+            PlannedAction::Update | PlannedAction::Downgrade
+        ),
+        pre_release: change.selector.channel == ReleaseChannel::PreRelease,
+    }
+}
+
+impl ExtensionRepository for &MarketplaceClient {
+    fn search<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        query: &'life1 str,
+        marketplace_url: Option<&'life2 str>,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<crate::domain::SearchResult, DomainError>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        Self: 'async_trait,
+    {
+        (**self).search(query, marketplace_url)
+    }
+
+    fn get_extension<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        id: &'life1 str,
+        marketplace_url: Option<&'life2 str>,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<crate::domain::Extension, DomainError>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
         'life1: 'async_trait,
         'life2: 'async_trait,
         Self: 'async_trait,
@@ -310,6 +1826,50 @@ impl ExtensionRepository for &MarketplaceClient {
         (**self).get_extension(id, marketplace_url)
     }
 
+    fn get_extensions<'life0, 'life1, 'life2, 'life3, 'async_trait>(
+        &'life0 self,
+        ids: &'life1 [String],
+        engine: Option<&'life2 str>,
+        marketplace_url: Option<&'life3 str>,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<Vec<crate::domain::Extension>, DomainError>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        Self: 'async_trait,
+    {
+        (**self).get_extensions(ids, engine, marketplace_url)
+    }
+
+    fn resolve_extension<'life0, 'life1, 'life2, 'life3, 'async_trait>(
+        &'life0 self,
+        id: &'life1 str,
+        selector: &'life2 crate::domain::VersionSelector,
+        marketplace_url: Option<&'life3 str>,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<crate::domain::Extension, DomainError>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        Self: 'async_trait,
+    {
+        (**self).resolve_extension(id, selector, marketplace_url)
+    }
+
     fn download<'life0, 'life1, 'life2, 'async_trait>(
         &'life0 self,
         extension: &'life1 crate::domain::Extension,
@@ -0,0 +1,212 @@
+use crate::application::install_use_case::validate_extension_id;
+use crate::domain::{
+    DomainError, ExtensionStateRepository, IdeType, InstallationDetector, InstallationExecutor,
+    InstallationMethod, InstallationStrategy,
+};
+
+/// Use case for enabling and disabling installed extensions
+pub struct SetExtensionStateUseCase<D, X, S>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+    S: ExtensionStateRepository,
+{
+    detector: D,
+    executor: X,
+    state: S,
+}
+
+impl<D, X, S> SetExtensionStateUseCase<D, X, S>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+    S: ExtensionStateRepository,
+{
+    pub fn new(detector: D, executor: X, state: S) -> Self {
+        Self {
+            detector,
+            executor,
+            state,
+        }
+    }
+
+    /// Enables or disables an installed extension
+    ///
+    /// Returns `false` if the extension already was in the requested state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Extension ID format is invalid
+    /// - The profile does not exist
+    /// - Extension is not installed
+    /// - The editor is running and `force` is not set
+    /// - The editor state cannot be written
+    pub async fn execute(
+        &self,
+        extension_id: &str,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+        enabled: bool,
+        force: bool,
+    ) -> Result<bool, DomainError> {
+        validate_extension_id(extension_id)?;
+
+        let extensions_dir = self.detector.extensions_directory(ide_type)?;
+        let method = if extensions_dir.is_dir() {
+            InstallationMethod::FileSystem { extensions_dir }
+        } else {
+            self.detector.detect_method(ide_type).await?
+        };
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+
+        let strategy =
+            InstallationStrategy::new(ide_type.clone(), method).with_profile(profile.clone());
+        let installed = self
+            .executor
+            .list_installed(&strategy)
+            .await?
+            .into_iter()
+            .find(|extension| extension.id.eq_ignore_ascii_case(extension_id))
+            .ok_or_else(|| DomainError::ExtensionNotFound(extension_id.to_string()))?;
+
+        self.state
+            .set_disabled(ide_type, profile.as_ref(), &installed.id, !enabled, force)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{InstalledExtension, ProfileTarget};
+    use async_trait::async_trait;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    struct MockDetector;
+
+    #[async_trait]
+    impl InstallationDetector for MockDetector {
+        async fn detect_method(
+            &self,
+            _ide_type: &IdeType,
+        ) -> Result<InstallationMethod, DomainError> {
+            Ok(InstallationMethod::FileSystem {
+                extensions_dir: PathBuf::from("/home/user/.vscode/extensions"),
+            })
+        }
+
+        fn extensions_directory(&self, _ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+            Ok(PathBuf::from("/nonexistent/extensions"))
+        }
+
+        fn resolve_profile(
+            &self,
+            _ide_type: &IdeType,
+            _name: &str,
+        ) -> Result<Option<ProfileTarget>, DomainError> {
+            Ok(None)
+        }
+    }
+
+    struct MockExecutor;
+
+    #[async_trait]
+    impl InstallationExecutor for MockExecutor {
+        async fn execute(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+            _vsix_data: &[u8],
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn uninstall(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_installed(
+            &self,
+            _strategy: &InstallationStrategy,
+        ) -> Result<Vec<InstalledExtension>, DomainError> {
+            Ok(vec![InstalledExtension {
+                id: "publisher.extension".to_string(),
+                version: "1.0.0".to_string(),
+                target_platform: None,
+                size: None,
+                installed_at: None,
+                location: None,
+                disabled: false,
+                pre_release: false,
+            }])
+        }
+    }
+
+    #[derive(Default)]
+    struct MockState {
+        changes: Mutex<Vec<(String, bool, bool)>>,
+    }
+
+    impl ExtensionStateRepository for MockState {
+        fn disabled_extensions(
+            &self,
+            _ide_type: &IdeType,
+            _profile: Option<&ProfileTarget>,
+        ) -> Result<Vec<String>, DomainError> {
+            unimplemented!()
+        }
+
+        fn set_disabled(
+            &self,
+            _ide_type: &IdeType,
+            _profile: Option<&ProfileTarget>,
+            extension_id: &str,
+            disabled: bool,
+            force: bool,
+        ) -> Result<bool, DomainError> {
+            self.changes
+                .lock()
+                .unwrap()
+                .push((extension_id.to_string(), disabled, force));
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disable_installed_extension() {
+        let use_case =
+            SetExtensionStateUseCase::new(MockDetector, MockExecutor, MockState::default());
+
+        let changed = use_case
+            .execute("Publisher.Extension", &IdeType::vscode(), None, false, true)
+            .await
+            .unwrap();
+        assert!(changed);
+
+        let changes = use_case.state.changes.lock().unwrap();
+        assert_eq!(
+            *changes,
+            vec![("publisher.extension".to_string(), true, true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enable_requires_installed_extension() {
+        let use_case =
+            SetExtensionStateUseCase::new(MockDetector, MockExecutor, MockState::default());
+
+        let result = use_case
+            .execute("publisher.missing", &IdeType::vscode(), None, true, false)
+            .await;
+        assert!(matches!(result, Err(DomainError::ExtensionNotFound(_))));
+        assert!(use_case.state.changes.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::domain::{DomainError, ExtensionRepository, ExtensionSync, InstalledExtension};
+use std::collections::{HashMap, HashSet};
+
+/// Use case for comparing the extensions of two editors
+pub struct SyncExtensionsUseCase<E: ExtensionRepository> {
+    extension_repo: E,
+}
+
+impl<E: ExtensionRepository> SyncExtensionsUseCase<E> {
+    pub fn new(extension_repo: E) -> Self {
+        Self { extension_repo }
+    }
+
+    /// Diffs the installed extensions of a source and a target editor
+    ///
+    /// Extensions missing from the target are looked up in the target's
+    /// marketplace in a single query; the ones it does not have are reported as
+    /// unavailable. IDs are compared case-insensitively and side-by-side versions
+    /// count once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the marketplace query fails
+    pub async fn execute(
+        &self,
+        source: &[InstalledExtension],
+        target: &[InstalledExtension],
+        marketplace_url: Option<&str>,
+    ) -> Result<ExtensionSync, DomainError> {
+        let source_ids: HashSet<String> = source.iter().map(|ext| ext.id.to_lowercase()).collect();
+        let target_ids: HashSet<String> = target.iter().map(|ext| ext.id.to_lowercase()).collect();
+
+        let mut missing: Vec<String> = Vec::new();
+        for ext in source {
+            if !target_ids.contains(&ext.id.to_lowercase())
+                && !missing.iter().any(|id| id.eq_ignore_ascii_case(&ext.id))
+            {
+                missing.push(ext.id.clone());
+            }
+        }
+
+        let mut extras: Vec<InstalledExtension> = Vec::new();
+        for ext in target {
+            if !source_ids.contains(&ext.id.to_lowercase())
+                && !extras
+                    .iter()
+                    .any(|extra| extra.id.eq_ignore_ascii_case(&ext.id))
+            {
+                extras.push(ext.clone());
+            }
+        }
+
+        let mut sync = ExtensionSync {
+            extras,
+            ..ExtensionSync::default()
+        };
+        if missing.is_empty() {
+            return Ok(sync);
+        }
+
+        let mut available: HashMap<String, _> = self
+            .extension_repo
+            .get_extensions(&missing, None, marketplace_url)
+            .await?
+            .into_iter()
+            .map(|ext| (ext.unique_identifier().to_lowercase(), ext))
+            .collect();
+
+        for id in missing {
+            match available.remove(&id.to_lowercase()) {
+                Some(ext) => sync.to_install.push(ext),
+                None => sync.unavailable.push(id),
+            }
+        }
+
+        Ok(sync)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Extension, SearchResult};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockExtensionRepo {
+        queries: Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ExtensionRepository for MockExtensionRepo {
+        async fn search(
+            &self,
+            _query: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<SearchResult, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_extension(
+            &self,
+            _id: &str,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Extension, DomainError> {
+            unimplemented!()
+        }
+
+        async fn get_extensions(
+            &self,
+            ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            self.queries.lock().unwrap().push(ids.to_vec());
+            Ok(vec![Extension {
+                id: "ms-python.python".to_string(),
+                name: "python".to_string(),
+                publisher: "ms-python".to_string(),
+                version: "2024.2.0".to_string(),
+                display_name: "Python".to_string(),
+                description: None,
+                downloads: 0,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
+            }])
+        }
+
+        async fn download(
+            &self,
+            _extension: &Extension,
+            _target_platform: Option<&str>,
+        ) -> Result<Vec<u8>, DomainError> {
+            unimplemented!()
+        }
+    }
+
+    fn installed_extension(id: &str, version: &str) -> InstalledExtension {
+        InstalledExtension {
+            id: id.to_string(),
+            version: version.to_string(),
+            target_platform: None,
+            size: None,
+            installed_at: None,
+            location: None,
+            disabled: false,
+            pre_release: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_diffs_installed_sets() {
+        let use_case = SyncExtensionsUseCase::new(MockExtensionRepo::default());
+        let source = vec![
+            installed_extension("MS-Python.python", "2024.1.0"),
+            installed_extension("ms-python.python", "2024.0.0"),
+            installed_extension("ms-vscode.cpptools", "1.20.0"),
+            installed_extension("rust-lang.rust-analyzer", "0.3.0"),
+        ];
+        let target = vec![
+            installed_extension("Rust-Lang.Rust-Analyzer", "0.2.0"),
+            installed_extension("anysphere.cursorpyright", "1.0.0"),
+        ];
+
+        let sync = use_case.execute(&source, &target, None).await.unwrap();
+
+        let to_install: Vec<String> = sync
+            .to_install
+            .iter()
+            .map(Extension::unique_identifier)
+            .collect();
+        assert_eq!(to_install, vec!["ms-python.python"]);
+        assert_eq!(sync.unavailable, vec!["ms-vscode.cpptools"]);
+        assert_eq!(sync.extras, vec![target[1].clone()]);
+        assert_eq!(
+            *use_case.extension_repo.queries.lock().unwrap(),
+            vec![vec![
+                "MS-Python.python".to_string(),
+                "ms-vscode.cpptools".to_string()
+            ]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_skips_lookup_when_nothing_is_missing() {
+        let use_case = SyncExtensionsUseCase::new(MockExtensionRepo::default());
+        let source = vec![installed_extension("rust-lang.rust-analyzer", "0.3.0")];
+
+        let sync = use_case.execute(&source, &source, None).await.unwrap();
+
+        assert_eq!(sync, ExtensionSync::default());
+        assert!(use_case.extension_repo.queries.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::application::install_use_case::validate_extension_id;
+use crate::domain::{
+    DomainError, IdeType, InstallationDetector, InstallationExecutor, InstallationStrategy,
+    PlannedAction, PlannedChange,
+};
+
+/// Use case for removing installed extensions
+pub struct UninstallExtensionUseCase<D, X>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+{
+    detector: D,
+    executor: X,
+}
+
+impl<D, X> UninstallExtensionUseCase<D, X>
+where
+    D: InstallationDetector,
+    X: InstallationExecutor,
+{
+    pub fn new(detector: D, executor: X) -> Self {
+        Self { detector, executor }
+    }
+
+    /// Executes the uninstall use case with automatic method detection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Extension ID format is invalid
+    /// - The profile does not exist
+    /// - Installation method cannot be detected
+    /// - Extension is not installed
+    /// - Removal fails
+    pub async fn execute(
+        &self,
+        extension_id: &str,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+    ) -> Result<(), DomainError> {
+        validate_extension_id(extension_id)?;
+
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method).with_profile(profile);
+
+        self.executor.uninstall(&strategy, extension_id).await
+    }
+
+    /// Resolves what [`execute`](Self::execute) would do without doing it
+    ///
+    /// The installed extensions are listed to find the version and folder that
+    /// would be removed. A profile uninstall only changes the profile's
+    /// `extensions.json`, so no folder is reported for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension ID format is invalid, the profile does not
+    /// exist, the installation method cannot be detected or the extension is not
+    /// installed
+    pub async fn plan(
+        &self,
+        extension_id: &str,
+        ide_type: &IdeType,
+        profile: Option<&str>,
+    ) -> Result<PlannedChange, DomainError> {
+        validate_extension_id(extension_id)?;
+
+        let method = self.detector.detect_method(ide_type).await?;
+        let profile = profile
+            .map(|name| self.detector.resolve_profile(ide_type, name))
+            .transpose()?
+            .flatten();
+        let strategy = InstallationStrategy::new(ide_type.clone(), method).with_profile(profile);
+
+        let installed = self
+            .executor
+            .list_installed(&strategy)
+            .await?
+            .into_iter()
+            .find(|extension| extension.id.eq_ignore_ascii_case(extension_id))
+            .ok_or_else(|| DomainError::ExtensionNotFound(extension_id.to_string()))?;
+
+        Ok(PlannedChange {
+            action: PlannedAction::Uninstall,
+            extension_id: installed.id,
+            version: None,
+            installed_version: Some(installed.version),
+            target_platform: installed.target_platform,
+            download_url: None,
+            dependencies: Vec::new(),
+            destination: installed.location.filter(|_| strategy.profile.is_none()),
+            strategy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{InstallationMethod, InstalledExtension, ProfileTarget};
+    use async_trait::async_trait;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    struct MockDetector;
+
+    #[async_trait]
+    impl InstallationDetector for MockDetector {
+        async fn detect_method(
+            &self,
+            _ide_type: &IdeType,
+        ) -> Result<InstallationMethod, DomainError> {
+            Ok(InstallationMethod::FileSystem {
+                extensions_dir: PathBuf::from("/home/user/.vscode/extensions"),
+            })
+        }
+
+        fn extensions_directory(&self, _ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+            Ok(PathBuf::from("/home/user/.vscode/extensions"))
+        }
+
+        fn resolve_profile(
+            &self,
+            _ide_type: &IdeType,
+            name: &str,
+        ) -> Result<Option<ProfileTarget>, DomainError> {
+            Ok(Some(ProfileTarget {
+                name: name.to_string(),
+                registry_path: PathBuf::from(
+                    "/home/user/.config/Code/User/profiles/1/extensions.json",
+                ),
+            }))
+        }
+    }
+
+    #[derive(Default)]
+    struct MockExecutor {
+        uninstalled: Mutex<Vec<(IdeType, String)>>,
+        profiles: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait]
+    impl InstallationExecutor for MockExecutor {
+        async fn execute(
+            &self,
+            _strategy: &InstallationStrategy,
+            _extension_id: &str,
+            _vsix_data: &[u8],
+        ) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn uninstall(
+            &self,
+            strategy: &InstallationStrategy,
+            extension_id: &str,
+        ) -> Result<(), DomainError> {
+            self.uninstalled
+                .lock()
+                .unwrap()
+                .push((strategy.ide_type.clone(), extension_id.to_string()));
+            self.profiles.lock().unwrap().push(
+                strategy
+                    .profile
+                    .as_ref()
+                    .map(|profile| profile.name.clone()),
+            );
+            Ok(())
+        }
+
+        async fn list_installed(
+            &self,
+            _strategy: &InstallationStrategy,
+        ) -> Result<Vec<InstalledExtension>, DomainError> {
+            Ok(vec![InstalledExtension {
+                id: "publisher.extension".to_string(),
+                version: "1.2.0".to_string(),
+                target_platform: None,
+                size: None,
+                installed_at: None,
+                location: Some(PathBuf::from(
+                    "/home/user/.vscode/extensions/publisher.extension",
+                )),
+                disabled: false,
+                pre_release: false,
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_uses_detected_strategy() {
+        let use_case = UninstallExtensionUseCase::new(MockDetector, MockExecutor::default());
+
+        use_case
+            .execute("publisher.extension", &IdeType::cursor(), None)
+            .await
+            .unwrap();
+
+        let uninstalled = use_case.executor.uninstalled.lock().unwrap();
+        assert_eq!(
+            *uninstalled,
+            vec![(IdeType::cursor(), "publisher.extension".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_from_profile() {
+        let use_case = UninstallExtensionUseCase::new(MockDetector, MockExecutor::default());
+
+        use_case
+            .execute("publisher.extension", &IdeType::vscode(), Some("Rust"))
+            .await
+            .unwrap();
+
+        let profiles = use_case.executor.profiles.lock().unwrap();
+        assert_eq!(*profiles, vec![Some("Rust".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_plan_uninstall() {
+        let use_case = UninstallExtensionUseCase::new(MockDetector, MockExecutor::default());
+
+        let plan = use_case
+            .plan("Publisher.Extension", &IdeType::vscode(), None)
+            .await
+            .unwrap();
+        assert_eq!(plan.action, PlannedAction::Uninstall);
+        assert_eq!(plan.installed_version.as_deref(), Some("1.2.0"));
+        assert_eq!(
+            plan.destination,
+            Some(PathBuf::from(
+                "/home/user/.vscode/extensions/publisher.extension"
+            ))
+        );
+        assert!(use_case.executor.uninstalled.lock().unwrap().is_empty());
+
+        let plan = use_case
+            .plan("publisher.extension", &IdeType::vscode(), Some("Rust"))
+            .await
+            .unwrap();
+        assert_eq!(plan.destination, None);
+
+        let result = use_case
+            .plan("publisher.missing", &IdeType::vscode(), None)
+            .await;
+        assert!(matches!(result, Err(DomainError::ExtensionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_invalid_extension_id() {
+        let use_case = UninstallExtensionUseCase::new(MockDetector, MockExecutor::default());
+
+        let result = use_case
+            .execute("invalidid", &IdeType::vscode(), None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+    }
+}
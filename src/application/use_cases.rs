@@ -117,6 +117,15 @@ mod tests {
             unimplemented!()
         }
 
+        async fn get_extensions(
+            &self,
+            _ids: &[String],
+            _engine: Option<&str>,
+            _marketplace_url: Option<&str>,
+        ) -> Result<Vec<Extension>, DomainError> {
+            unimplemented!()
+        }
+
         async fn download(
             &self,
             _extension: &Extension,
@@ -156,6 +165,9 @@ mod tests {
                 display_name: "Python".to_string(),
                 description: Some("Python support".to_string()),
                 downloads: 1000,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
             }],
             total_count: 1,
         };
@@ -0,0 +1,174 @@
+use crate::domain::{
+    EditorManifest, ExtensionRequirement, InstalledExtension, ReleaseChannel, VersionSelector,
+    compare_versions,
+};
+use std::cmp::Ordering;
+
+/// The extensions of a VS Code profile export (`.code-profile` file)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeProfile {
+    /// Profile name shown by the editor when the file is imported
+    pub name: String,
+    pub extensions: Vec<ProfileExtension>,
+}
+
+/// An extension entry of a profile export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileExtension {
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    /// Exact version to install, `None` for the latest one
+    pub version: Option<String>,
+    /// Whether the extension follows its pre-release channel
+    pub pre_release: bool,
+    /// Whether the extension is installed but disabled
+    pub disabled: bool,
+}
+
+impl CodeProfile {
+    /// Builds a profile from installed extensions, keeping the newest version of
+    /// each ID
+    ///
+    /// Versions are only recorded when `include_versions` is set, so importing the
+    /// profile otherwise installs the latest releases.
+    #[must_use]
+    pub fn from_installed(
+        name: &str,
+        installed: &[InstalledExtension],
+        include_versions: bool,
+    ) -> Self {
+        let mut newest: Vec<&InstalledExtension> = Vec::new();
+        for ext in installed {
+            match newest
+                .iter_mut()
+                .find(|kept| kept.id.eq_ignore_ascii_case(&ext.id))
+            {
+                Some(kept) => {
+                    if compare_versions(&ext.version, &kept.version) == Ordering::Greater {
+                        *kept = ext;
+                    }
+                }
+                None => newest.push(ext),
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            extensions: newest
+                .into_iter()
+                .map(|ext| ProfileExtension {
+                    id: ext.id.clone(),
+                    version: include_versions.then(|| ext.version.clone()),
+                    pre_release: ext.pre_release,
+                    disabled: ext.disabled,
+                })
+                .collect(),
+        }
+    }
+
+    /// The profile's extensions as manifest requirements for `editor`, pinning
+    /// recorded versions and following pre-release channels
+    #[must_use]
+    pub fn to_manifest(&self, editor: &str) -> EditorManifest {
+        EditorManifest {
+            editor: editor.to_string(),
+            extensions: self
+                .extensions
+                .iter()
+                .map(|ext| ExtensionRequirement {
+                    id: ext.id.clone(),
+                    selector: VersionSelector {
+                        version: ext.version.clone(),
+                        channel: if ext.pre_release {
+                            ReleaseChannel::PreRelease
+                        } else {
+                            ReleaseChannel::Stable
+                        },
+                        platform: None,
+                        engine: None,
+                    },
+                })
+                .collect(),
+            prune: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(id: &str, version: &str) -> InstalledExtension {
+        InstalledExtension {
+            id: id.to_string(),
+            version: version.to_string(),
+            target_platform: None,
+            size: None,
+            installed_at: None,
+            location: None,
+            disabled: false,
+            pre_release: false,
+        }
+    }
+
+    #[test]
+    fn test_from_installed_keeps_newest_version() {
+        let mut beta = installed("golang.go", "0.41.0");
+        beta.pre_release = true;
+        beta.disabled = true;
+        let installed = [installed("golang.go", "0.40.0"), beta];
+
+        let profile = CodeProfile::from_installed("Go", &installed, true);
+        assert_eq!(profile.name, "Go");
+        assert_eq!(
+            profile.extensions,
+            vec![ProfileExtension {
+                id: "golang.go".to_string(),
+                version: Some("0.41.0".to_string()),
+                pre_release: true,
+                disabled: true,
+            }]
+        );
+
+        let profile = CodeProfile::from_installed("Go", &installed, false);
+        assert_eq!(profile.extensions[0].version, None);
+    }
+
+    #[test]
+    fn test_to_manifest() {
+        let profile = CodeProfile {
+            name: "Default".to_string(),
+            extensions: vec![
+                ProfileExtension {
+                    id: "rust-lang.rust-analyzer".to_string(),
+                    version: Some("0.3.2000".to_string()),
+                    pre_release: false,
+                    disabled: false,
+                },
+                ProfileExtension {
+                    id: "golang.go".to_string(),
+                    version: None,
+                    pre_release: true,
+                    disabled: true,
+                },
+            ],
+        };
+
+        let manifest = profile.to_manifest("vscode");
+        assert_eq!(manifest.editor, "vscode");
+        assert!(!manifest.prune);
+        assert_eq!(
+            manifest.extensions[0].selector.version.as_deref(),
+            Some("0.3.2000")
+        );
+        assert_eq!(
+            manifest.extensions[0].selector.channel,
+            ReleaseChannel::Stable
+        );
+        assert!(!manifest.extensions[1].selector.is_latest());
+        assert_eq!(
+            manifest.extensions[1].selector.channel,
+            ReleaseChannel::PreRelease
+        );
+    }
+}
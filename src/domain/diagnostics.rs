@@ -0,0 +1,247 @@
+use crate::domain::{StaleExtension, StaleReason};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// What `vsix doctor` found out about the machine and its editors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnvironmentDiagnosis {
+    /// Target platform extensions are downloaded for, `None` if unsupported
+    pub platform: Option<String>,
+    /// C library of the system, e.g. "glibc 2.39" or "musl", if known
+    pub libc: Option<String>,
+    /// Proxy environment variables, with credentials masked
+    pub proxies: Vec<(String, String)>,
+    /// Configuration file editors are read from, and whether it exists
+    pub config_path: Option<PathBuf>,
+    pub config_exists: bool,
+    pub editors: Vec<EditorDiagnosis>,
+}
+
+impl EnvironmentDiagnosis {
+    /// Describes problems that are not specific to one editor
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.platform.is_none() {
+            warnings.push(
+                "This OS and CPU have no marketplace platform, only universal packages can be installed"
+                    .to_string(),
+            );
+        }
+        if let (Some(libc), Some(platform)) = (&self.libc, &self.platform)
+            && libc.starts_with("musl")
+            && !platform.starts_with("alpine")
+        {
+            warnings.push(format!(
+                "The system uses musl but {platform} packages are built for glibc; native extensions may fail to load"
+            ));
+        }
+        warnings
+    }
+}
+
+/// Where an editor's extensions directory comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DirectorySource {
+    /// The `--extensions-dir` option
+    Option,
+    /// The `VSCODE_EXTENSIONS` environment variable
+    ExtensionsEnvVar,
+    /// The `VSCODE_PORTABLE` environment variable
+    PortableEnvVar,
+    /// A `data` folder next to the editor's binary
+    PortableInstall,
+    /// The editor's remote server (`--server`, or no local installation)
+    RemoteServer,
+    /// The editor's default location
+    Default,
+}
+
+impl fmt::Display for DirectorySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Option => write!(f, "--extensions-dir"),
+            Self::ExtensionsEnvVar => write!(f, "VSCODE_EXTENSIONS"),
+            Self::PortableEnvVar => write!(f, "VSCODE_PORTABLE"),
+            Self::PortableInstall => write!(f, "portable install"),
+            Self::RemoteServer => write!(f, "remote server"),
+            Self::Default => write!(f, "default location"),
+        }
+    }
+}
+
+/// Whether vsix can write to a directory
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DirectoryAccess {
+    Missing,
+    ReadOnly,
+    Writable,
+}
+
+impl fmt::Display for DirectoryAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "missing"),
+            Self::ReadOnly => write!(f, "read-only"),
+            Self::Writable => write!(f, "writable"),
+        }
+    }
+}
+
+/// What `vsix doctor` found out about one editor
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EditorDiagnosis {
+    /// Name used to select the editor, e.g. `--ide vscodium`
+    pub name: String,
+    pub display_name: String,
+    /// CLI binary found in PATH
+    pub cli_path: Option<PathBuf>,
+    /// First line of `<cli> --version`, or why it could not be read
+    pub cli_version: Result<String, String>,
+    /// How `vsix install` would install, e.g. "CLI" or "extensions directory"
+    pub install_method: Result<String, String>,
+    pub extensions_dir: Result<PathBuf, String>,
+    pub extensions_dir_source: Option<DirectorySource>,
+    pub extensions_dir_access: DirectoryAccess,
+    /// Gallery service the editor searches and downloads from
+    pub gallery_url: String,
+    /// Round trip of a gallery query in milliseconds, or why it failed
+    pub gallery_latency: Result<u64, String>,
+    pub installed_count: usize,
+    /// Folders `vsix gc` would remove
+    pub stale: Vec<StaleExtension>,
+}
+
+impl EditorDiagnosis {
+    /// Whether the editor looks installed on this machine
+    #[must_use]
+    pub fn is_present(&self) -> bool {
+        self.cli_path.is_some() || self.extensions_dir_access != DirectoryAccess::Missing
+    }
+
+    /// Describes the problems found for the editor
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(cli_path) = &self.cli_path
+            && let Err(e) = &self.cli_version
+        {
+            warnings.push(format!("{} --version failed: {e}", cli_path.display()));
+        }
+        if let Err(e) = &self.install_method {
+            warnings.push(format!("No installation method: {e}"));
+        }
+        match (&self.extensions_dir, self.extensions_dir_access) {
+            (Err(e), _) => warnings.push(format!("Extensions directory unknown: {e}")),
+            (Ok(dir), DirectoryAccess::ReadOnly) => {
+                warnings.push(format!("{} is not writable", dir.display()));
+            }
+            _ => {}
+        }
+        if let Err(e) = &self.gallery_latency {
+            warnings.push(format!("{} is unreachable: {e}", self.gallery_url));
+        }
+
+        let count = |reason| self.stale.iter().filter(|ext| ext.reason == reason).count();
+        let old = count(StaleReason::OldVersion) + count(StaleReason::Obsolete);
+        if old > 0 {
+            warnings.push(format!(
+                "{old} duplicated or obsolete extension version(s), run `vsix gc`"
+            ));
+        }
+        let orphaned = count(StaleReason::Orphaned);
+        if orphaned > 0 {
+            warnings.push(format!(
+                "{orphaned} extension folder(s) missing from extensions.json, run `vsix gc`"
+            ));
+        }
+        for ext in self
+            .stale
+            .iter()
+            .filter(|ext| ext.reason == StaleReason::Broken)
+        {
+            warnings.push(format!(
+                "Broken manifest or missing folder: {}",
+                ext.folder.display()
+            ));
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnosis() -> EditorDiagnosis {
+        EditorDiagnosis {
+            name: "vscode".to_string(),
+            display_name: "Visual Studio Code".to_string(),
+            cli_path: None,
+            cli_version: Err("not found".to_string()),
+            install_method: Ok("extensions directory".to_string()),
+            extensions_dir: Ok(PathBuf::from("/home/user/.vscode/extensions")),
+            extensions_dir_source: Some(DirectorySource::Default),
+            extensions_dir_access: DirectoryAccess::Writable,
+            gallery_url: "https://marketplace.visualstudio.com/_apis/public/gallery".to_string(),
+            gallery_latency: Ok(120),
+            installed_count: 3,
+            stale: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_editor_warnings() {
+        let mut editor = diagnosis();
+        assert!(editor.is_present());
+        assert!(editor.warnings().is_empty());
+
+        editor.extensions_dir_access = DirectoryAccess::ReadOnly;
+        editor.gallery_latency = Err("HTTP 503".to_string());
+        editor.stale = vec![
+            StaleExtension {
+                folder: PathBuf::from("/home/user/.vscode/extensions/acme.tools-1.0.0"),
+                reason: StaleReason::OldVersion,
+                id: Some("acme.tools".to_string()),
+                version: Some("1.0.0".to_string()),
+                size: 10,
+            },
+            StaleExtension {
+                folder: PathBuf::from("/home/user/.vscode/extensions/acme.broken"),
+                reason: StaleReason::Broken,
+                id: None,
+                version: None,
+                size: 0,
+            },
+        ];
+
+        let warnings = editor.warnings();
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings[0].contains("is not writable"));
+        assert!(warnings[1].contains("HTTP 503"));
+        assert!(warnings[2].contains("1 duplicated"));
+        assert!(warnings[3].contains("acme.broken"));
+
+        editor.extensions_dir_access = DirectoryAccess::Missing;
+        assert!(!editor.is_present());
+    }
+
+    #[test]
+    fn test_environment_warns_about_musl() {
+        let mut environment = EnvironmentDiagnosis {
+            platform: Some("linux-x64".to_string()),
+            libc: Some("glibc 2.39".to_string()),
+            proxies: Vec::new(),
+            config_path: None,
+            config_exists: false,
+            editors: Vec::new(),
+        };
+        assert!(environment.warnings().is_empty());
+
+        environment.libc = Some("musl".to_string());
+        assert!(environment.warnings()[0].contains("musl"));
+    }
+}
@@ -9,6 +9,15 @@ pub struct Extension {
     pub display_name: String,
     pub description: Option<String>,
     pub downloads: u64,
+    /// Package URL reported by the gallery, if any
+    #[serde(default)]
+    pub package_url: Option<String>,
+    /// Target platform of the selected version, `None` for universal builds
+    #[serde(default)]
+    pub target_platform: Option<String>,
+    /// Extension dependencies and pack members declared by the selected version
+    #[serde(default)]
+    pub dependencies: Vec<String>,
 }
 
 impl Extension {
@@ -17,8 +26,17 @@ impl Extension {
         format!("{}.{}", self.publisher, self.name)
     }
 
+    /// Returns the URL the VSIX package is downloaded from
+    ///
+    /// The package URL reported by the gallery is preferred; it already points at
+    /// the selected version's target platform. Otherwise the VS Code marketplace
+    /// package endpoint is used.
     #[must_use]
     pub fn download_url(&self, target_platform: Option<&str>) -> String {
+        if let Some(package_url) = &self.package_url {
+            return package_url.clone();
+        }
+
         let base_url = format!(
             "https://marketplace.visualstudio.com/_apis/public/gallery/publishers/{}/vsextensions/{}/{}/vspackage",
             self.publisher, self.name, self.version
@@ -37,6 +55,89 @@ pub struct SearchResult {
     pub total_count: usize,
 }
 
+/// An extension that is installed in an editor
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstalledExtension {
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    pub version: String,
+    /// Target platform of platform-specific builds, `None` for universal ones
+    pub target_platform: Option<String>,
+    /// Size on disk in bytes, if known
+    pub size: Option<u64>,
+    /// Installation time in milliseconds since the Unix epoch, if known
+    pub installed_at: Option<u64>,
+    /// Folder the extension is installed in, if known
+    pub location: Option<std::path::PathBuf>,
+    /// Whether the editor has the extension disabled
+    #[serde(default)]
+    pub disabled: bool,
+    /// Whether the extension was installed from its pre-release channel
+    #[serde(default)]
+    pub pre_release: bool,
+}
+
+/// An installed extension for which the marketplace has a newer version
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutdatedExtension {
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    pub installed_version: String,
+    pub latest_version: String,
+    /// Whether the installed version follows the pre-release channel
+    #[serde(default)]
+    pub pre_release: bool,
+}
+
+/// Differences between the extensions installed in two editors
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExtensionSync {
+    /// Extensions missing from the target, as found in the target's marketplace
+    pub to_install: Vec<Extension>,
+    /// IDs of extensions missing from the target that its marketplace does not have
+    pub unavailable: Vec<String>,
+    /// Extensions installed in the target only
+    pub extras: Vec<InstalledExtension>,
+}
+
+/// Why `vsix gc` considers an extension folder removable
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StaleReason {
+    /// Listed in the editor's `.obsolete` file, pending deletion
+    Obsolete,
+    /// An older version of an extension that has a newer one installed
+    OldVersion,
+    /// Not registered in any `extensions.json` of the editor
+    Orphaned,
+    /// Missing its `package.json`, or registered but gone
+    Broken,
+}
+
+impl std::fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Obsolete => write!(f, "obsolete"),
+            Self::OldVersion => write!(f, "old version"),
+            Self::Orphaned => write!(f, "orphaned"),
+            Self::Broken => write!(f, "broken"),
+        }
+    }
+}
+
+/// An extension folder no editor profile uses any more
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StaleExtension {
+    /// Folder inside the extensions directory
+    pub folder: std::path::PathBuf,
+    pub reason: StaleReason,
+    /// Extension ID in format "publisher.name", if the folder has a manifest
+    pub id: Option<String>,
+    pub version: Option<String>,
+    /// Size on disk in bytes
+    pub size: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,6 +152,9 @@ mod tests {
             display_name: "Python".to_string(),
             description: Some("Python language support".to_string()),
             downloads: 1000,
+            package_url: None,
+            target_platform: None,
+            dependencies: Vec::new(),
         };
 
         assert_eq!(ext.unique_identifier(), "ms-python.python");
@@ -66,6 +170,9 @@ mod tests {
             display_name: "Python".to_string(),
             description: None,
             downloads: 1000,
+            package_url: None,
+            target_platform: None,
+            dependencies: Vec::new(),
         };
 
         let url = ext.download_url(Some("win32-x64"));
@@ -85,6 +192,9 @@ mod tests {
             display_name: "Python".to_string(),
             description: None,
             downloads: 1000,
+            package_url: None,
+            target_platform: None,
+            dependencies: Vec::new(),
         };
 
         let url = ext.download_url(None);
@@ -14,6 +14,21 @@ pub enum DomainError {
     #[error("Installation failed: {0}")]
     InstallationFailed(String),
 
+    #[error("Extension already installed: {0}")]
+    AlreadyInstalled(String),
+
+    #[error("Extension not compatible: {0}")]
+    Incompatible(String),
+
+    #[error("Editor state is locked: {0}")]
+    Locked(String),
+
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
+    #[error("Lockfile is out of date: {0}")]
+    StaleLock(String),
+
     #[error("Directory not found: {0}")]
     DirectoryNotFound(String),
 
@@ -0,0 +1,430 @@
+use crate::domain::DomainError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory of an IDE (extensions, user data) per operating system
+///
+/// Relative paths are resolved against the user's home directory; absolute
+/// paths and paths starting with `~/` are supported as well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionsDirectory {
+    pub linux: String,
+    pub macos: String,
+    pub windows: String,
+}
+
+impl ExtensionsDirectory {
+    /// Uses the same directory on every operating system
+    #[must_use]
+    pub fn same(path: &str) -> Self {
+        Self {
+            linux: path.to_string(),
+            macos: path.to_string(),
+            windows: path.to_string(),
+        }
+    }
+
+    /// Returns the path configured for the current operating system
+    #[must_use]
+    pub fn for_current_os(&self) -> &str {
+        if cfg!(target_os = "windows") {
+            &self.windows
+        } else if cfg!(target_os = "macos") {
+            &self.macos
+        } else {
+            &self.linux
+        }
+    }
+
+    /// Resolves the directory for the current operating system
+    #[must_use]
+    pub fn resolve(&self, home: &Path) -> PathBuf {
+        let path = self.for_current_os();
+
+        if let Some(relative) = path.strip_prefix("~/") {
+            home.join(relative)
+        } else {
+            // Joining an absolute path replaces the home directory entirely
+            home.join(path)
+        }
+    }
+}
+
+/// Describes an IDE that can host VS Code extensions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdeType {
+    /// Name used to select the IDE, e.g. `--ide vscodium`
+    pub name: String,
+    pub display_name: String,
+    /// CLI binaries to look for in PATH, in order of preference
+    pub commands: Vec<String>,
+    pub extensions_dir: ExtensionsDirectory,
+    /// Extension gallery the IDE uses by default, `None` for the VS Code marketplace
+    #[serde(default)]
+    pub gallery_url: Option<String>,
+    /// Extensions directory of the IDE's remote server (Remote-SSH, dev containers)
+    #[serde(default)]
+    pub server_extensions_dir: Option<String>,
+    /// User data directory holding settings, global storage and profiles
+    #[serde(default)]
+    pub user_data_dir: Option<ExtensionsDirectory>,
+}
+
+impl IdeType {
+    /// Visual Studio Code
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in registry has no `vscode` entry
+    #[must_use]
+    pub fn vscode() -> Self {
+        IdeRegistry::builtin()
+            .get("vscode")
+            .cloned()
+            .expect("vscode is a built-in IDE")
+    }
+
+    /// Cursor
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in registry has no `cursor` entry
+    #[must_use]
+    pub fn cursor() -> Self {
+        IdeRegistry::builtin()
+            .get("cursor")
+            .cloned()
+            .expect("cursor is a built-in IDE")
+    }
+
+    /// Returns the preferred CLI command name for the IDE
+    #[must_use]
+    pub fn command_name(&self) -> &str {
+        self.commands
+            .first()
+            .map_or(self.name.as_str(), String::as_str)
+    }
+
+    /// Returns the display name for the IDE
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Resolves the user data directory, if known
+    #[must_use]
+    pub fn user_data_directory(&self, home: &Path) -> Option<PathBuf> {
+        self.user_data_dir.as_ref().map(|dir| dir.resolve(home))
+    }
+
+    /// Resolves the remote server extensions directory, if the IDE has a server
+    #[must_use]
+    pub fn server_extensions_directory(&self, home: &Path) -> Option<PathBuf> {
+        self.server_extensions_dir
+            .as_deref()
+            .map(|path| ExtensionsDirectory::same(path).resolve(home))
+    }
+}
+
+const OPEN_VSX_GALLERY: &str = "https://open-vsx.org/vscode/gallery";
+
+struct BuiltinIde {
+    name: &'static str,
+    display_name: &'static str,
+    commands: &'static [&'static str],
+    linux: &'static str,
+    macos: &'static str,
+    windows: &'static str,
+    gallery_url: Option<&'static str>,
+    server: Option<&'static str>,
+    /// Folder name of the user data directory in the OS's application data folder
+    user_data: Option<&'static str>,
+}
+
+const BUILTIN_IDES: &[BuiltinIde] = &[
+    BuiltinIde {
+        name: "vscode",
+        display_name: "VSCode",
+        commands: &["code"],
+        linux: ".vscode/extensions",
+        macos: ".vscode/extensions",
+        windows: ".vscode/extensions",
+        gallery_url: None,
+        server: Some(".vscode-server/extensions"),
+        user_data: Some("Code"),
+    },
+    BuiltinIde {
+        name: "insiders",
+        display_name: "VSCode Insiders",
+        commands: &["code-insiders"],
+        linux: ".vscode-insiders/extensions",
+        macos: ".vscode-insiders/extensions",
+        windows: ".vscode-insiders/extensions",
+        gallery_url: None,
+        server: Some(".vscode-server-insiders/extensions"),
+        user_data: Some("Code - Insiders"),
+    },
+    BuiltinIde {
+        name: "cursor",
+        display_name: "Cursor",
+        commands: &["cursor"],
+        linux: ".config/Cursor/User/extensions",
+        macos: "Library/Application Support/Cursor/User/extensions",
+        windows: "AppData/Roaming/Cursor/User/extensions",
+        gallery_url: None,
+        server: Some(".cursor-server/extensions"),
+        user_data: Some("Cursor"),
+    },
+    BuiltinIde {
+        name: "vscodium",
+        display_name: "VSCodium",
+        commands: &["codium", "vscodium"],
+        linux: ".vscode-oss/extensions",
+        macos: ".vscode-oss/extensions",
+        windows: ".vscode-oss/extensions",
+        gallery_url: Some(OPEN_VSX_GALLERY),
+        server: Some(".vscodium-server/extensions"),
+        user_data: Some("VSCodium"),
+    },
+    BuiltinIde {
+        name: "windsurf",
+        display_name: "Windsurf",
+        commands: &["windsurf"],
+        linux: ".windsurf/extensions",
+        macos: ".windsurf/extensions",
+        windows: ".windsurf/extensions",
+        gallery_url: Some(OPEN_VSX_GALLERY),
+        server: Some(".windsurf-server/extensions"),
+        user_data: Some("Windsurf"),
+    },
+    BuiltinIde {
+        name: "code-server",
+        display_name: "code-server",
+        commands: &["code-server"],
+        linux: ".local/share/code-server/extensions",
+        macos: ".local/share/code-server/extensions",
+        windows: "AppData/Local/code-server/extensions",
+        gallery_url: Some(OPEN_VSX_GALLERY),
+        server: None,
+        user_data: None,
+    },
+    BuiltinIde {
+        name: "positron",
+        display_name: "Positron",
+        commands: &["positron"],
+        linux: ".positron/extensions",
+        macos: ".positron/extensions",
+        windows: ".positron/extensions",
+        gallery_url: Some(OPEN_VSX_GALLERY),
+        server: Some(".positron-server/extensions"),
+        user_data: Some("Positron"),
+    },
+    BuiltinIde {
+        name: "trae",
+        display_name: "Trae",
+        commands: &["trae"],
+        linux: ".trae/extensions",
+        macos: ".trae/extensions",
+        windows: ".trae/extensions",
+        gallery_url: None,
+        server: Some(".trae-server/extensions"),
+        user_data: Some("Trae"),
+    },
+];
+
+impl From<&BuiltinIde> for IdeType {
+    fn from(builtin: &BuiltinIde) -> Self {
+        Self {
+            name: builtin.name.to_string(),
+            display_name: builtin.display_name.to_string(),
+            commands: builtin.commands.iter().map(ToString::to_string).collect(),
+            extensions_dir: ExtensionsDirectory {
+                linux: builtin.linux.to_string(),
+                macos: builtin.macos.to_string(),
+                windows: builtin.windows.to_string(),
+            },
+            gallery_url: builtin.gallery_url.map(ToString::to_string),
+            server_extensions_dir: builtin.server.map(ToString::to_string),
+            user_data_dir: builtin.user_data.map(|folder| ExtensionsDirectory {
+                linux: format!(".config/{folder}"),
+                macos: format!("Library/Application Support/{folder}"),
+                windows: format!("AppData/Roaming/{folder}"),
+            }),
+        }
+    }
+}
+
+/// Registry of the IDEs extensions can be installed into
+#[derive(Debug, Clone)]
+pub struct IdeRegistry {
+    ides: Vec<IdeType>,
+}
+
+impl IdeRegistry {
+    /// Creates a registry containing only the built-in IDEs
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self {
+            ides: BUILTIN_IDES.iter().map(IdeType::from).collect(),
+        }
+    }
+
+    /// Adds user-defined IDEs, replacing built-in entries with the same name
+    #[must_use]
+    pub fn with_user_entries(mut self, entries: Vec<IdeType>) -> Self {
+        for entry in entries {
+            match self
+                .ides
+                .iter_mut()
+                .find(|ide| ide.name.eq_ignore_ascii_case(&entry.name))
+            {
+                Some(existing) => *existing = entry,
+                None => self.ides.push(entry),
+            }
+        }
+        self
+    }
+
+    /// Looks up an IDE by name (case-insensitive)
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&IdeType> {
+        self.ides
+            .iter()
+            .find(|ide| ide.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Looks up an IDE by name, failing with the list of known names
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::Unknown` if no IDE with that name is registered
+    pub fn resolve(&self, name: &str) -> Result<&IdeType, DomainError> {
+        self.get(name).ok_or_else(|| {
+            DomainError::Unknown(format!(
+                "Unknown IDE '{name}'. Available: {}",
+                self.names().join(", ")
+            ))
+        })
+    }
+
+    /// Returns the names of all registered IDEs
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.ides.iter().map(|ide| ide.name.as_str()).collect()
+    }
+
+    /// Returns all registered IDEs
+    #[must_use]
+    pub fn all(&self) -> &[IdeType] {
+        &self.ides
+    }
+}
+
+impl Default for IdeRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry_covers_supported_ides() {
+        let registry = IdeRegistry::builtin();
+
+        for (name, command) in [
+            ("vscode", "code"),
+            ("insiders", "code-insiders"),
+            ("cursor", "cursor"),
+            ("vscodium", "codium"),
+            ("windsurf", "windsurf"),
+            ("code-server", "code-server"),
+            ("positron", "positron"),
+            ("trae", "trae"),
+        ] {
+            let ide = registry.get(name).unwrap();
+            assert_eq!(ide.command_name(), command);
+        }
+
+        assert_eq!(
+            registry.get("VSCodium").unwrap().gallery_url.as_deref(),
+            Some(OPEN_VSX_GALLERY)
+        );
+    }
+
+    #[test]
+    fn test_user_entries_override_and_extend() {
+        let custom = IdeType {
+            name: "my-editor".to_string(),
+            display_name: "My Editor".to_string(),
+            commands: vec!["my-editor".to_string()],
+            extensions_dir: ExtensionsDirectory::same(".my-editor/extensions"),
+            gallery_url: None,
+            server_extensions_dir: None,
+            user_data_dir: None,
+        };
+        let vscode_override = IdeType {
+            extensions_dir: ExtensionsDirectory::same("/opt/vscode/extensions"),
+            ..IdeType::vscode()
+        };
+
+        let registry = IdeRegistry::builtin().with_user_entries(vec![custom, vscode_override]);
+
+        assert!(registry.get("my-editor").is_some());
+        assert_eq!(
+            registry
+                .get("vscode")
+                .unwrap()
+                .extensions_dir
+                .resolve(Path::new("/home/user")),
+            PathBuf::from("/opt/vscode/extensions")
+        );
+    }
+
+    #[test]
+    fn test_resolve_unknown_ide_lists_names() {
+        let registry = IdeRegistry::builtin();
+
+        match registry.resolve("notepad") {
+            Err(DomainError::Unknown(msg)) => assert!(msg.contains("vscodium")),
+            other => panic!("Expected Unknown error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_extensions_directory_resolve() {
+        let home = Path::new("/home/user");
+
+        assert_eq!(
+            ExtensionsDirectory::same(".vscode/extensions").resolve(home),
+            PathBuf::from("/home/user/.vscode/extensions")
+        );
+        assert_eq!(
+            ExtensionsDirectory::same("~/.vscode-oss/extensions").resolve(home),
+            PathBuf::from("/home/user/.vscode-oss/extensions")
+        );
+    }
+
+    #[test]
+    fn test_server_extensions_directory() {
+        let home = Path::new("/home/user");
+
+        assert_eq!(
+            IdeType::vscode().server_extensions_directory(home),
+            Some(PathBuf::from("/home/user/.vscode-server/extensions"))
+        );
+        assert_eq!(
+            IdeType::cursor().server_extensions_directory(home),
+            Some(PathBuf::from("/home/user/.cursor-server/extensions"))
+        );
+        assert_eq!(
+            IdeRegistry::builtin()
+                .get("code-server")
+                .unwrap()
+                .server_extensions_directory(home),
+            None
+        );
+    }
+}
@@ -1,39 +1,40 @@
-use crate::domain::DomainError;
+use crate::domain::{DomainError, IdeType, InstalledExtension};
 use async_trait::async_trait;
 use std::path::PathBuf;
 
-/// Represents the available IDE types for extension installation
-#[derive(Debug, Clone, PartialEq)]
-pub enum IdeType {
-    VsCode,
-    Cursor,
-}
-
-impl IdeType {
-    /// Returns the CLI command name for the IDE
-    pub fn command_name(&self) -> &'static str {
-        match self {
-            IdeType::VsCode => "code",
-            IdeType::Cursor => "cursor",
-        }
-    }
-
-    /// Returns the display name for the IDE
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            IdeType::VsCode => "VSCode",
-            IdeType::Cursor => "Cursor",
-        }
-    }
-}
-
 /// Represents the installation method available for an IDE
 #[derive(Debug, Clone, PartialEq)]
 pub enum InstallationMethod {
     /// Install using CLI command (e.g., `code --install-extension`)
-    CliCommand { command_path: PathBuf },
+    ///
+    /// A custom extensions directory is forwarded as `--extensions-dir`.
+    CliCommand {
+        command_path: PathBuf,
+        extensions_dir: Option<PathBuf>,
+    },
     /// Install by extracting to file system directory
     FileSystem { extensions_dir: PathBuf },
+    /// Install into a remote server's extensions directory (e.g. `~/.vscode-server/extensions`)
+    ///
+    /// Only extensions that can run on the workspace side are installed here.
+    RemoteServer { extensions_dir: PathBuf },
+}
+
+/// A VS Code profile that keeps its own list of installed extensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileTarget {
+    pub name: String,
+    /// The profile's `extensions.json` (`User/profiles/<id>/extensions.json`)
+    pub registry_path: PathBuf,
+}
+
+/// Options for installing an extension
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// Reinstall even if the same version is already installed
+    pub force: bool,
+    /// Track pre-release versions of the extension (`--pre-release` for the CLI)
+    pub pre_release: bool,
 }
 
 /// Value object representing an installation strategy for a specific IDE
@@ -41,19 +42,103 @@ pub enum InstallationMethod {
 pub struct InstallationStrategy {
     pub ide_type: IdeType,
     pub method: InstallationMethod,
+    /// Profile to install into, `None` for the default profile
+    pub profile: Option<ProfileTarget>,
+    pub options: InstallOptions,
 }
 
 impl InstallationStrategy {
     pub fn new(ide_type: IdeType, method: InstallationMethod) -> Self {
-        Self { ide_type, method }
+        Self {
+            ide_type,
+            method,
+            profile: None,
+            options: InstallOptions::default(),
+        }
+    }
+
+    /// Targets a profile instead of the default profile
+    #[must_use]
+    pub fn with_profile(mut self, profile: Option<ProfileTarget>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Sets the install options
+    #[must_use]
+    pub fn with_options(mut self, options: InstallOptions) -> Self {
+        self.options = options;
+        self
     }
 }
 
+/// What a planned change does to an extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlannedAction {
+    Install,
+    Update,
+    /// Replaces the installed version with an older one
+    Downgrade,
+    Uninstall,
+}
+
+impl std::fmt::Display for PlannedAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Install => write!(f, "install"),
+            Self::Update => write!(f, "update"),
+            Self::Downgrade => write!(f, "downgrade"),
+            Self::Uninstall => write!(f, "uninstall"),
+        }
+    }
+}
+
+/// A change that `--dry-run` reports instead of applying
+#[derive(Debug, Clone)]
+pub struct PlannedChange {
+    pub action: PlannedAction,
+    /// Extension ID in format "publisher.name", or the package URL if the ID is only
+    /// known after downloading the package
+    pub extension_id: String,
+    /// Version that would be installed, `None` for uninstalls or unknown packages
+    pub version: Option<String>,
+    pub installed_version: Option<String>,
+    /// Target platform of the selected build, `None` for universal ones
+    pub target_platform: Option<String>,
+    /// URL the package would be downloaded from, `None` if nothing is downloaded
+    pub download_url: Option<String>,
+    /// Extension dependencies and pack members of the package
+    pub dependencies: Vec<String>,
+    /// The detected method, profile and options the change would be applied with
+    pub strategy: InstallationStrategy,
+    /// Folder that would be written or removed, `None` if it depends on the
+    /// package's contents or only the profile's `extensions.json` changes
+    pub destination: Option<PathBuf>,
+}
+
 /// Service for detecting available installation methods
 #[async_trait]
 pub trait InstallationDetector: Send + Sync {
     /// Detects the available installation method for the specified IDE
     async fn detect_method(&self, ide_type: &IdeType) -> Result<InstallationMethod, DomainError>;
+
+    /// Returns the directory the IDE keeps its extensions in
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the home directory cannot be determined
+    fn extensions_directory(&self, ide_type: &IdeType) -> Result<PathBuf, DomainError>;
+
+    /// Looks up a profile of the IDE by name, `None` for the default profile
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the IDE has no profile with that name
+    fn resolve_profile(
+        &self,
+        ide_type: &IdeType,
+        name: &str,
+    ) -> Result<Option<ProfileTarget>, DomainError>;
 }
 
 /// Service for executing installations using a specific strategy
@@ -66,6 +151,19 @@ pub trait InstallationExecutor: Send + Sync {
         extension_id: &str,
         vsix_data: &[u8],
     ) -> Result<(), DomainError>;
+
+    /// Removes an installed extension using the provided strategy
+    async fn uninstall(
+        &self,
+        strategy: &InstallationStrategy,
+        extension_id: &str,
+    ) -> Result<(), DomainError>;
+
+    /// Lists the installed extensions using the provided strategy
+    async fn list_installed(
+        &self,
+        strategy: &InstallationStrategy,
+    ) -> Result<Vec<InstalledExtension>, DomainError>;
 }
 
 #[cfg(test)]
@@ -74,28 +172,29 @@ mod tests {
 
     #[test]
     fn test_ide_type_command_names() {
-        assert_eq!(IdeType::VsCode.command_name(), "code");
-        assert_eq!(IdeType::Cursor.command_name(), "cursor");
+        assert_eq!(IdeType::vscode().command_name(), "code");
+        assert_eq!(IdeType::cursor().command_name(), "cursor");
     }
 
     #[test]
     fn test_ide_type_display_names() {
-        assert_eq!(IdeType::VsCode.display_name(), "VSCode");
-        assert_eq!(IdeType::Cursor.display_name(), "Cursor");
+        assert_eq!(IdeType::vscode().display_name(), "VSCode");
+        assert_eq!(IdeType::cursor().display_name(), "Cursor");
     }
 
     #[test]
     fn test_installation_strategy_creation() {
         let strategy = InstallationStrategy::new(
-            IdeType::VsCode,
+            IdeType::vscode(),
             InstallationMethod::CliCommand {
                 command_path: PathBuf::from("/usr/local/bin/code"),
+                extensions_dir: None,
             },
         );
 
-        assert_eq!(strategy.ide_type, IdeType::VsCode);
+        assert_eq!(strategy.ide_type, IdeType::vscode());
         match strategy.method {
-            InstallationMethod::CliCommand { command_path } => {
+            InstallationMethod::CliCommand { command_path, .. } => {
                 assert_eq!(command_path, PathBuf::from("/usr/local/bin/code"));
             }
             _ => panic!("Expected CliCommand"),
@@ -0,0 +1,261 @@
+use crate::domain::{Architecture, DomainError, Extension, ExtensionRequirement};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// File name of the lockfile written next to a `vsix.toml` manifest
+pub const LOCK_FILE: &str = "vsix.lock";
+
+/// Format version written to new lockfiles
+pub const LOCK_FORMAT_VERSION: u32 = 1;
+
+/// The exact packages a manifest resolved to, as recorded in `vsix.lock`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionLock {
+    pub version: u32,
+    #[serde(default, rename = "extension")]
+    pub extensions: Vec<LockedExtension>,
+}
+
+impl Default for ExtensionLock {
+    fn default() -> Self {
+        Self {
+            version: LOCK_FORMAT_VERSION,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+/// A resolved extension package and the SHA-256 of its bytes
+///
+/// Platform-specific extensions have one entry per target platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedExtension {
+    /// Editor section of the manifest the extension is listed in
+    pub editor: String,
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    pub version: String,
+    /// Target platform of the package, `None` for universal ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_platform: Option<String>,
+    pub url: String,
+    /// Lowercase hex SHA-256 of the VSIX package
+    pub sha256: String,
+}
+
+impl LockedExtension {
+    /// Records a downloaded package
+    #[must_use]
+    pub fn new(editor: &str, extension: &Extension, vsix_data: &[u8]) -> Self {
+        Self {
+            editor: editor.to_string(),
+            id: extension.unique_identifier(),
+            version: extension.version.clone(),
+            target_platform: extension.target_platform.clone(),
+            url: extension.download_url(extension.target_platform.as_deref()),
+            sha256: sha256_hex(vsix_data),
+        }
+    }
+
+    /// Checks downloaded bytes against the recorded hash
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::ChecksumMismatch` if the hashes differ
+    pub fn verify(&self, vsix_data: &[u8]) -> Result<(), DomainError> {
+        let actual = sha256_hex(vsix_data);
+        if actual.eq_ignore_ascii_case(&self.sha256) {
+            Ok(())
+        } else {
+            Err(DomainError::ChecksumMismatch(format!(
+                "{}@{} from {} has SHA-256 {actual}, the lockfile expects {}",
+                self.id, self.version, self.url, self.sha256
+            )))
+        }
+    }
+
+    /// The extension this entry installs, downloaded from the locked URL
+    #[must_use]
+    pub fn to_extension(&self) -> Extension {
+        let (publisher, name) = self.id.split_once('.').unwrap_or(("", &self.id));
+        Extension {
+            id: self.id.clone(),
+            name: name.to_string(),
+            publisher: publisher.to_string(),
+            version: self.version.clone(),
+            display_name: name.to_string(),
+            description: None,
+            downloads: 0,
+            package_url: Some(self.url.clone()),
+            target_platform: self.target_platform.clone(),
+            dependencies: Vec::new(),
+        }
+    }
+}
+
+impl ExtensionLock {
+    /// Finds the entry for a manifest requirement on the requirement's target
+    /// platform, or this machine's
+    ///
+    /// The package built for that platform is preferred over a universal one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::StaleLock` if the requirement is not locked, is not
+    /// locked for the platform, or its entry no longer satisfies the
+    /// requirement's version
+    pub fn entry_for(
+        &self,
+        editor: &str,
+        requirement: &ExtensionRequirement,
+    ) -> Result<&LockedExtension, DomainError> {
+        let stale = |reason: String| {
+            DomainError::StaleLock(format!("{reason}; run `vsix lock` to update {LOCK_FILE}"))
+        };
+
+        let entries: Vec<&LockedExtension> = self
+            .extensions
+            .iter()
+            .filter(|entry| {
+                entry.editor.eq_ignore_ascii_case(editor)
+                    && entry.id.eq_ignore_ascii_case(&requirement.id)
+            })
+            .collect();
+        if entries.is_empty() {
+            return Err(stale(format!(
+                "{} for {editor} is not locked",
+                requirement.id
+            )));
+        }
+
+        let detected = Architecture::detect();
+        let platform = requirement
+            .selector
+            .platform
+            .as_deref()
+            .or_else(|| detected.to_platform_string());
+        let entry = entries
+            .iter()
+            .find(|entry| {
+                entry.target_platform.is_some() && entry.target_platform.as_deref() == platform
+            })
+            .or_else(|| entries.iter().find(|entry| entry.target_platform.is_none()))
+            .ok_or_else(|| {
+                let locked_platforms: Vec<&str> = entries
+                    .iter()
+                    .filter_map(|entry| entry.target_platform.as_deref())
+                    .collect();
+                stale(format!(
+                    "{} is locked for {}, not {}",
+                    requirement.id,
+                    locked_platforms.join(", "),
+                    platform.unwrap_or("this platform")
+                ))
+            })?;
+
+        if let Some(version) = &requirement.selector.version
+            && *version != entry.version
+        {
+            return Err(stale(format!(
+                "{} is pinned to {version} but locked at {}",
+                requirement.id, entry.version
+            )));
+        }
+
+        Ok(entry)
+    }
+}
+
+/// Returns the lowercase hex SHA-256 of `data`
+#[must_use]
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::VersionSelector;
+
+    fn locked(target_platform: Option<&str>) -> LockedExtension {
+        LockedExtension {
+            editor: "vscode".to_string(),
+            id: "acme.tools".to_string(),
+            version: "1.0.0".to_string(),
+            target_platform: target_platform.map(ToString::to_string),
+            url: "https://example.com/acme.tools-1.0.0.vsix".to_string(),
+            sha256: sha256_hex(b"package"),
+        }
+    }
+
+    fn requirement(version: Option<&str>, platform: Option<&str>) -> ExtensionRequirement {
+        ExtensionRequirement {
+            id: "Acme.Tools".to_string(),
+            selector: VersionSelector {
+                version: version.map(ToString::to_string),
+                platform: platform.map(ToString::to_string),
+                ..VersionSelector::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let entry = locked(None);
+        assert!(entry.verify(b"package").is_ok());
+        assert!(matches!(
+            entry.verify(b"tampered"),
+            Err(DomainError::ChecksumMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_entry_for() {
+        let lock = ExtensionLock {
+            extensions: vec![locked(Some("linux-x64")), locked(Some("win32-x64"))],
+            ..ExtensionLock::default()
+        };
+
+        for platform in ["linux-x64", "win32-x64"] {
+            let entry = lock
+                .entry_for("vscode", &requirement(Some("1.0.0"), Some(platform)))
+                .unwrap();
+            assert_eq!(entry.target_platform.as_deref(), Some(platform));
+        }
+        for (editor, requirement) in [
+            ("cursor", requirement(None, Some("linux-x64"))),
+            ("vscode", requirement(Some("2.0.0"), Some("linux-x64"))),
+            ("vscode", requirement(None, Some("darwin-arm64"))),
+        ] {
+            assert!(matches!(
+                lock.entry_for(editor, &requirement),
+                Err(DomainError::StaleLock(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_entry_for_falls_back_to_universal_package() {
+        let lock = ExtensionLock {
+            extensions: vec![locked(Some("linux-x64")), locked(None)],
+            ..ExtensionLock::default()
+        };
+
+        let entry = |platform| {
+            lock.entry_for("vscode", &requirement(None, Some(platform)))
+                .unwrap()
+                .target_platform
+                .clone()
+        };
+        assert_eq!(entry("linux-x64").as_deref(), Some("linux-x64"));
+        assert_eq!(entry("darwin-arm64"), None);
+    }
+}
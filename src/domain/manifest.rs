@@ -0,0 +1,96 @@
+use crate::domain::{
+    LockedExtension, OutdatedExtension, PlannedAction, ReleaseChannel, VersionSelector,
+};
+
+/// File name `vsix apply` reads when no manifest is given
+pub const MANIFEST_FILE: &str = "vsix.toml";
+
+/// The extensions a `vsix.toml` manifest declares, per editor
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtensionManifest {
+    pub editors: Vec<EditorManifest>,
+}
+
+/// The extensions one editor should have installed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorManifest {
+    /// Editor name as accepted by `--ide`
+    pub editor: String,
+    pub extensions: Vec<ExtensionRequirement>,
+    /// Remove installed extensions the manifest does not list
+    pub prune: bool,
+}
+
+/// An extension a manifest asks for, with optional version constraints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionRequirement {
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    pub selector: VersionSelector,
+}
+
+/// A change `vsix apply` makes to converge an editor to its manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestChange {
+    pub editor: String,
+    pub action: PlannedAction,
+    pub extension_id: String,
+    pub installed_version: Option<String>,
+    /// Version the manifest resolves to, `None` for uninstalls
+    pub version: Option<String>,
+    /// Selector the version was resolved with, used again to install it
+    pub selector: VersionSelector,
+    /// Lockfile entry to install from instead of the marketplace
+    pub locked: Option<LockedExtension>,
+    /// VS Code profile to change instead of the default one
+    pub profile: Option<String>,
+}
+
+impl ManifestChange {
+    /// The change that updates an outdated extension to the version found by
+    /// `vsix outdated`, staying on its release channel
+    #[must_use]
+    pub fn update(editor: &str, outdated: &OutdatedExtension, profile: Option<&str>) -> Self {
+        Self {
+            editor: editor.to_string(),
+            action: PlannedAction::Update,
+            extension_id: outdated.id.clone(),
+            installed_version: Some(outdated.installed_version.clone()),
+            version: Some(outdated.latest_version.clone()),
+            selector: VersionSelector {
+                version: Some(outdated.latest_version.clone()),
+                channel: if outdated.pre_release {
+                    ReleaseChannel::PreRelease
+                } else {
+                    ReleaseChannel::Stable
+                },
+                platform: None,
+                engine: None,
+            },
+            locked: None,
+            profile: profile.map(ToString::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_stays_on_release_channel() {
+        let outdated = OutdatedExtension {
+            id: "golang.go".to_string(),
+            installed_version: "0.40.0".to_string(),
+            latest_version: "0.41.0".to_string(),
+            pre_release: true,
+        };
+
+        let change = ManifestChange::update("vscode", &outdated, Some("Go"));
+        assert_eq!(change.action, PlannedAction::Update);
+        assert_eq!(change.installed_version.as_deref(), Some("0.40.0"));
+        assert_eq!(change.selector.version.as_deref(), Some("0.41.0"));
+        assert_eq!(change.selector.channel, ReleaseChannel::PreRelease);
+        assert_eq!(change.profile.as_deref(), Some("Go"));
+    }
+}
@@ -1,13 +1,27 @@
+pub mod code_profile;
+pub mod diagnostics;
 pub mod entities;
 pub mod errors;
+pub mod ide;
 pub mod installation_strategy;
+pub mod lock;
+pub mod manifest;
 pub mod repositories;
 pub mod sort;
 pub mod value_objects;
+pub mod vsix_package;
+pub mod workspace;
 
+pub use code_profile::*;
+pub use diagnostics::*;
 pub use entities::*;
 pub use errors::*;
+pub use ide::*;
 pub use installation_strategy::*;
+pub use lock::*;
+pub use manifest::*;
 pub use repositories::*;
 pub use sort::*;
 pub use value_objects::*;
+pub use vsix_package::*;
+pub use workspace::*;
@@ -1,9 +1,12 @@
 use crate::domain::entities::{Extension, SearchResult};
 use crate::domain::errors::DomainError;
+use crate::domain::ide::IdeType;
+use crate::domain::installation_strategy::ProfileTarget;
+use crate::domain::value_objects::VersionSelector;
 use async_trait::async_trait;
 
 #[async_trait]
-pub trait ExtensionRepository {
+pub trait ExtensionRepository: Send + Sync {
     async fn search(
         &self,
         query: &str,
@@ -14,6 +17,36 @@ pub trait ExtensionRepository {
         id: &str,
         marketplace_url: Option<&str>,
     ) -> Result<Extension, DomainError>;
+    /// Looks up several extensions by ID in a single marketplace query
+    ///
+    /// `engine` is the VS Code version of the target editor; versions whose
+    /// engine range does not accept it are skipped. Extensions that are not
+    /// found, or have no version the editor accepts, are omitted from the result.
+    async fn get_extensions(
+        &self,
+        ids: &[String],
+        engine: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<Extension>, DomainError>;
+    /// Looks up the version of an extension that `selector` picks
+    ///
+    /// The default implementation only knows the version
+    /// [`get_extension`](Self::get_extension) returns and fails if the selector
+    /// asks for another one.
+    async fn resolve_extension(
+        &self,
+        id: &str,
+        selector: &VersionSelector,
+        marketplace_url: Option<&str>,
+    ) -> Result<Extension, DomainError> {
+        let extension = self.get_extension(id, marketplace_url).await?;
+        match &selector.version {
+            Some(version) if *version != extension.version => {
+                Err(DomainError::ExtensionNotFound(format!("{id}@{version}")))
+            }
+            _ => Ok(extension),
+        }
+    }
     async fn download(
         &self,
         extension: &Extension,
@@ -21,6 +54,40 @@ pub trait ExtensionRepository {
     ) -> Result<Vec<u8>, DomainError>;
 }
 
+/// The editor state that records which installed extensions are disabled
+pub trait ExtensionStateRepository: Send + Sync {
+    /// Returns the IDs of the extensions disabled in an editor, or in one of its
+    /// profiles
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the editor state cannot be read
+    fn disabled_extensions(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+    ) -> Result<Vec<String>, DomainError>;
+
+    /// Disables or enables an extension, returning `false` if it already was in
+    /// that state
+    ///
+    /// The editor overwrites its state when it exits, so the change is refused
+    /// while the editor is running unless `force` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::Locked` if the editor is running or holds a lock on
+    /// its state, and another error if the state cannot be read or written
+    fn set_disabled(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+        extension_id: &str,
+        disabled: bool,
+        force: bool,
+    ) -> Result<bool, DomainError>;
+}
+
 #[async_trait]
 pub trait InstallationRepository {
     async fn install_vscode(&self, vsix_data: &[u8], extension_id: &str)
@@ -66,6 +66,9 @@ mod tests {
                 display_name: "Zebra".to_string(),
                 description: None,
                 downloads: 100,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
             },
             Extension {
                 id: "2".to_string(),
@@ -75,6 +78,9 @@ mod tests {
                 display_name: "Alpha".to_string(),
                 description: None,
                 downloads: 200,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
             },
         ];
 
@@ -98,6 +104,9 @@ mod tests {
                 display_name: "A".to_string(),
                 description: None,
                 downloads: 100,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
             },
             Extension {
                 id: "2".to_string(),
@@ -107,6 +116,9 @@ mod tests {
                 display_name: "B".to_string(),
                 description: None,
                 downloads: 200,
+                package_url: None,
+                target_platform: None,
+                dependencies: Vec::new(),
             },
         ];
 
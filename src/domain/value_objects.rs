@@ -1,4 +1,8 @@
+use serde_json::Value;
+use std::cmp::Ordering;
 use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Architecture {
@@ -15,6 +19,19 @@ pub enum Architecture {
 }
 
 impl Architecture {
+    /// Every platform a package can be built for
+    pub const TARGETS: [Self; 9] = [
+        Self::Win32X64,
+        Self::Win32Arm64,
+        Self::DarwinX64,
+        Self::DarwinArm64,
+        Self::LinuxX64,
+        Self::LinuxArm64,
+        Self::LinuxArmhf,
+        Self::AlpineX64,
+        Self::AlpineArm64,
+    ];
+
     #[must_use]
     pub fn detect() -> Self {
         let os = std::env::consts::OS;
@@ -49,12 +66,317 @@ impl Architecture {
     }
 }
 
+impl FromStr for Architecture {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "win32-x64" => Ok(Architecture::Win32X64),
+            "win32-arm64" => Ok(Architecture::Win32Arm64),
+            "darwin-x64" => Ok(Architecture::DarwinX64),
+            "darwin-arm64" => Ok(Architecture::DarwinArm64),
+            "linux-x64" => Ok(Architecture::LinuxX64),
+            "linux-arm64" => Ok(Architecture::LinuxArm64),
+            "linux-armhf" => Ok(Architecture::LinuxArmhf),
+            "alpine-x64" => Ok(Architecture::AlpineX64),
+            "alpine-arm64" => Ok(Architecture::AlpineArm64),
+            "universal" => Ok(Architecture::Universal),
+            _ => Err(format!("Invalid target platform: {s}")),
+        }
+    }
+}
+
 impl fmt::Display for Architecture {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_platform_string().unwrap_or("universal"))
     }
 }
 
+/// Where an extension runs when the editor is connected to a remote machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionKind {
+    /// Runs in the local editor
+    Ui,
+    /// Runs on the remote server next to the workspace
+    Workspace,
+    /// Runs in the browser
+    Web,
+}
+
+impl ExtensionKind {
+    /// Determines where an extension can run, following VS Code's rules
+    ///
+    /// Kinds declared in `extensionKind` take precedence. Otherwise an extension
+    /// with a `main` entry point runs on the workspace side, a browser-only
+    /// extension is a web extension, and a declarative extension (themes,
+    /// grammars, snippets) can run anywhere.
+    #[must_use]
+    pub fn resolve(declared: Option<&[Self]>, has_main: bool, has_browser: bool) -> Vec<Self> {
+        if let Some(declared) = declared {
+            return declared.to_vec();
+        }
+
+        match (has_main, has_browser) {
+            (true, true) => vec![Self::Workspace, Self::Web],
+            (true, false) => vec![Self::Workspace],
+            (false, true) => vec![Self::Web],
+            (false, false) => vec![Self::Ui, Self::Workspace, Self::Web],
+        }
+    }
+
+    /// Determines where an extension can run from its `package.json`
+    ///
+    /// `extensionKind` may be a single string (older extensions) or an array; unknown
+    /// values are ignored.
+    #[must_use]
+    pub fn from_manifest(manifest: &Value) -> Vec<Self> {
+        let declared: Option<Vec<Self>> = match &manifest["extensionKind"] {
+            Value::String(kind) => Some(kind.parse().into_iter().collect()),
+            Value::Array(kinds) => Some(
+                kinds
+                    .iter()
+                    .filter_map(|kind| kind.as_str()?.parse().ok())
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        Self::resolve(
+            declared.as_deref(),
+            manifest["main"].is_string(),
+            manifest["browser"].is_string(),
+        )
+    }
+}
+
+impl FromStr for ExtensionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ui" => Ok(Self::Ui),
+            "workspace" => Ok(Self::Workspace),
+            "web" => Ok(Self::Web),
+            _ => Err(format!("Invalid extension kind: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for ExtensionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::Ui => "ui",
+            Self::Workspace => "workspace",
+            Self::Web => "web",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+/// Where `vsix install` takes an extension from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// An extension ID (`publisher.name`) looked up in the marketplace
+    Marketplace(String),
+    /// A `.vsix` file on disk
+    File(PathBuf),
+    /// A `.vsix` file downloaded over HTTP(S)
+    Url(String),
+}
+
+impl PackageSource {
+    /// Classifies an `install` argument
+    ///
+    /// `http://` and `https://` arguments are URLs. Arguments ending in `.vsix`
+    /// or containing a path separator are local files, anything else is an
+    /// extension ID.
+    #[must_use]
+    pub fn parse(input: &str) -> Self {
+        let lower = input.to_ascii_lowercase();
+        if lower.starts_with("https://") || lower.starts_with("http://") {
+            Self::Url(input.to_string())
+        } else if lower.ends_with(".vsix") || input.contains('/') || input.contains('\\') {
+            Self::File(PathBuf::from(input))
+        } else {
+            Self::Marketplace(input.to_string())
+        }
+    }
+}
+
+impl fmt::Display for PackageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Marketplace(id) => write!(f, "{id}"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Url(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+/// Which releases of an extension may be installed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    /// Pre-releases are preferred when they are newer than the latest stable
+    /// release
+    PreRelease,
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "pre-release" | "prerelease" => Ok(Self::PreRelease),
+            _ => Err(format!(
+                "Unknown release channel '{s}', expected 'stable' or 'pre-release'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stable => write!(f, "stable"),
+            Self::PreRelease => write!(f, "pre-release"),
+        }
+    }
+}
+
+/// Selects the version of an extension to look up in the marketplace
+///
+/// The default selects the newest stable release built for this machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionSelector {
+    /// Exact version to install, `None` for the newest one
+    pub version: Option<String>,
+    pub channel: ReleaseChannel,
+    /// Target platform such as `linux-x64`, `None` for this machine's platform
+    pub platform: Option<String>,
+    /// VS Code version of the target editor, e.g. `1.95.3`
+    ///
+    /// Versions whose engine range does not accept it are skipped. `None`
+    /// accepts every version.
+    pub engine: Option<String>,
+}
+
+impl VersionSelector {
+    /// Returns whether this selects the newest stable release for this machine
+    #[must_use]
+    pub fn is_latest(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compares two extension version strings
+///
+/// Versions are compared component by component, numerically where both
+/// components are numbers (so `1.10.0` is newer than `1.9.0`). A version with a
+/// `-pre` style suffix sorts before the same version without one.
+#[must_use]
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_release, a_suffix) = a.split_once('-').unwrap_or((a, ""));
+    let (b_release, b_suffix) = b.split_once('-').unwrap_or((b, ""));
+
+    let mut a_parts = a_release.split('.');
+    let mut b_parts = b_release.split('.');
+
+    loop {
+        let ordering = match (a_parts.next(), b_parts.next()) {
+            (None, None) => break,
+            (Some(a_part), None) => compare_component(a_part, "0"),
+            (None, Some(b_part)) => compare_component("0", b_part),
+            (Some(a_part), Some(b_part)) => compare_component(a_part, b_part),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    match (a_suffix.is_empty(), b_suffix.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a_suffix.cmp(b_suffix),
+    }
+}
+
+fn compare_component(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Returns whether an extension's engine range (`engines.vscode`) accepts an
+/// editor's VS Code version
+///
+/// Follows the check VS Code itself makes: `*` accepts every version,
+/// `>=1.80.0` accepts 1.80.0 and newer, `^1.80.0` accepts newer versions with
+/// the same major version (the same minor version for `^0.x`), and a bare
+/// `1.80.0` only that version. An `x` component matches anything, and suffixes
+/// such as `-insider` are ignored. A range that cannot be parsed accepts every
+/// version.
+#[must_use]
+pub fn engine_accepts(range: &str, editor_version: &str) -> bool {
+    let range = range.trim();
+    let (operator, wanted) = if let Some(rest) = range.strip_prefix(">=") {
+        (">=", rest)
+    } else if let Some(rest) = range.strip_prefix('^') {
+        ("^", rest)
+    } else {
+        ("", range)
+    };
+    let (Some(wanted), Some(current)) = (
+        parse_engine_version(wanted.trim()),
+        parse_engine_version(editor_version),
+    ) else {
+        return true;
+    };
+
+    let current = current.map(|part| part.unwrap_or(0));
+    let mut base = wanted.map(|part| part.unwrap_or(0));
+    let mut must_equal = wanted.map(|part| part.is_some());
+
+    match operator {
+        ">=" => return current >= base,
+        "^" if base[0] == 0 => must_equal[2] = false,
+        "^" => must_equal = [must_equal[0], false, false],
+        _ => {}
+    }
+
+    // Editors since 1.0.0 accept ranges for 0.x, unless they name an exact version
+    if current[0] == 1 && base[0] == 0 && must_equal != [true; 3] {
+        base = [1, 0, 0];
+        must_equal = [true, false, false];
+    }
+
+    for ((current, base), must_equal) in current.into_iter().zip(base).zip(must_equal) {
+        match current.cmp(&base) {
+            Ordering::Less => return false,
+            Ordering::Greater => return !must_equal,
+            Ordering::Equal => {}
+        }
+    }
+    true
+}
+
+/// Parses the major, minor and patch version of an engine, `None` for `x`
+fn parse_engine_version(version: &str) -> Option<[Option<u64>; 3]> {
+    let release = version
+        .split_once('-')
+        .map_or(version, |(release, _)| release);
+    let mut parts = release.split('.').map(|part| match part {
+        "x" | "X" | "*" => Some(None),
+        _ => part.parse().ok().map(Some),
+    });
+    Some([parts.next()??, parts.next()??, parts.next()??])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,9 +398,78 @@ mod tests {
         assert_eq!(Architecture::Universal.to_platform_string(), None);
     }
 
+    #[test]
+    fn test_architecture_from_str() {
+        assert_eq!("linux-x64".parse(), Ok(Architecture::LinuxX64));
+        assert_eq!("Darwin-ARM64".parse(), Ok(Architecture::DarwinArm64));
+        assert_eq!("universal".parse(), Ok(Architecture::Universal));
+        assert!("linux-sparc".parse::<Architecture>().is_err());
+    }
+
     #[test]
     fn test_architecture_display() {
         assert_eq!(Architecture::Win32X64.to_string(), "win32-x64");
         assert_eq!(Architecture::Universal.to_string(), "universal");
     }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2024.1.0", "2024.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-beta", "1.0.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_engine_accepts() {
+        assert!(engine_accepts("^1.80.0", "1.95.3"));
+        assert!(engine_accepts("^1.80.0", "1.80.0"));
+        assert!(!engine_accepts("^1.96.0", "1.95.3"));
+        assert!(!engine_accepts("^1.80.0", "2.0.0"));
+        assert!(engine_accepts(">=1.80.0", "2.0.0"));
+        assert!(!engine_accepts(">=1.80.0", "1.79.2"));
+        assert!(engine_accepts("1.95.3", "1.95.3"));
+        assert!(!engine_accepts("1.95.0", "1.95.3"));
+        assert!(engine_accepts("^1.96.0-insider", "1.96.0-insider"));
+        assert!(engine_accepts("1.x.x", "1.95.3"));
+        assert!(engine_accepts("^0.10.0", "1.95.3"));
+        assert!(engine_accepts("*", "1.95.3"));
+        assert!(engine_accepts("not a range", "1.95.3"));
+    }
+
+    #[test]
+    fn test_extension_kind_resolve() {
+        use ExtensionKind::{Ui, Web, Workspace};
+
+        assert_eq!(ExtensionKind::resolve(Some(&[Ui]), true, false), vec![Ui]);
+        assert_eq!(ExtensionKind::resolve(None, true, false), vec![Workspace]);
+        assert_eq!(ExtensionKind::resolve(None, false, true), vec![Web]);
+        assert_eq!(
+            ExtensionKind::resolve(None, false, false),
+            vec![Ui, Workspace, Web]
+        );
+        assert_eq!("workspace".parse(), Ok(Workspace));
+        assert!("remote".parse::<ExtensionKind>().is_err());
+    }
+
+    #[test]
+    fn test_package_source_parse() {
+        assert_eq!(
+            PackageSource::parse("rust-lang.rust-analyzer"),
+            PackageSource::Marketplace("rust-lang.rust-analyzer".to_string())
+        );
+        assert_eq!(
+            PackageSource::parse("ext-1.0.0.VSIX"),
+            PackageSource::File(PathBuf::from("ext-1.0.0.VSIX"))
+        );
+        assert_eq!(
+            PackageSource::parse("./dist/ext"),
+            PackageSource::File(PathBuf::from("./dist/ext"))
+        );
+        assert_eq!(
+            PackageSource::parse("https://ci.example.com/ext.vsix"),
+            PackageSource::Url("https://ci.example.com/ext.vsix".to_string())
+        );
+    }
 }
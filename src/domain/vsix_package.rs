@@ -0,0 +1,413 @@
+use crate::domain::{DomainError, ExtensionKind};
+use serde_json::Value;
+
+/// Property IDs used in `extension.vsixmanifest`
+const ENGINE_PROPERTY: &str = "Microsoft.VisualStudio.Code.Engine";
+const DEPENDENCIES_PROPERTY: &str = "Microsoft.VisualStudio.Code.ExtensionDependencies";
+const PACK_PROPERTY: &str = "Microsoft.VisualStudio.Code.ExtensionPack";
+const KIND_PROPERTY: &str = "Microsoft.VisualStudio.Code.ExtensionKind";
+const PRE_RELEASE_PROPERTY: &str = "Microsoft.VisualStudio.Code.PreRelease";
+
+/// Metadata of a VSIX package, read from its manifests
+///
+/// A VSIX carries two manifests: the extension's `package.json` and the
+/// `extension.vsixmanifest` generated by `vsce`. Values from the vsixmanifest
+/// take precedence, since it is what marketplaces index; `package.json` fills in
+/// anything it does not declare. Localized `%key%` placeholders are resolved from
+/// `package.nls.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VsixPackage {
+    pub publisher: String,
+    pub name: String,
+    pub version: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    /// Target platform for platform-specific packages (e.g. `linux-x64`)
+    pub target_platform: Option<String>,
+    /// Supported VS Code versions (`engines.vscode`, e.g. `^1.80.0`)
+    pub engine: Option<String>,
+    /// Extensions that must be installed for this one to work
+    pub dependencies: Vec<String>,
+    /// Extensions installed together with this one if it is an extension pack
+    pub pack: Vec<String>,
+    /// Where the extension can run when connected to a remote machine
+    pub extension_kind: Vec<ExtensionKind>,
+    pub pre_release: bool,
+    /// Events that load the extension (e.g. `onLanguage:rust`)
+    pub activation_events: Vec<String>,
+    pub contributes: Contributions,
+}
+
+/// What an extension adds to the editor, from `contributes` in `package.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Contributions {
+    /// Command IDs
+    pub commands: Vec<String>,
+    /// Language IDs
+    pub languages: Vec<String>,
+    /// Debugger types
+    pub debuggers: Vec<String>,
+    /// Setting keys
+    pub settings: Vec<String>,
+}
+
+/// A file inside a VSIX archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveFile {
+    pub path: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Whether the file is a native executable or library
+    pub native: bool,
+}
+
+/// A VSIX package and its contents, for review before installing
+#[derive(Debug, Clone)]
+pub struct VsixInspection {
+    pub package: VsixPackage,
+    pub files: Vec<ArchiveFile>,
+}
+
+impl VsixInspection {
+    /// Returns the bundled native executables and libraries
+    pub fn native_binaries(&self) -> impl Iterator<Item = &ArchiveFile> {
+        self.files.iter().filter(|file| file.native)
+    }
+
+    /// Returns the total uncompressed size of the package
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+}
+
+impl VsixPackage {
+    /// Parses the package metadata from the contents of its manifests
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::InvalidExtensionFormat` if a manifest is malformed or
+    /// the package does not declare a publisher, name and version
+    pub fn from_manifests(
+        package_json: &str,
+        vsixmanifest: Option<&str>,
+        nls: Option<&str>,
+    ) -> Result<Self, DomainError> {
+        let manifest: Value = serde_json::from_str(package_json)
+            .map_err(|e| invalid(format!("Invalid package.json: {e}")))?;
+        let nls: Value = match nls {
+            Some(nls) => serde_json::from_str(nls)
+                .map_err(|e| invalid(format!("Invalid package.nls.json: {e}")))?,
+            None => Value::Null,
+        };
+        let xml = vsixmanifest.map(VsixManifest::parse).transpose()?;
+        let xml = xml.unwrap_or_default();
+
+        let string = |key: &str| {
+            manifest[key]
+                .as_str()
+                .map(|value| localize(value, &nls))
+                .filter(|value| !value.is_empty())
+        };
+        let required = |xml_value: Option<String>, key: &str| {
+            xml_value
+                .or_else(|| string(key))
+                .ok_or_else(|| invalid(format!("package.json has no \"{key}\"")))
+        };
+
+        let extension_kind = match xml.property(KIND_PROPERTY) {
+            Some(kinds) => split_list(kinds)
+                .iter()
+                .filter_map(|kind| kind.parse().ok())
+                .collect(),
+            None => ExtensionKind::from_manifest(&manifest),
+        };
+
+        Ok(Self {
+            publisher: required(xml.publisher.clone(), "publisher")?,
+            name: required(xml.name.clone(), "name")?,
+            version: required(xml.version.clone(), "version")?,
+            display_name: xml.display_name.clone().or_else(|| string("displayName")),
+            description: xml.description.clone().or_else(|| string("description")),
+            target_platform: xml.target_platform.clone(),
+            engine: xml
+                .property(ENGINE_PROPERTY)
+                .map(str::to_string)
+                .or_else(|| manifest["engines"]["vscode"].as_str().map(str::to_string)),
+            dependencies: xml
+                .property(DEPENDENCIES_PROPERTY)
+                .map(split_list)
+                .unwrap_or_else(|| string_array(&manifest["extensionDependencies"])),
+            pack: xml
+                .property(PACK_PROPERTY)
+                .map(split_list)
+                .unwrap_or_else(|| string_array(&manifest["extensionPack"])),
+            extension_kind,
+            pre_release: xml
+                .property(PRE_RELEASE_PROPERTY)
+                .is_some_and(|value| value.eq_ignore_ascii_case("true")),
+            activation_events: string_array(&manifest["activationEvents"]),
+            contributes: Contributions::from_manifest(&manifest["contributes"]),
+        })
+    }
+
+    /// Returns the extension ID in `publisher.name` format
+    #[must_use]
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.publisher, self.name)
+    }
+
+    /// Checks that the package is the extension that was asked for
+    ///
+    /// Extension IDs are case-insensitive, as in VS Code.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::InvalidExtensionFormat` if the package declares a
+    /// different `publisher.name`
+    pub fn ensure_identity(&self, expected_id: &str) -> Result<(), DomainError> {
+        if self.id().eq_ignore_ascii_case(expected_id) {
+            Ok(())
+        } else {
+            Err(invalid(format!(
+                "Package identity {} does not match the requested {expected_id}",
+                self.id()
+            )))
+        }
+    }
+}
+
+impl Contributions {
+    fn from_manifest(contributes: &Value) -> Self {
+        let ids = |key: &str, field: &str| -> Vec<String> {
+            contributes[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item[field].as_str().map(str::to_string))
+                .collect()
+        };
+
+        // `configuration` is a single object or an array of sections
+        let sections = match &contributes["configuration"] {
+            Value::Array(sections) => sections.iter().collect(),
+            section => vec![section],
+        };
+        let settings = sections
+            .into_iter()
+            .filter_map(|section| section["properties"].as_object())
+            .flat_map(|properties| properties.keys().cloned())
+            .collect();
+
+        Self {
+            commands: ids("commands", "command"),
+            languages: ids("languages", "id"),
+            debuggers: ids("debuggers", "type"),
+            settings,
+        }
+    }
+}
+
+/// Values read from `extension.vsixmanifest`
+#[derive(Debug, Default)]
+struct VsixManifest {
+    publisher: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+    target_platform: Option<String>,
+    display_name: Option<String>,
+    description: Option<String>,
+    properties: Vec<(String, String)>,
+}
+
+impl VsixManifest {
+    fn parse(xml: &str) -> Result<Self, DomainError> {
+        let document = roxmltree::Document::parse(xml)
+            .map_err(|e| invalid(format!("Invalid extension.vsixmanifest: {e}")))?;
+        let mut manifest = Self::default();
+
+        for node in document.descendants().filter(roxmltree::Node::is_element) {
+            let attribute = |name: &str| node.attribute(name).map(str::to_string);
+            match node.tag_name().name() {
+                "Identity" => {
+                    manifest.publisher = attribute("Publisher");
+                    manifest.name = attribute("Id");
+                    manifest.version = attribute("Version");
+                    manifest.target_platform = attribute("TargetPlatform");
+                }
+                "DisplayName" => manifest.display_name = node.text().map(str::to_string),
+                "Description" => manifest.description = node.text().map(str::to_string),
+                "Property" => {
+                    if let (Some(id), Some(value)) = (attribute("Id"), attribute("Value")) {
+                        manifest.properties.push((id, value));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Returns a non-empty property value
+    fn property(&self, id: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key == id)
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Resolves a `%key%` placeholder from `package.nls.json`
+fn localize(value: &str, nls: &Value) -> String {
+    value
+        .strip_prefix('%')
+        .and_then(|key| key.strip_suffix('%'))
+        .and_then(|key| match &nls[key] {
+            Value::String(text) => Some(text.clone()),
+            // Entries with translator comments: { "message": "...", "comment": [...] }
+            entry => entry["message"].as_str().map(str::to_string),
+        })
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn string_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn invalid(message: String) -> DomainError {
+    DomainError::InvalidExtensionFormat(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE_JSON: &str = r#"{
+        "publisher": "acme",
+        "name": "tools",
+        "version": "2.1.0",
+        "displayName": "%displayName%",
+        "description": "%description%",
+        "engines": { "vscode": "^1.80.0" },
+        "extensionDependencies": ["acme.core"],
+        "main": "./out/extension.js",
+        "activationEvents": ["onLanguage:acme"],
+        "contributes": {
+            "commands": [{ "command": "acme.run", "title": "Run" }],
+            "languages": [{ "id": "acme" }],
+            "debuggers": [{ "type": "acme-debug" }],
+            "configuration": [
+                { "properties": { "acme.path": { "type": "string" } } },
+                { "properties": { "acme.trace": { "type": "boolean" } } }
+            ]
+        }
+    }"#;
+
+    const NLS_JSON: &str = r#"{
+        "displayName": "Acme Tools",
+        "description": { "message": "Tools for Acme", "comment": ["Shown in the gallery"] }
+    }"#;
+
+    const VSIXMANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+        <PackageManifest Version="2.0.0" xmlns="http://schemas.microsoft.com/developer/vsx-schema/2011">
+          <Metadata>
+            <Identity Language="en-US" Id="tools" Version="2.2.0-beta" Publisher="acme" TargetPlatform="linux-x64"/>
+            <DisplayName>Acme Tools</DisplayName>
+            <Description xml:space="preserve">Tools for Acme</Description>
+            <Properties>
+              <Property Id="Microsoft.VisualStudio.Code.Engine" Value="^1.85.0" />
+              <Property Id="Microsoft.VisualStudio.Code.ExtensionDependencies" Value="acme.core" />
+              <Property Id="Microsoft.VisualStudio.Code.ExtensionPack" Value="acme.lint,acme.format" />
+              <Property Id="Microsoft.VisualStudio.Code.ExtensionKind" Value="workspace,web" />
+              <Property Id="Microsoft.VisualStudio.Code.PreRelease" Value="true" />
+            </Properties>
+          </Metadata>
+        </PackageManifest>"#;
+
+    #[test]
+    fn test_parse_package_json() {
+        let package = VsixPackage::from_manifests(PACKAGE_JSON, None, Some(NLS_JSON)).unwrap();
+
+        assert_eq!(package.id(), "acme.tools");
+        assert_eq!(package.version, "2.1.0");
+        assert_eq!(package.display_name.as_deref(), Some("Acme Tools"));
+        assert_eq!(package.description.as_deref(), Some("Tools for Acme"));
+        assert_eq!(package.engine.as_deref(), Some("^1.80.0"));
+        assert_eq!(package.dependencies, vec!["acme.core"]);
+        assert!(package.pack.is_empty());
+        assert_eq!(package.extension_kind, vec![ExtensionKind::Workspace]);
+        assert_eq!(package.target_platform, None);
+        assert!(!package.pre_release);
+        assert_eq!(package.activation_events, vec!["onLanguage:acme"]);
+        assert_eq!(
+            package.contributes,
+            Contributions {
+                commands: vec!["acme.run".to_string()],
+                languages: vec!["acme".to_string()],
+                debuggers: vec!["acme-debug".to_string()],
+                settings: vec!["acme.path".to_string(), "acme.trace".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_vsixmanifest_takes_precedence() {
+        let package =
+            VsixPackage::from_manifests(PACKAGE_JSON, Some(VSIXMANIFEST), Some(NLS_JSON)).unwrap();
+
+        assert_eq!(package.version, "2.2.0-beta");
+        assert_eq!(package.target_platform.as_deref(), Some("linux-x64"));
+        assert_eq!(package.engine.as_deref(), Some("^1.85.0"));
+        assert_eq!(package.pack, vec!["acme.lint", "acme.format"]);
+        assert_eq!(
+            package.extension_kind,
+            vec![ExtensionKind::Workspace, ExtensionKind::Web]
+        );
+        assert!(package.pre_release);
+    }
+
+    #[test]
+    fn test_missing_identity_is_rejected() {
+        let result = VsixPackage::from_manifests(r#"{"name": "tools"}"#, None, None);
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(msg)) if msg.contains("publisher")
+        ));
+
+        let result = VsixPackage::from_manifests(PACKAGE_JSON, Some("<PackageManifest>"), None);
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_ensure_identity() {
+        let package = VsixPackage::from_manifests(PACKAGE_JSON, None, None).unwrap();
+
+        assert!(package.ensure_identity("Acme.Tools").is_ok());
+        assert!(matches!(
+            package.ensure_identity("acme.other"),
+            Err(DomainError::InvalidExtensionFormat(msg)) if msg.contains("acme.other")
+        ));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::domain::InstalledExtension;
+
+/// Extensions a workspace recommends, from `.vscode/extensions.json`, the
+/// `extensions` section of a `.code-workspace` file or a `devcontainer.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceRecommendations {
+    pub recommendations: Vec<String>,
+    /// Extensions the workspace asks not to be recommended
+    pub unwanted: Vec<String>,
+}
+
+impl WorkspaceRecommendations {
+    /// Adds the recommendations of another file, keeping the first spelling of
+    /// each ID
+    pub fn merge(&mut self, other: Self) {
+        for (list, ids) in [
+            (&mut self.recommendations, other.recommendations),
+            (&mut self.unwanted, other.unwanted),
+        ] {
+            for id in ids {
+                if !list.iter().any(|known| known.eq_ignore_ascii_case(&id)) {
+                    list.push(id);
+                }
+            }
+        }
+    }
+
+    /// Returns the recommended extensions that are neither installed nor unwanted
+    #[must_use]
+    pub fn missing(&self, installed: &[InstalledExtension]) -> Vec<String> {
+        self.recommendations
+            .iter()
+            .filter(|id| {
+                !self
+                    .unwanted
+                    .iter()
+                    .any(|unwanted| unwanted.eq_ignore_ascii_case(id))
+                    && !installed
+                        .iter()
+                        .any(|extension| extension.id.eq_ignore_ascii_case(id))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_missing_skips_installed_and_unwanted() {
+        let mut recommendations = WorkspaceRecommendations {
+            recommendations: ids(&["rust-lang.rust-analyzer", "tamasfe.even-better-toml"]),
+            unwanted: Vec::new(),
+        };
+        recommendations.merge(WorkspaceRecommendations {
+            recommendations: ids(&["Rust-Lang.Rust-Analyzer", "ms-python.python"]),
+            unwanted: ids(&["MS-Python.python"]),
+        });
+
+        let installed = vec![InstalledExtension {
+            id: "tamasfe.even-better-toml".to_string(),
+            version: "0.19.2".to_string(),
+            target_platform: None,
+            size: None,
+            installed_at: None,
+            location: None,
+            disabled: false,
+            pre_release: false,
+        }];
+
+        assert_eq!(recommendations.recommendations.len(), 3);
+        assert_eq!(
+            recommendations.missing(&installed),
+            vec!["rust-lang.rust-analyzer"]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::domain::{CodeProfile, DomainError, ProfileExtension};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A `.code-profile` file as exported by the editor
+///
+/// Each resource of the profile is a JSON document stored as a string:
+///
+/// ```json
+/// {
+///   "name": "Rust",
+///   "extensions": "[{\"identifier\":{\"id\":\"rust-lang.rust-analyzer\"},\"version\":\"0.3.2000\",\"preRelease\":false,\"disabled\":false}]"
+/// }
+/// ```
+///
+/// Settings, keybindings and other resources are not read.
+#[derive(Debug, Deserialize, Serialize)]
+struct ProfileFile {
+    #[serde(default)]
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extensions: Option<ExtensionsResource>,
+}
+
+/// The extensions resource, a JSON string in exports but accepted inline too
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ExtensionsResource {
+    Embedded(String),
+    Inline(Vec<ProfileExtensionEntry>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileExtensionEntry {
+    identifier: ExtensionIdentifier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(default)]
+    pre_release: bool,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ExtensionIdentifier {
+    id: String,
+}
+
+/// Reads the extensions of a `.code-profile` file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or its extensions resource
+/// cannot be parsed
+pub fn read_code_profile(path: &Path) -> Result<CodeProfile, DomainError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        DomainError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("{}: {e}", path.display()),
+        ))
+    })?;
+    parse_code_profile(&content)
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))
+}
+
+/// Writes a `.code-profile` file containing only an extensions resource
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written
+pub fn write_code_profile(path: &Path, profile: &CodeProfile) -> Result<(), DomainError> {
+    let serialize_error =
+        |e: serde_json::Error| DomainError::ParseError(format!("Cannot serialize profile: {e}"));
+    let entries: Vec<_> = profile
+        .extensions
+        .iter()
+        .map(|ext| ProfileExtensionEntry {
+            identifier: ExtensionIdentifier { id: ext.id.clone() },
+            version: ext.version.clone(),
+            pre_release: ext.pre_release,
+            disabled: ext.disabled,
+        })
+        .collect();
+    let file = ProfileFile {
+        name: profile.name.clone(),
+        extensions: Some(ExtensionsResource::Embedded(
+            serde_json::to_string(&entries).map_err(serialize_error)?,
+        )),
+    };
+    fs::write(
+        path,
+        serde_json::to_string_pretty(&file).map_err(serialize_error)?,
+    )?;
+    Ok(())
+}
+
+fn parse_code_profile(content: &str) -> Result<CodeProfile, serde_json::Error> {
+    let file: ProfileFile = serde_json::from_str(content)?;
+    let entries = match file.extensions {
+        Some(ExtensionsResource::Embedded(json)) => serde_json::from_str(&json)?,
+        Some(ExtensionsResource::Inline(entries)) => entries,
+        None => Vec::new(),
+    };
+
+    Ok(CodeProfile {
+        name: file.name,
+        extensions: entries
+            .into_iter()
+            .map(|entry| ProfileExtension {
+                id: entry.identifier.id,
+                version: entry.version,
+                pre_release: entry.pre_release,
+                disabled: entry.disabled,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_code_profile_export() {
+        let profile = parse_code_profile(
+            r#"{
+                "name": "Python",
+                "settings": "{\"settings\":\"{}\"}",
+                "extensions": "[{\"identifier\":{\"id\":\"ms-python.python\",\"uuid\":\"f1f59ae4\"},\"displayName\":\"Python\",\"preRelease\":true,\"disabled\":true},{\"identifier\":{\"id\":\"ms-python.vscode-pylance\"},\"version\":\"2024.2.1\"}]"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(profile.name, "Python");
+        assert_eq!(
+            profile.extensions,
+            vec![
+                ProfileExtension {
+                    id: "ms-python.python".to_string(),
+                    version: None,
+                    pre_release: true,
+                    disabled: true,
+                },
+                ProfileExtension {
+                    id: "ms-python.vscode-pylance".to_string(),
+                    version: Some("2024.2.1".to_string()),
+                    pre_release: false,
+                    disabled: false,
+                },
+            ]
+        );
+
+        assert!(
+            parse_code_profile(r#"{"name":"Empty"}"#)
+                .unwrap()
+                .extensions
+                .is_empty()
+        );
+        assert!(parse_code_profile(r#"{"extensions":"not json"}"#).is_err());
+    }
+
+    #[test]
+    fn test_write_and_read_code_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("rust.code-profile");
+        let profile = CodeProfile {
+            name: "Rust".to_string(),
+            extensions: vec![ProfileExtension {
+                id: "rust-lang.rust-analyzer".to_string(),
+                version: Some("0.3.2000".to_string()),
+                pre_release: false,
+                disabled: true,
+            }],
+        };
+        write_code_profile(&path, &profile).unwrap();
+
+        let content: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(content["extensions"].is_string());
+        assert_eq!(read_code_profile(&path).unwrap(), profile);
+    }
+}
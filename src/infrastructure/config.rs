@@ -0,0 +1,167 @@
+use crate::domain::{DomainError, ExtensionsDirectory, IdeRegistry, IdeType};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing to an alternative configuration file
+pub const CONFIG_ENV_VAR: &str = "VSIX_CONFIG";
+
+/// User configuration, read from `<config dir>/vsix/config.toml`
+///
+/// ```toml
+/// [[editors]]
+/// name = "my-code"
+/// display_name = "My Code"
+/// commands = ["my-code"]
+/// extensions_dir = "~/.my-code/extensions"
+/// gallery_url = "https://open-vsx.org/vscode/gallery"
+/// server_extensions_dir = "~/.my-code-server/extensions"
+/// user_data_dir = { linux = ".config/MyCode", macos = "Library/Application Support/MyCode", windows = "AppData/Roaming/MyCode" }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct VsixConfig {
+    /// Additional IDEs, or overrides of built-in ones with the same name
+    #[serde(default)]
+    pub editors: Vec<EditorConfig>,
+}
+
+/// A user-defined IDE entry
+#[derive(Debug, Deserialize)]
+pub struct EditorConfig {
+    pub name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    pub extensions_dir: ExtensionsDirConfig,
+    pub gallery_url: Option<String>,
+    pub server_extensions_dir: Option<String>,
+    pub user_data_dir: Option<ExtensionsDirConfig>,
+}
+
+/// Directory of a user-defined IDE, either shared or per OS
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ExtensionsDirConfig {
+    Same(String),
+    PerOs(ExtensionsDirectory),
+}
+
+impl From<ExtensionsDirConfig> for ExtensionsDirectory {
+    fn from(config: ExtensionsDirConfig) -> Self {
+        match config {
+            ExtensionsDirConfig::Same(path) => Self::same(&path),
+            ExtensionsDirConfig::PerOs(dirs) => dirs,
+        }
+    }
+}
+
+impl From<EditorConfig> for IdeType {
+    fn from(config: EditorConfig) -> Self {
+        Self {
+            display_name: config.display_name.unwrap_or_else(|| config.name.clone()),
+            commands: config.commands,
+            extensions_dir: config.extensions_dir.into(),
+            user_data_dir: config.user_data_dir.map(Into::into),
+            gallery_url: config.gallery_url,
+            server_extensions_dir: config.server_extensions_dir,
+            name: config.name,
+        }
+    }
+}
+
+impl VsixConfig {
+    /// Loads the user configuration
+    ///
+    /// A missing configuration file yields the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed
+    pub fn load() -> Result<Self, DomainError> {
+        match config_path() {
+            Some(path) if path.exists() => Self::load_file(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the configuration from an explicit file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub fn load_file(path: &Path) -> Result<Self, DomainError> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))
+    }
+
+    /// Builds the IDE registry from the built-in IDEs and the configured ones
+    #[must_use]
+    pub fn into_ide_registry(self) -> IdeRegistry {
+        IdeRegistry::builtin()
+            .with_user_entries(self.editors.into_iter().map(IdeType::from).collect())
+    }
+}
+
+/// Returns the path of the user configuration file
+#[must_use]
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV_VAR) {
+        return Some(PathBuf::from(path));
+    }
+
+    dirs::config_dir().map(|dir| dir.join("vsix").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_user_editors() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+                [[editors]]
+                name = "my-code"
+                commands = ["my-code"]
+                extensions_dir = "~/.my-code/extensions"
+                gallery_url = "https://open-vsx.org/vscode/gallery"
+
+                [[editors]]
+                name = "cursor"
+                display_name = "Cursor"
+                commands = ["cursor"]
+                extensions_dir = { linux = ".cursor/extensions", macos = ".cursor/extensions", windows = ".cursor/extensions" }
+            "#,
+        )
+        .unwrap();
+
+        let registry = VsixConfig::load_file(&path).unwrap().into_ide_registry();
+
+        let custom = registry.get("my-code").unwrap();
+        assert_eq!(custom.display_name(), "my-code");
+        assert_eq!(
+            custom.extensions_dir.resolve(Path::new("/home/user")),
+            PathBuf::from("/home/user/.my-code/extensions")
+        );
+
+        let cursor = registry.get("cursor").unwrap();
+        assert_eq!(cursor.extensions_dir.linux, ".cursor/extensions");
+    }
+
+    #[test]
+    fn test_load_invalid_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[[editors]]\nname = 42\n").unwrap();
+
+        assert!(matches!(
+            VsixConfig::load_file(&path),
+            Err(DomainError::ParseError(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::domain::DomainError;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// File name of the registry editors keep inside an extensions directory
+pub const REGISTRY_FILE_NAME: &str = "extensions.json";
+
+/// The `extensions.json` registry that VS Code based editors keep next to the
+/// installed extension folders.
+///
+/// Entries are kept as raw JSON so that fields this crate does not know about
+/// survive a load/save round trip untouched.
+pub struct ExtensionsRegistry {
+    path: PathBuf,
+    entries: Vec<Value>,
+}
+
+impl ExtensionsRegistry {
+    /// Loads the registry of an extensions directory
+    ///
+    /// A missing registry file is treated as an empty registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a JSON array
+    pub fn load(extensions_dir: &Path) -> Result<Self, DomainError> {
+        Self::load_file(extensions_dir.join(REGISTRY_FILE_NAME))
+    }
+
+    /// Loads a registry from an explicit file path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a JSON array
+    pub fn load_file(path: PathBuf) -> Result<Self, DomainError> {
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                entries: Vec::new(),
+            });
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let entries = if content.trim().is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str::<Vec<Value>>(&content)
+                .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))?
+        };
+
+        Ok(Self { path, entries })
+    }
+
+    /// Returns all raw registry entries
+    #[must_use]
+    pub fn entries(&self) -> &[Value] {
+        &self.entries
+    }
+
+    /// Removes all entries for the given extension ID (case-insensitive) and returns them
+    pub fn remove(&mut self, extension_id: &str) -> Vec<Value> {
+        let (removed, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|entry| {
+                entry_id(entry).is_some_and(|id| id.eq_ignore_ascii_case(extension_id))
+            });
+        self.entries = kept;
+        removed
+    }
+
+    /// Removes the entries pointing at a folder (case-insensitive) and returns them
+    pub fn remove_location(&mut self, folder_name: &str) -> Vec<Value> {
+        let (removed, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|entry| {
+                entry_relative_location(entry)
+                    .is_some_and(|location| location.eq_ignore_ascii_case(folder_name))
+            });
+        self.entries = kept;
+        removed
+    }
+
+    /// Adds an entry, replacing any existing entries for the same extension
+    pub fn upsert(&mut self, entry: Value) {
+        if let Some(id) = entry_id(&entry).map(ToString::to_string) {
+            self.remove(&id);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Writes the registry back to disk
+    ///
+    /// The new content is written to a temporary file first and renamed over the
+    /// registry, so a crash never leaves a truncated file behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written
+    pub fn save(&self) -> Result<(), DomainError> {
+        let content = serde_json::to_string(&self.entries)
+            .map_err(|e| DomainError::ParseError(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Builds a registry entry for an extension folder inside `extensions_dir`
+#[must_use]
+pub fn new_entry(extension_id: &str, version: &str, extension_dir: &Path) -> Value {
+    let installed_timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    let path = extension_dir.to_string_lossy().replace('\\', "/");
+
+    json!({
+        "identifier": { "id": extension_id },
+        "version": version,
+        "location": {
+            "$mid": 1,
+            "path": path,
+            "scheme": "file"
+        },
+        "relativeLocation": extension_dir.file_name().map(|name| name.to_string_lossy()),
+        "metadata": {
+            "installedTimestamp": installed_timestamp,
+            "source": "vsix"
+        }
+    })
+}
+
+/// Returns the `publisher.name` identifier of a registry entry
+#[must_use]
+pub fn entry_id(entry: &Value) -> Option<&str> {
+    entry["identifier"]["id"].as_str()
+}
+
+/// Returns the folder of a registry entry, relative to the extensions directory
+///
+/// Only single path components are accepted so that a tampered registry cannot
+/// point outside the extensions directory.
+#[must_use]
+pub fn entry_relative_location(entry: &Value) -> Option<&str> {
+    let location = entry["relativeLocation"].as_str()?;
+    let mut components = Path::new(location).components();
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Some(location),
+        _ => None,
+    }
+}
+
+/// Finds the folders of an extension inside an extensions directory
+///
+/// Matches both the `publisher.name` layout used by this crate and the
+/// `publisher.name-1.2.3[-platform]` layout used by the editors themselves.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be read
+pub fn find_extension_dirs(
+    extensions_dir: &Path,
+    extension_id: &str,
+) -> Result<Vec<PathBuf>, DomainError> {
+    if !extensions_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let id = extension_id.to_lowercase();
+    let versioned_prefix = format!("{id}-");
+    let mut dirs = Vec::new();
+
+    for entry in fs::read_dir(extensions_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        let is_versioned = name
+            .strip_prefix(&versioned_prefix)
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+
+        if name == id || is_versioned {
+            dirs.push(entry.path());
+        }
+    }
+
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_preserves_other_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let entries = json!([
+            {
+                "identifier": { "id": "ms-python.python" },
+                "version": "2024.1.0",
+                "relativeLocation": "ms-python.python-2024.1.0",
+                "metadata": { "installedTimestamp": 1 }
+            },
+            {
+                "identifier": { "id": "rust-lang.rust-analyzer" },
+                "version": "0.3.0",
+                "relativeLocation": "rust-lang.rust-analyzer-0.3.0"
+            }
+        ]);
+        fs::write(
+            temp_dir.path().join(REGISTRY_FILE_NAME),
+            entries.to_string(),
+        )
+        .unwrap();
+
+        let mut registry = ExtensionsRegistry::load(temp_dir.path()).unwrap();
+        let removed = registry.remove("MS-Python.Python");
+        assert_eq!(removed.len(), 1);
+        registry.save().unwrap();
+
+        let reloaded = ExtensionsRegistry::load(temp_dir.path()).unwrap();
+        assert_eq!(reloaded.entries().len(), 1);
+        assert_eq!(
+            entry_id(&reloaded.entries()[0]),
+            Some("rust-lang.rust-analyzer")
+        );
+    }
+
+    #[test]
+    fn test_entry_relative_location_rejects_traversal() {
+        assert_eq!(
+            entry_relative_location(&json!({ "relativeLocation": "pub.ext-1.0.0" })),
+            Some("pub.ext-1.0.0")
+        );
+        assert_eq!(
+            entry_relative_location(&json!({ "relativeLocation": "../../home" })),
+            None
+        );
+        assert_eq!(
+            entry_relative_location(&json!({ "relativeLocation": "/etc" })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_extension_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in [
+            "pub.ext",
+            "pub.ext-1.0.0",
+            "pub.ext-2.0.0-linux-x64",
+            "pub.ext-extra",
+            "pub.other-1.0.0",
+        ] {
+            fs::create_dir(temp_dir.path().join(name)).unwrap();
+        }
+
+        let dirs = find_extension_dirs(temp_dir.path(), "pub.ext").unwrap();
+        let names: Vec<_> = dirs
+            .iter()
+            .map(|d| d.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            ["pub.ext", "pub.ext-1.0.0", "pub.ext-2.0.0-linux-x64"]
+        );
+    }
+}
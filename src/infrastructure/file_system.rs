@@ -1,8 +1,8 @@
+use super::vsix_archive::extract_vsix;
 use crate::domain::{DomainError, InstallationRepository};
 use async_trait::async_trait;
 use std::fs;
-use std::path::{Path, PathBuf};
-use zip::ZipArchive;
+use std::path::PathBuf;
 
 pub struct FileSystemRepository;
 
@@ -17,32 +17,6 @@ impl FileSystemRepository {
     pub fn new() -> Self {
         Self
     }
-
-    fn extract_vsix(vsix_data: &[u8], target_dir: &Path) -> Result<(), DomainError> {
-        let cursor = std::io::Cursor::new(vsix_data);
-        let mut archive = ZipArchive::new(cursor)
-            .map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))?;
-
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|e| DomainError::IoError(std::io::Error::other(e)))?;
-
-            let outpath = target_dir.join(file.name());
-
-            if file.name().ends_with('/') {
-                fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(parent) = outpath.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                let mut outfile = fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
-            }
-        }
-
-        Ok(())
-    }
 }
 
 #[async_trait]
@@ -60,7 +34,7 @@ impl InstallationRepository for FileSystemRepository {
         }
 
         fs::create_dir_all(&target_dir)?;
-        Self::extract_vsix(vsix_data, &target_dir)?;
+        extract_vsix(vsix_data, &target_dir)?;
 
         Ok(())
     }
@@ -78,7 +52,7 @@ impl InstallationRepository for FileSystemRepository {
         }
 
         fs::create_dir_all(&target_dir)?;
-        Self::extract_vsix(vsix_data, &target_dir)?;
+        extract_vsix(vsix_data, &target_dir)?;
 
         Ok(())
     }
@@ -0,0 +1,462 @@
+use crate::domain::{DomainError, ExtensionStateRepository, IdeType, ProfileTarget};
+use rusqlite::{Connection, ErrorCode, OpenFlags, OptionalExtension, TransactionBehavior};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File name of the SQLite database holding an editor's global state
+pub const STATE_DATABASE_FILE: &str = "state.vscdb";
+
+/// Global state key listing the disabled extensions
+pub const DISABLED_EXTENSIONS_KEY: &str = "extensionsIdentifiers/disabled";
+
+/// File the editor's main process keeps in the user data directory while running
+pub const EDITOR_LOCK_FILE: &str = "code.lock";
+
+/// How long a forced write waits for another process to release the database
+const FORCED_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reads and writes the disabled extensions in an editor's `state.vscdb`
+///
+/// The default profile keeps its state in `User/globalStorage/state.vscdb`, other
+/// profiles in `User/profiles/<id>/globalStorage/state.vscdb`.
+pub struct GlobalStateStore;
+
+impl GlobalStateStore {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn user_data_dir(ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            DomainError::DirectoryNotFound("Home directory not found".to_string())
+        })?;
+        ide_type.user_data_directory(&home).ok_or_else(|| {
+            DomainError::Unknown(format!(
+                "{} does not keep extension state",
+                ide_type.display_name()
+            ))
+        })
+    }
+
+    /// Returns the state database of the editor or profile
+    fn database_path(
+        user_data_dir: &Path,
+        profile: Option<&ProfileTarget>,
+    ) -> Result<PathBuf, DomainError> {
+        let state_dir = match profile {
+            Some(profile) => profile
+                .registry_path
+                .parent()
+                .ok_or_else(|| {
+                    DomainError::DirectoryNotFound(profile.registry_path.display().to_string())
+                })?
+                .to_path_buf(),
+            None => user_data_dir.join("User"),
+        };
+
+        Ok(state_dir.join("globalStorage").join(STATE_DATABASE_FILE))
+    }
+}
+
+impl Default for GlobalStateStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExtensionStateRepository for GlobalStateStore {
+    fn disabled_extensions(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+    ) -> Result<Vec<String>, DomainError> {
+        // Editors without a user data directory cannot have disabled extensions
+        let Ok(user_data_dir) = Self::user_data_dir(ide_type) else {
+            return Ok(Vec::new());
+        };
+        read_disabled_extensions(&Self::database_path(&user_data_dir, profile)?)
+    }
+
+    fn set_disabled(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+        extension_id: &str,
+        disabled: bool,
+        force: bool,
+    ) -> Result<bool, DomainError> {
+        let user_data_dir = Self::user_data_dir(ide_type)?;
+        if !user_data_dir.is_dir() {
+            return Err(DomainError::DirectoryNotFound(format!(
+                "{} user data directory {}",
+                ide_type.display_name(),
+                user_data_dir.display()
+            )));
+        }
+
+        let lock_file = user_data_dir.join(EDITOR_LOCK_FILE);
+        if !force && is_editor_running(&lock_file) {
+            return Err(DomainError::Locked(format!(
+                "{} is running ({} exists) and would overwrite the change when it exits; close it, or use --force if it is not running",
+                ide_type.display_name(),
+                lock_file.display()
+            )));
+        }
+
+        let database = Self::database_path(&user_data_dir, profile)?;
+        write_disabled_state(&database, extension_id, disabled, force)
+    }
+}
+
+/// Whether the editor that wrote a lock file is still running
+///
+/// The lock file holds the PID of the editor's main process. A lock file left
+/// behind by a crash names a process that no longer exists. If the PID cannot be
+/// read or checked, the editor is assumed to be running.
+fn is_editor_running(lock_file: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(lock_file) else {
+        return lock_file.exists();
+    };
+    match content.trim().parse::<u32>() {
+        Ok(pid) => is_process_running(pid).unwrap_or(true),
+        Err(_) => true,
+    }
+}
+
+/// Checks whether a process exists, `None` if that cannot be determined
+fn is_process_running(pid: u32) -> Option<bool> {
+    if cfg!(target_os = "linux") {
+        return Some(Path::new("/proc").join(pid.to_string()).exists());
+    }
+
+    if cfg!(windows) {
+        let output = std::process::Command::new("tasklist")
+            .args(["/NH", "/FO", "CSV", "/FI", &format!("PID eq {pid}")])
+            .output()
+            .ok()?;
+        let pid = format!("\"{pid}\"");
+        return Some(String::from_utf8_lossy(&output.stdout).contains(&pid));
+    }
+
+    // `kill -0` only checks whether the process exists; it also fails for
+    // processes of other users, which the editor of this user is not
+    let status = std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .ok()?;
+    Some(status.success())
+}
+
+impl ExtensionStateRepository for &GlobalStateStore {
+    fn disabled_extensions(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+    ) -> Result<Vec<String>, DomainError> {
+        (**self).disabled_extensions(ide_type, profile)
+    }
+
+    fn set_disabled(
+        &self,
+        ide_type: &IdeType,
+        profile: Option<&ProfileTarget>,
+        extension_id: &str,
+        disabled: bool,
+        force: bool,
+    ) -> Result<bool, DomainError> {
+        (**self).set_disabled(ide_type, profile, extension_id, disabled, force)
+    }
+}
+
+/// Reads the IDs of the disabled extensions from a state database
+///
+/// A missing database means nothing has been disabled yet.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or the stored list is not
+/// valid JSON
+pub fn read_disabled_extensions(database: &Path) -> Result<Vec<String>, DomainError> {
+    if !database.is_file() {
+        return Ok(Vec::new());
+    }
+
+    let connection = Connection::open_with_flags(
+        database,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| state_error(database, &e))?;
+    let entries = read_entries(&connection).map_err(|e| state_error(database, &e))?;
+
+    parse_entries(database, entries.as_deref()).map(|entries| {
+        entries
+            .iter()
+            .filter_map(|entry| entry["id"].as_str().map(ToString::to_string))
+            .collect()
+    })
+}
+
+/// Adds an extension to, or removes it from, the disabled list of a state database
+///
+/// The update runs in a write transaction, so it fails instead of interleaving
+/// with another writer. With `wait` set, a busy database is retried for a few
+/// seconds first. Entries for other extensions, including their `uuid`s, are kept
+/// as they are. The database is created if the editor has not created it yet.
+///
+/// # Errors
+///
+/// Returns `DomainError::Locked` if another process holds the database, and
+/// another error if it cannot be read or written
+pub fn write_disabled_state(
+    database: &Path,
+    extension_id: &str,
+    disabled: bool,
+    wait: bool,
+) -> Result<bool, DomainError> {
+    if let Some(parent) = database.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut connection = Connection::open(database).map_err(|e| state_error(database, &e))?;
+    connection
+        .busy_timeout(if wait {
+            FORCED_BUSY_TIMEOUT
+        } else {
+            Duration::ZERO
+        })
+        .map_err(|e| state_error(database, &e))?;
+
+    let transaction = connection
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| state_error(database, &e))?;
+    transaction
+        .execute(
+            "CREATE TABLE IF NOT EXISTS ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)",
+            [],
+        )
+        .map_err(|e| state_error(database, &e))?;
+
+    let entries = read_entries(&transaction).map_err(|e| state_error(database, &e))?;
+    let mut entries = parse_entries(database, entries.as_deref())?;
+    let is_disabled = |entry: &Value| {
+        entry["id"]
+            .as_str()
+            .is_some_and(|id| id.eq_ignore_ascii_case(extension_id))
+    };
+
+    let was_disabled = entries.iter().any(is_disabled);
+    if was_disabled == disabled {
+        return Ok(false);
+    }
+
+    if disabled {
+        entries.push(json!({ "id": extension_id.to_lowercase() }));
+    } else {
+        entries.retain(|entry| !is_disabled(entry));
+    }
+
+    // The editor removes the key rather than storing an empty list
+    if entries.is_empty() {
+        transaction.execute(
+            "DELETE FROM ItemTable WHERE key = ?1",
+            [DISABLED_EXTENSIONS_KEY],
+        )
+    } else {
+        transaction.execute(
+            "INSERT INTO ItemTable (key, value) VALUES (?1, ?2)",
+            [DISABLED_EXTENSIONS_KEY, &Value::Array(entries).to_string()],
+        )
+    }
+    .map_err(|e| state_error(database, &e))?;
+    transaction
+        .commit()
+        .map_err(|e| state_error(database, &e))?;
+
+    Ok(true)
+}
+
+/// Reads the raw disabled list, which editors store as text or as a blob
+fn read_entries(connection: &Connection) -> rusqlite::Result<Option<String>> {
+    connection
+        .query_row(
+            "SELECT value FROM ItemTable WHERE key = ?1",
+            [DISABLED_EXTENSIONS_KEY],
+            |row| {
+                let value: rusqlite::types::Value = row.get(0)?;
+                Ok(match value {
+                    rusqlite::types::Value::Text(text) => Some(text),
+                    rusqlite::types::Value::Blob(bytes) => {
+                        Some(String::from_utf8_lossy(&bytes).into_owned())
+                    }
+                    _ => None,
+                })
+            },
+        )
+        .optional()
+        .map(Option::flatten)
+        .or_else(|e| match e {
+            // The editor has not written any state yet
+            rusqlite::Error::SqliteFailure(_, Some(message))
+                if message.contains("no such table") =>
+            {
+                Ok(None)
+            }
+            e => Err(e),
+        })
+}
+
+fn parse_entries(database: &Path, entries: Option<&str>) -> Result<Vec<Value>, DomainError> {
+    match entries {
+        Some(entries) if !entries.trim().is_empty() => serde_json::from_str(entries).map_err(|e| {
+            DomainError::ParseError(format!(
+                "Invalid {DISABLED_EXTENSIONS_KEY} in {}: {e}",
+                database.display()
+            ))
+        }),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn state_error(database: &Path, error: &rusqlite::Error) -> DomainError {
+    match error.sqlite_error_code() {
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => DomainError::Locked(format!(
+            "{} is in use by another process; close the editor or use --force",
+            database.display()
+        )),
+        _ => DomainError::Unknown(format!("{}: {error}", database.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn disabled_entries(database: &Path) -> String {
+        let connection = Connection::open(database).unwrap();
+        connection
+            .query_row(
+                "SELECT value FROM ItemTable WHERE key = ?1",
+                [DISABLED_EXTENSIONS_KEY],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_disable_and_enable_keeps_other_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = temp_dir
+            .path()
+            .join("globalStorage")
+            .join(STATE_DATABASE_FILE);
+        assert!(read_disabled_extensions(&database).unwrap().is_empty());
+
+        let connection = {
+            std::fs::create_dir_all(database.parent().unwrap()).unwrap();
+            Connection::open(&database).unwrap()
+        };
+        connection
+            .execute(
+                "CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)",
+                [],
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT INTO ItemTable (key, value) VALUES (?1, ?2)",
+                [
+                    DISABLED_EXTENSIONS_KEY,
+                    r#"[{"id":"github.copilot","uuid":"23c4aeee-f844-43cd-b53e-1113e483f1a6"}]"#,
+                ],
+            )
+            .unwrap();
+        drop(connection);
+
+        assert!(write_disabled_state(&database, "Rust-Lang.Rust-Analyzer", true, false).unwrap());
+        assert!(!write_disabled_state(&database, "rust-lang.rust-analyzer", true, false).unwrap());
+        assert_eq!(
+            read_disabled_extensions(&database).unwrap(),
+            vec!["github.copilot", "rust-lang.rust-analyzer"]
+        );
+        assert!(disabled_entries(&database).contains("23c4aeee-f844-43cd-b53e-1113e483f1a6"));
+
+        assert!(write_disabled_state(&database, "GitHub.Copilot", false, false).unwrap());
+        assert!(write_disabled_state(&database, "rust-lang.rust-analyzer", false, false).unwrap());
+        assert!(!write_disabled_state(&database, "rust-lang.rust-analyzer", false, false).unwrap());
+        assert!(read_disabled_extensions(&database).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_refuses_locked_database() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = temp_dir.path().join(STATE_DATABASE_FILE);
+        write_disabled_state(&database, "acme.tools", true, false).unwrap();
+
+        let mut holder = Connection::open(&database).unwrap();
+        let transaction = holder
+            .transaction_with_behavior(TransactionBehavior::Exclusive)
+            .unwrap();
+
+        let result = write_disabled_state(&database, "acme.other", true, false);
+        assert!(matches!(result, Err(DomainError::Locked(_))));
+
+        transaction.rollback().unwrap();
+        assert!(write_disabled_state(&database, "acme.other", true, false).unwrap());
+    }
+
+    #[test]
+    fn test_set_disabled_refuses_running_editor() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut ide_type = IdeType::vscode();
+        ide_type.user_data_dir = Some(crate::domain::ExtensionsDirectory::same(
+            temp_dir.path().to_str().unwrap(),
+        ));
+        std::fs::write(
+            temp_dir.path().join(EDITOR_LOCK_FILE),
+            std::process::id().to_string(),
+        )
+        .unwrap();
+
+        let store = GlobalStateStore::new();
+        let result = store.set_disabled(&ide_type, None, "acme.tools", true, false);
+        assert!(matches!(result, Err(DomainError::Locked(msg)) if msg.contains("--force")));
+
+        assert!(
+            store
+                .set_disabled(&ide_type, None, "acme.tools", true, true)
+                .unwrap()
+        );
+        assert_eq!(
+            store.disabled_extensions(&ide_type, None).unwrap(),
+            vec!["acme.tools"]
+        );
+        assert!(
+            temp_dir
+                .path()
+                .join("User/globalStorage")
+                .join(STATE_DATABASE_FILE)
+                .is_file()
+        );
+    }
+
+    #[test]
+    fn test_set_disabled_ignores_stale_lock_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut ide_type = IdeType::vscode();
+        ide_type.user_data_dir = Some(crate::domain::ExtensionsDirectory::same(
+            temp_dir.path().to_str().unwrap(),
+        ));
+        // Left behind by an editor that crashed; no process has this PID
+        std::fs::write(temp_dir.path().join(EDITOR_LOCK_FILE), u32::MAX.to_string()).unwrap();
+
+        let store = GlobalStateStore::new();
+        assert!(
+            store
+                .set_disabled(&ide_type, None, "acme.tools", true, false)
+                .unwrap()
+        );
+    }
+}
@@ -1,20 +1,89 @@
+use super::extensions_registry::{
+    ExtensionsRegistry, entry_id, entry_relative_location, find_extension_dirs, new_entry,
+};
+use super::installed_extensions::{
+    parse_cli_listing, read_package_json, scan_extensions_directory,
+};
+use super::profiles::{DEFAULT_PROFILE_NAME, read_profiles};
+use super::vsix_archive::{extract_vsix, read_vsix_package};
 use crate::domain::{
-    DomainError, IdeType, InstallationDetector, InstallationExecutor, InstallationMethod,
-    InstallationStrategy,
+    DirectorySource, DomainError, ExtensionKind, IdeType, InstallationDetector,
+    InstallationExecutor, InstallationMethod, InstallationStrategy, InstalledExtension,
+    ProfileTarget, VsixPackage,
 };
 use async_trait::async_trait;
+use std::ffi::OsStr;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use zip::ZipArchive;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tempfile::TempPath;
+use tokio::process::Command;
+
+/// Environment variable VS Code reads a custom extensions directory from
+pub const EXTENSIONS_DIR_ENV_VAR: &str = "VSCODE_EXTENSIONS";
+
+/// Environment variable pointing at the data directory of a portable install
+pub const PORTABLE_ENV_VAR: &str = "VSCODE_PORTABLE";
+
+/// Time an IDE CLI invocation may take before it is killed
+pub const DEFAULT_CLI_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// Default implementation of the installation detector
-pub struct SystemInstallationDetector;
+///
+/// On machines reached through Remote-SSH or dev containers, the IDE's server
+/// (e.g. `~/.vscode-server/extensions`) is used when it exists and no local
+/// installation does. Server mode forces the server directory.
+///
+/// A custom extensions directory (`--extensions-dir`, `VSCODE_EXTENSIONS`) or a
+/// portable install (`VSCODE_PORTABLE`, or a `data` folder next to the IDE's
+/// binary) takes precedence over the default location.
+pub struct SystemInstallationDetector {
+    server_target: bool,
+    extensions_dir: Option<PathBuf>,
+}
 
 impl SystemInstallationDetector {
     pub fn new() -> Self {
-        Self
+        Self {
+            server_target: false,
+            extensions_dir: None,
+        }
+    }
+
+    /// Uses the given extensions directory instead of the IDE's default one
+    #[must_use]
+    pub fn with_extensions_dir(mut self, extensions_dir: Option<PathBuf>) -> Self {
+        self.extensions_dir = extensions_dir;
+        self
+    }
+
+    /// Always targets the IDE's remote server extensions directory
+    #[must_use]
+    pub fn with_server_target(mut self, server_target: bool) -> Self {
+        self.server_target = server_target;
+        self
+    }
+
+    /// Returns the `extensions.json` files of the IDE's profiles
+    ///
+    /// Profiles share the extensions directory of the default profile, so their
+    /// registries tell which folders are still in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the home directory cannot be determined or the
+    /// profile list cannot be read
+    pub fn profile_registries(&self, ide_type: &IdeType) -> Result<Vec<PathBuf>, DomainError> {
+        let Some(user_data_dir) = ide_type.user_data_directory(&Self::home_dir()?) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(read_profiles(&user_data_dir)?
+            .iter()
+            .map(|profile| profile.registry_path(&user_data_dir))
+            .collect())
     }
 
     /// Checks if a command exists in the system PATH
@@ -22,38 +91,179 @@ impl SystemInstallationDetector {
         which::which(command).ok()
     }
 
+    fn home_dir() -> Result<PathBuf, DomainError> {
+        dirs::home_dir()
+            .ok_or_else(|| DomainError::DirectoryNotFound("Home directory not found".to_string()))
+    }
+
     /// Gets the extensions directory for file system installation
     fn get_extensions_directory(&self, ide_type: &IdeType) -> Result<PathBuf, DomainError> {
-        let home = dirs::home_dir().ok_or_else(|| {
-            DomainError::DirectoryNotFound("Home directory not found".to_string())
-        })?;
+        match self.custom_extensions_directory(ide_type) {
+            Some(extensions_dir) => Ok(extensions_dir),
+            None => Ok(ide_type.extensions_dir.resolve(&Self::home_dir()?)),
+        }
+    }
 
-        let extensions_dir = match ide_type {
-            IdeType::VsCode => home.join(".vscode").join("extensions"),
-            IdeType::Cursor => {
-                if cfg!(target_os = "windows") {
-                    home.join("AppData")
-                        .join("Roaming")
-                        .join("Cursor")
-                        .join("User")
-                        .join("extensions")
-                } else if cfg!(target_os = "macos") {
-                    home.join("Library")
-                        .join("Application Support")
-                        .join("Cursor")
-                        .join("User")
-                        .join("extensions")
-                } else {
-                    home.join(".config")
-                        .join("Cursor")
-                        .join("User")
-                        .join("extensions")
-                }
-            }
-        };
+    /// Returns the extensions directory set explicitly or by a portable install
+    ///
+    /// In order of precedence: `--extensions-dir`, `VSCODE_EXTENSIONS`,
+    /// `VSCODE_PORTABLE` and a `data` folder next to the IDE's CLI binary.
+    fn custom_extensions_directory(&self, ide_type: &IdeType) -> Option<PathBuf> {
+        self.custom_extensions_directory_with_source(ide_type)
+            .map(|(dir, _)| dir)
+    }
+
+    fn custom_extensions_directory_with_source(
+        &self,
+        ide_type: &IdeType,
+    ) -> Option<(PathBuf, DirectorySource)> {
+        let non_empty =
+            |value: std::ffi::OsString| (!value.is_empty()).then(|| PathBuf::from(value));
+
+        self.extensions_dir
+            .clone()
+            .map(|dir| (dir, DirectorySource::Option))
+            .or_else(|| {
+                std::env::var_os(EXTENSIONS_DIR_ENV_VAR)
+                    .and_then(non_empty)
+                    .map(|dir| (dir, DirectorySource::ExtensionsEnvVar))
+            })
+            .or_else(|| {
+                std::env::var_os(PORTABLE_ENV_VAR)
+                    .and_then(non_empty)
+                    .map(|data_dir| (data_dir.join("extensions"), DirectorySource::PortableEnvVar))
+            })
+            .or_else(|| {
+                self.find_cli(ide_type)
+                    .and_then(|command_path| portable_data_dir(&command_path))
+                    .map(|data_dir| {
+                        (
+                            data_dir.join("extensions"),
+                            DirectorySource::PortableInstall,
+                        )
+                    })
+            })
+    }
+
+    /// Returns the extensions directory of the IDE and where it comes from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the home directory cannot be determined, or server
+    /// mode is forced for an IDE without a remote server
+    pub fn locate_extensions_directory(
+        &self,
+        ide_type: &IdeType,
+    ) -> Result<(PathBuf, DirectorySource), DomainError> {
+        if let Some(custom) = self.custom_extensions_directory_with_source(ide_type) {
+            return Ok(custom);
+        }
+
+        match self.server_extensions_directory(ide_type)? {
+            Some(server_dir) => Ok((server_dir, DirectorySource::RemoteServer)),
+            None => Ok((
+                ide_type.extensions_dir.resolve(&Self::home_dir()?),
+                DirectorySource::Default,
+            )),
+        }
+    }
+
+    /// Returns the VS Code version of the IDE its CLI belongs to, e.g. `1.95.3`
+    ///
+    /// The version is read from the installation's `product.json`. Forks such as
+    /// Cursor report the VS Code version they are based on rather than their own.
+    /// Returns `None` when targeting a remote server, whose version is unknown,
+    /// or when the installation cannot be found.
+    #[must_use]
+    pub fn engine_version(&self, ide_type: &IdeType) -> Option<String> {
+        if self.server_target {
+            return None;
+        }
+        self.find_cli(ide_type)
+            .and_then(|command_path| product_version(&command_path))
+    }
 
-        Ok(extensions_dir)
+    /// Finds the first of the IDE's CLI commands in PATH
+    #[must_use]
+    pub fn find_cli(&self, ide_type: &IdeType) -> Option<PathBuf> {
+        ide_type
+            .commands
+            .iter()
+            .find_map(|command| self.is_command_available(command))
     }
+
+    /// Returns the remote server extensions directory if it should be targeted
+    fn server_extensions_directory(
+        &self,
+        ide_type: &IdeType,
+    ) -> Result<Option<PathBuf>, DomainError> {
+        let home = Self::home_dir()?;
+        let server_dir = ide_type.server_extensions_directory(&home);
+
+        if self.server_target {
+            return server_dir.map(Some).ok_or_else(|| {
+                DomainError::InstallationFailed(format!(
+                    "{} has no remote server extensions directory",
+                    ide_type.display_name()
+                ))
+            });
+        }
+
+        // A server directory without a local installation means we are on the remote side
+        let local_dir = ide_type.extensions_dir.resolve(&home);
+        Ok(server_dir.filter(|dir| dir.is_dir() && !local_dir.is_dir()))
+    }
+}
+
+/// Finds the `data` folder of a portable install from the path of its CLI binary
+///
+/// Portable installs keep `data/` in the installation root.
+fn portable_data_dir(command_path: &Path) -> Option<PathBuf> {
+    installation_roots(command_path)?
+        .into_iter()
+        .map(|root| root.join("data"))
+        .find(|data_dir| data_dir.is_dir())
+}
+
+/// Reads the VS Code version from the `product.json` of the installation a CLI
+/// binary belongs to
+///
+/// `product.json` sits in the installation root on macOS (`Resources/app`) and
+/// in its `resources/app` folder on Linux and Windows. Forks record the VS Code
+/// version they are based on as `vscodeVersion`.
+fn product_version(command_path: &Path) -> Option<String> {
+    let product: serde_json::Value = installation_roots(command_path)?
+        .into_iter()
+        .flat_map(|root| {
+            [
+                root.join("product.json"),
+                root.join("resources/app/product.json"),
+            ]
+        })
+        .find_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())?;
+
+    product["vscodeVersion"]
+        .as_str()
+        .or_else(|| product["version"].as_str())
+        .map(ToString::to_string)
+}
+
+/// Returns the folders that may be the root of the installation a CLI binary
+/// belongs to
+///
+/// The root contains the binary itself or the `bin/` folder holding the CLI
+/// script. Symbolic links, e.g. `/usr/bin/code`, are followed.
+fn installation_roots(command_path: &Path) -> Option<Vec<PathBuf>> {
+    let command_path = fs::canonicalize(command_path).ok()?;
+    let bin_dir = command_path.parent()?;
+
+    let mut roots = vec![bin_dir.to_path_buf()];
+    if bin_dir.file_name() == Some(OsStr::new("bin"))
+        && let Some(root) = bin_dir.parent()
+    {
+        roots.push(root.to_path_buf());
+    }
+    Some(roots)
 }
 
 impl Default for SystemInstallationDetector {
@@ -65,15 +275,84 @@ impl Default for SystemInstallationDetector {
 #[async_trait]
 impl InstallationDetector for SystemInstallationDetector {
     async fn detect_method(&self, ide_type: &IdeType) -> Result<InstallationMethod, DomainError> {
-        // First, try to find the CLI command in PATH
-        if let Some(command_path) = self.is_command_available(ide_type.command_name()) {
-            return Ok(InstallationMethod::CliCommand { command_path });
+        let server_dir = self.server_extensions_directory(ide_type)?;
+        let custom_dir = self.custom_extensions_directory(ide_type);
+
+        // A local CLI would install into the local editor, not the server
+        if self.server_target
+            && let Some(server_dir) = server_dir
+        {
+            return Ok(InstallationMethod::RemoteServer {
+                extensions_dir: custom_dir.unwrap_or(server_dir),
+            });
+        }
+
+        // First, try to find one of the CLI commands in PATH
+        if let Some(command_path) = self.find_cli(ide_type) {
+            return Ok(InstallationMethod::CliCommand {
+                command_path,
+                extensions_dir: custom_dir,
+            });
+        }
+
+        if custom_dir.is_none()
+            && let Some(extensions_dir) = server_dir
+        {
+            return Ok(InstallationMethod::RemoteServer { extensions_dir });
         }
 
         // Fallback to file system installation
         let extensions_dir = self.get_extensions_directory(ide_type)?;
         Ok(InstallationMethod::FileSystem { extensions_dir })
     }
+
+    fn extensions_directory(&self, ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+        self.locate_extensions_directory(ide_type)
+            .map(|(dir, _)| dir)
+    }
+
+    fn resolve_profile(
+        &self,
+        ide_type: &IdeType,
+        name: &str,
+    ) -> Result<Option<ProfileTarget>, DomainError> {
+        if name.eq_ignore_ascii_case(DEFAULT_PROFILE_NAME) {
+            return Ok(None);
+        }
+
+        let user_data_dir = ide_type
+            .user_data_directory(&Self::home_dir()?)
+            .ok_or_else(|| {
+                DomainError::InstallationFailed(format!(
+                    "{} does not support profiles",
+                    ide_type.display_name()
+                ))
+            })?;
+        let profiles = read_profiles(&user_data_dir)?;
+
+        let profile = profiles
+            .iter()
+            .find(|profile| profile.name == name)
+            .or_else(|| {
+                profiles
+                    .iter()
+                    .find(|profile| profile.name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+                DomainError::Unknown(format!(
+                    "{} has no profile named '{name}'. Available: {DEFAULT_PROFILE_NAME}{}{}",
+                    ide_type.display_name(),
+                    if names.is_empty() { "" } else { ", " },
+                    names.join(", ")
+                ))
+            })?;
+
+        Ok(Some(ProfileTarget {
+            name: profile.name.clone(),
+            registry_path: profile.registry_path(&user_data_dir),
+        }))
+    }
 }
 
 #[async_trait]
@@ -81,17 +360,47 @@ impl InstallationDetector for &SystemInstallationDetector {
     async fn detect_method(&self, ide_type: &IdeType) -> Result<InstallationMethod, DomainError> {
         (**self).detect_method(ide_type).await
     }
+
+    fn extensions_directory(&self, ide_type: &IdeType) -> Result<PathBuf, DomainError> {
+        (**self).extensions_directory(ide_type)
+    }
+
+    fn resolve_profile(
+        &self,
+        ide_type: &IdeType,
+        name: &str,
+    ) -> Result<Option<ProfileTarget>, DomainError> {
+        (**self).resolve_profile(ide_type, name)
+    }
 }
 
 /// Default implementation of the installation executor
-pub struct SystemInstallationExecutor;
+///
+/// IDE CLIs run as async subprocesses and are killed when they exceed the
+/// timeout or the install is cancelled.
+pub struct SystemInstallationExecutor {
+    cli_timeout: Duration,
+}
 
 impl SystemInstallationExecutor {
     pub fn new() -> Self {
-        Self
+        Self {
+            cli_timeout: DEFAULT_CLI_TIMEOUT,
+        }
+    }
+
+    /// Sets how long an IDE CLI invocation may take
+    #[must_use]
+    pub fn with_cli_timeout(mut self, cli_timeout: Duration) -> Self {
+        self.cli_timeout = cli_timeout;
+        self
     }
 
     /// Installs by extracting to file system
+    ///
+    /// The archive is extracted into a staging directory next to the target and
+    /// only swapped into place once it has been validated. The previous version
+    /// is kept until the swap succeeds and restored on any failure.
     async fn install_via_filesystem(
         &self,
         extensions_dir: &Path,
@@ -99,65 +408,334 @@ impl SystemInstallationExecutor {
         vsix_data: &[u8],
     ) -> Result<(), DomainError> {
         let target_dir = extensions_dir.join(extension_id);
+        let staging_dir = extensions_dir.join(format!(".{extension_id}.staging"));
+        let backup_dir = extensions_dir.join(format!(".{extension_id}.backup"));
+
+        // Recover from an interrupted previous run before touching anything
+        Self::recover_interrupted_install(&target_dir, &staging_dir, &backup_dir)?;
+
+        // Extract VSIX archive into the staging directory
+        fs::create_dir_all(&staging_dir)?;
+        let staged = extract_vsix(vsix_data, &staging_dir)
+            .and_then(|()| Self::validate_staged_extension(&staging_dir));
 
-        // Remove existing installation if present
-        if target_dir.exists() {
-            fs::remove_dir_all(&target_dir)?;
+        if let Err(e) = staged {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
         }
 
-        // Create target directory
-        fs::create_dir_all(&target_dir)?;
+        Self::swap_into_place(&staging_dir, &target_dir, &backup_dir)
+    }
+
+    /// Rejects extensions that can only run in the local editor
+    ///
+    /// UI extensions (themes with code, keymaps, ...) are loaded by the local
+    /// editor even when connected to a remote, so installing them on the server
+    /// would have no effect.
+    fn ensure_workspace_extension(package: &VsixPackage) -> Result<(), DomainError> {
+        if package.extension_kind.contains(&ExtensionKind::Workspace) {
+            return Ok(());
+        }
+
+        let kinds: Vec<String> = package
+            .extension_kind
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Err(DomainError::InstallationFailed(format!(
+            "{} runs in the local editor (extensionKind: {}) and cannot be installed on the remote server",
+            package.id(),
+            kinds.join(", ")
+        )))
+    }
 
-        // Extract VSIX archive
-        self.extract_vsix(vsix_data, &target_dir)?;
+    /// Cleans up leftovers of an install that was interrupted mid-way
+    fn recover_interrupted_install(
+        target_dir: &Path,
+        staging_dir: &Path,
+        backup_dir: &Path,
+    ) -> Result<(), DomainError> {
+        if staging_dir.exists() {
+            fs::remove_dir_all(staging_dir)?;
+        }
+
+        if backup_dir.exists() {
+            if target_dir.exists() {
+                fs::remove_dir_all(backup_dir)?;
+            } else {
+                // The previous version was moved aside but never replaced
+                fs::rename(backup_dir, target_dir)?;
+            }
+        }
 
         Ok(())
     }
 
-    /// Extracts VSIX archive to target directory
-    fn extract_vsix(&self, vsix_data: &[u8], target_dir: &Path) -> Result<(), DomainError> {
-        let cursor = std::io::Cursor::new(vsix_data);
-        let mut archive = ZipArchive::new(cursor)
-            .map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))?;
+    /// Checks that a staged extraction looks like an installable extension
+    fn validate_staged_extension(staging_dir: &Path) -> Result<(), DomainError> {
+        let has_manifest = staging_dir.join("package.json").is_file()
+            || staging_dir.join("extension").join("package.json").is_file();
 
-        for i in 0..archive.len() {
-            let mut file = archive
-                .by_index(i)
-                .map_err(|e| DomainError::IoError(std::io::Error::other(e)))?;
+        if !has_manifest {
+            return Err(DomainError::InvalidExtensionFormat(
+                "VSIX archive does not contain a package.json".to_string(),
+            ));
+        }
 
-            let outpath = target_dir.join(file.name());
+        Ok(())
+    }
 
-            if file.name().ends_with('/') {
-                fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(parent) = outpath.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-                let mut outfile = fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
+    /// Replaces the target directory with the staged one, rolling back on failure
+    fn swap_into_place(
+        staging_dir: &Path,
+        target_dir: &Path,
+        backup_dir: &Path,
+    ) -> Result<(), DomainError> {
+        let had_previous = target_dir.exists();
+
+        if had_previous && let Err(e) = fs::rename(target_dir, backup_dir) {
+            let _ = fs::remove_dir_all(staging_dir);
+            return Err(DomainError::InstallationFailed(format!(
+                "Failed to move previous version aside: {e}"
+            )));
+        }
+
+        if let Err(e) = fs::rename(staging_dir, target_dir) {
+            let _ = fs::remove_dir_all(staging_dir);
+            if had_previous {
+                fs::rename(backup_dir, target_dir)?;
             }
+            return Err(DomainError::InstallationFailed(format!(
+                "Failed to move extension into place: {e}"
+            )));
+        }
+
+        if had_previous {
+            let _ = fs::remove_dir_all(backup_dir);
         }
 
         Ok(())
     }
 
+    /// Removes an extension folder and its `extensions.json` entry
+    fn uninstall_via_filesystem(
+        &self,
+        extensions_dir: &Path,
+        extension_id: &str,
+    ) -> Result<(), DomainError> {
+        let mut registry = ExtensionsRegistry::load(extensions_dir)?;
+        let removed = registry.remove(extension_id);
+
+        let mut extension_dirs: Vec<PathBuf> = removed
+            .iter()
+            .filter_map(entry_relative_location)
+            .map(|location| extensions_dir.join(location))
+            .filter(|dir| dir.is_dir())
+            .collect();
+        extension_dirs.extend(find_extension_dirs(extensions_dir, extension_id)?);
+        extension_dirs.sort();
+        extension_dirs.dedup();
+
+        if removed.is_empty() && extension_dirs.is_empty() {
+            return Err(DomainError::ExtensionNotFound(extension_id.to_string()));
+        }
+
+        // Unregister first so the editor never sees an entry without its folder
+        if !removed.is_empty() {
+            registry.save()?;
+        }
+
+        for dir in extension_dirs {
+            fs::remove_dir_all(dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds an installed extension folder to a profile's `extensions.json`
+    ///
+    /// Profiles share the extension folders of the default extensions directory
+    /// and only keep their own list of which extensions are enabled in them.
+    fn register_in_profile(
+        profile: &ProfileTarget,
+        extension_id: &str,
+        extension_dir: &Path,
+    ) -> Result<(), DomainError> {
+        let manifest = read_package_json(extension_dir).ok_or_else(|| {
+            DomainError::InvalidExtensionFormat(
+                "Installed extension has no package.json".to_string(),
+            )
+        })?;
+        let version = manifest["version"].as_str().unwrap_or("0.0.0");
+
+        let mut registry = ExtensionsRegistry::load_file(profile.registry_path.clone())?;
+        registry.upsert(new_entry(extension_id, version, extension_dir));
+        registry.save()
+    }
+
+    /// Removes an extension from a profile, leaving the shared folder in place
+    fn unregister_from_profile(
+        profile: &ProfileTarget,
+        extension_id: &str,
+    ) -> Result<(), DomainError> {
+        let mut registry = ExtensionsRegistry::load_file(profile.registry_path.clone())?;
+        if registry.remove(extension_id).is_empty() {
+            return Err(DomainError::ExtensionNotFound(format!(
+                "{extension_id} (profile {})",
+                profile.name
+            )));
+        }
+        registry.save()
+    }
+
+    /// Keeps only the extensions listed in a profile's `extensions.json`
+    fn filter_by_profile(
+        profile: &ProfileTarget,
+        installed: Vec<InstalledExtension>,
+    ) -> Result<Vec<InstalledExtension>, DomainError> {
+        let registry = ExtensionsRegistry::load_file(profile.registry_path.clone())?;
+        let folders: Vec<&str> = registry
+            .entries()
+            .iter()
+            .filter_map(entry_relative_location)
+            .collect();
+        let ids: Vec<&str> = registry.entries().iter().filter_map(entry_id).collect();
+
+        Ok(installed
+            .into_iter()
+            .filter(|ext| {
+                let folder = ext
+                    .location
+                    .as_deref()
+                    .and_then(Path::file_name)
+                    .and_then(|name| name.to_str());
+                match folder {
+                    Some(folder) => folders.contains(&folder),
+                    None => ids.iter().any(|id| id.eq_ignore_ascii_case(&ext.id)),
+                }
+            })
+            .collect())
+    }
+
     /// Saves VSIX data to a temporary file for CLI installation
+    ///
+    /// The file gets a unique name and is only accessible to the current user.
+    /// It is deleted when the returned path is dropped.
     async fn save_vsix_to_temp(
         &self,
         extension_id: &str,
         vsix_data: &[u8],
-    ) -> Result<PathBuf, DomainError> {
-        let temp_dir = std::env::temp_dir();
-        let vsix_filename = format!("{}.vsix", extension_id.replace('.', "-"));
-        let vsix_path = temp_dir.join(vsix_filename);
-
-        let mut file = fs::File::create(&vsix_path)?;
+    ) -> Result<TempPath, DomainError> {
+        let mut file = tempfile::Builder::new()
+            .prefix(&format!("{}-", extension_id.replace('.', "-")))
+            .suffix(".vsix")
+            .tempfile()?;
         file.write_all(vsix_data)?;
+        file.flush()?;
+
+        Ok(file.into_temp_path())
+    }
+
+    /// Returns the first line `<cli> --version` prints
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CLI cannot be run, times out or fails
+    pub async fn cli_version(&self, command_path: &Path) -> Result<String, DomainError> {
+        let mut command = Command::new(command_path);
+        command.arg("--version");
+        let output = self.run_cli(command).await?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
 
-        Ok(vsix_path)
+        match stdout.lines().map(str::trim).find(|line| !line.is_empty()) {
+            Some(version) if output.status.success() => Ok(version.to_string()),
+            _ => Err(DomainError::InstallationFailed(format!(
+                "{} --version exited with {}",
+                command_path.display(),
+                output.status
+            ))),
+        }
+    }
+
+    /// Runs an IDE CLI invocation, killing it once the timeout expires
+    ///
+    /// The subprocess is also killed if the returned future is dropped.
+    async fn run_cli(&self, mut command: Command) -> Result<Output, DomainError> {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        match tokio::time::timeout(self.cli_timeout, command.output()).await {
+            Ok(output) => Ok(output?),
+            Err(_) => Err(DomainError::InstallationFailed(format!(
+                "{} did not finish within {} seconds",
+                command.as_std().get_program().to_string_lossy(),
+                self.cli_timeout.as_secs()
+            ))),
+        }
     }
 }
 
+/// Interprets the result of `<ide> --install-extension`
+///
+/// IDE CLIs report some outcomes only in their output: an extension that is
+/// already installed exits successfully, and a failed install of one of several
+/// extensions may still exit with 0.
+fn interpret_install_output(extension_id: &str, output: &Output) -> Result<(), DomainError> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr
+        .lines()
+        .chain(stdout.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let find = |patterns: &[&str]| {
+        lines.iter().find(|line| {
+            let line = line.to_lowercase();
+            patterns.iter().any(|pattern| line.contains(pattern))
+        })
+    };
+
+    if find(&["is already installed"]).is_some() {
+        return Err(DomainError::AlreadyInstalled(extension_id.to_string()));
+    }
+    if let Some(line) = find(&["not compatible", "incompatible"]) {
+        return Err(DomainError::Incompatible(format!("{extension_id}: {line}")));
+    }
+    if let Some(line) = find(&["failed installing extensions"]).filter(|_| output.status.success())
+    {
+        return Err(DomainError::InstallationFailed(line.to_string()));
+    }
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DomainError::InstallationFailed(format!(
+            "Failed to install extension via CLI: {}",
+            lines.first().copied().unwrap_or("no output")
+        )))
+    }
+}
+
+/// Builds an IDE CLI invocation, forwarding a custom extensions directory and profile
+fn cli_command(
+    command_path: &Path,
+    extensions_dir: Option<&Path>,
+    profile: Option<&ProfileTarget>,
+) -> Command {
+    let mut command = Command::new(command_path);
+    if let Some(extensions_dir) = extensions_dir {
+        command.arg("--extensions-dir").arg(extensions_dir);
+    }
+    if let Some(profile) = profile {
+        command.arg("--profile").arg(&profile.name);
+    }
+    command
+}
+
 impl Default for SystemInstallationExecutor {
     fn default() -> Self {
         Self::new()
@@ -172,39 +750,143 @@ impl InstallationExecutor for SystemInstallationExecutor {
         extension_id: &str,
         vsix_data: &[u8],
     ) -> Result<(), DomainError> {
+        // Refuse packages that are not the requested extension
+        let package = read_vsix_package(vsix_data)?;
+        package.ensure_identity(extension_id)?;
+
         match &strategy.method {
-            InstallationMethod::CliCommand { command_path } => {
-                // Save VSIX to temporary file
+            InstallationMethod::CliCommand {
+                command_path,
+                extensions_dir,
+            } => {
+                // Save VSIX to a temporary file, removed when `vsix_path` is dropped
                 let vsix_path = self.save_vsix_to_temp(extension_id, vsix_data).await?;
 
-                // Install using CLI command with the VSIX file
-                let result = Command::new(command_path)
-                    .arg("--install-extension")
-                    .arg(&vsix_path)
-                    .output()
-                    .map_err(DomainError::IoError)?;
+                let mut command = cli_command(
+                    command_path,
+                    extensions_dir.as_deref(),
+                    strategy.profile.as_ref(),
+                );
+                command.arg("--install-extension").arg(&*vsix_path);
+                if strategy.options.force {
+                    command.arg("--force");
+                }
+                if strategy.options.pre_release {
+                    command.arg("--pre-release");
+                }
 
-                // Clean up temporary file
-                let _ = fs::remove_file(&vsix_path);
+                let output = self.run_cli(command).await?;
+                interpret_install_output(extension_id, &output)
+            }
+            InstallationMethod::FileSystem { extensions_dir }
+            | InstallationMethod::RemoteServer { extensions_dir } => {
+                if matches!(strategy.method, InstallationMethod::RemoteServer { .. }) {
+                    Self::ensure_workspace_extension(&package)?;
+                }
+
+                // Ensure extensions directory exists
+                if !extensions_dir.exists() {
+                    fs::create_dir_all(extensions_dir)?;
+                }
+
+                let up_to_date = !strategy.options.force
+                    && read_package_json(&extensions_dir.join(extension_id))
+                        .is_some_and(|manifest| manifest["version"] == package.version.as_str());
+                if !up_to_date {
+                    self.install_via_filesystem(extensions_dir, extension_id, vsix_data)
+                        .await?;
+                } else if strategy.profile.is_none() {
+                    return Err(DomainError::AlreadyInstalled(format!(
+                        "{extension_id} {}",
+                        package.version
+                    )));
+                }
+
+                if let Some(profile) = &strategy.profile {
+                    Self::register_in_profile(
+                        profile,
+                        extension_id,
+                        &extensions_dir.join(extension_id),
+                    )?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    async fn uninstall(
+        &self,
+        strategy: &InstallationStrategy,
+        extension_id: &str,
+    ) -> Result<(), DomainError> {
+        match &strategy.method {
+            InstallationMethod::CliCommand {
+                command_path,
+                extensions_dir,
+            } => {
+                let mut command = cli_command(
+                    command_path,
+                    extensions_dir.as_deref(),
+                    strategy.profile.as_ref(),
+                );
+                command.arg("--uninstall-extension").arg(extension_id);
+                let result = self.run_cli(command).await?;
 
                 if !result.status.success() {
                     let error_msg = String::from_utf8_lossy(&result.stderr);
+                    if error_msg.contains("is not installed") {
+                        return Err(DomainError::ExtensionNotFound(extension_id.to_string()));
+                    }
                     return Err(DomainError::InstallationFailed(format!(
-                        "Failed to install extension via CLI: {}",
-                        error_msg
+                        "Failed to uninstall extension via CLI: {error_msg}"
                     )));
                 }
 
                 Ok(())
             }
-            InstallationMethod::FileSystem { extensions_dir } => {
-                // Ensure extensions directory exists
-                if !extensions_dir.exists() {
-                    fs::create_dir_all(extensions_dir)?;
+            InstallationMethod::FileSystem { extensions_dir }
+            | InstallationMethod::RemoteServer { extensions_dir } => match &strategy.profile {
+                Some(profile) => Self::unregister_from_profile(profile, extension_id),
+                None => self.uninstall_via_filesystem(extensions_dir, extension_id),
+            },
+        }
+    }
+
+    async fn list_installed(
+        &self,
+        strategy: &InstallationStrategy,
+    ) -> Result<Vec<InstalledExtension>, DomainError> {
+        match &strategy.method {
+            InstallationMethod::CliCommand {
+                command_path,
+                extensions_dir,
+            } => {
+                let mut command = cli_command(
+                    command_path,
+                    extensions_dir.as_deref(),
+                    strategy.profile.as_ref(),
+                );
+                command.arg("--list-extensions").arg("--show-versions");
+                let result = self.run_cli(command).await?;
+
+                if !result.status.success() {
+                    let error_msg = String::from_utf8_lossy(&result.stderr);
+                    return Err(DomainError::InstallationFailed(format!(
+                        "Failed to list extensions via CLI: {error_msg}"
+                    )));
                 }
 
-                self.install_via_filesystem(extensions_dir, extension_id, vsix_data)
-                    .await
+                Ok(parse_cli_listing(&String::from_utf8_lossy(&result.stdout)))
+            }
+            InstallationMethod::FileSystem { extensions_dir }
+            | InstallationMethod::RemoteServer { extensions_dir } => {
+                let installed = scan_extensions_directory(extensions_dir)?;
+
+                match &strategy.profile {
+                    Some(profile) => Self::filter_by_profile(profile, installed),
+                    None => Ok(installed),
+                }
             }
         }
     }
@@ -220,11 +902,27 @@ impl InstallationExecutor for &SystemInstallationExecutor {
     ) -> Result<(), DomainError> {
         (**self).execute(strategy, extension_id, vsix_data).await
     }
+
+    async fn uninstall(
+        &self,
+        strategy: &InstallationStrategy,
+        extension_id: &str,
+    ) -> Result<(), DomainError> {
+        (**self).uninstall(strategy, extension_id).await
+    }
+
+    async fn list_installed(
+        &self,
+        strategy: &InstallationStrategy,
+    ) -> Result<Vec<InstalledExtension>, DomainError> {
+        (**self).list_installed(strategy).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::InstallOptions;
     use tempfile::TempDir;
 
     #[test]
@@ -232,20 +930,89 @@ mod tests {
         let detector = SystemInstallationDetector::new();
 
         // Test VSCode directory
-        let vscode_dir = detector.get_extensions_directory(&IdeType::VsCode);
+        let vscode_dir = detector.get_extensions_directory(&IdeType::vscode());
         assert!(vscode_dir.is_ok());
         let path = vscode_dir.unwrap();
         assert!(path.to_string_lossy().contains(".vscode"));
         assert!(path.to_string_lossy().contains("extensions"));
 
         // Test Cursor directory
-        let cursor_dir = detector.get_extensions_directory(&IdeType::Cursor);
+        let cursor_dir = detector.get_extensions_directory(&IdeType::cursor());
         assert!(cursor_dir.is_ok());
         let path = cursor_dir.unwrap();
         assert!(path.to_string_lossy().contains("Cursor"));
         assert!(path.to_string_lossy().contains("extensions"));
     }
 
+    #[tokio::test]
+    async fn test_custom_extensions_dir_overrides_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let custom_dir = temp_dir.path().join("extensions");
+        let detector =
+            SystemInstallationDetector::new().with_extensions_dir(Some(custom_dir.clone()));
+
+        assert_eq!(
+            detector.extensions_directory(&IdeType::vscode()).unwrap(),
+            custom_dir
+        );
+        match detector.detect_method(&IdeType::vscode()).await.unwrap() {
+            InstallationMethod::CliCommand { extensions_dir, .. } => {
+                assert_eq!(extensions_dir, Some(custom_dir));
+            }
+            InstallationMethod::FileSystem { extensions_dir } => {
+                assert_eq!(extensions_dir, custom_dir);
+            }
+            other => panic!("Unexpected method {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_portable_data_dir_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("VSCode-linux-x64");
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::create_dir_all(root.join("data")).unwrap();
+        fs::write(root.join("bin").join("code"), "").unwrap();
+        fs::write(root.join("code"), "").unwrap();
+
+        let data_dir = fs::canonicalize(root.join("data")).unwrap();
+        assert_eq!(
+            portable_data_dir(&root.join("bin").join("code")),
+            Some(data_dir.clone())
+        );
+        assert_eq!(portable_data_dir(&root.join("code")), Some(data_dir));
+
+        fs::remove_dir(root.join("data")).unwrap();
+        assert_eq!(portable_data_dir(&root.join("bin").join("code")), None);
+    }
+
+    #[test]
+    fn test_product_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("cursor");
+        let app_dir = root.join("resources").join("app");
+        fs::create_dir_all(root.join("bin")).unwrap();
+        fs::create_dir_all(&app_dir).unwrap();
+        fs::write(root.join("bin").join("cursor"), "").unwrap();
+        assert_eq!(product_version(&root.join("bin").join("cursor")), None);
+
+        fs::write(app_dir.join("product.json"), r#"{"version": "1.93.1"}"#).unwrap();
+        assert_eq!(
+            product_version(&root.join("bin").join("cursor")).as_deref(),
+            Some("1.93.1")
+        );
+
+        fs::write(
+            app_dir.join("product.json"),
+            r#"{"version": "0.45.14", "vscodeVersion": "1.96.2"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            product_version(&root.join("bin").join("cursor")).as_deref(),
+            Some("1.96.2")
+        );
+    }
+
     #[tokio::test]
     async fn test_save_vsix_to_temp() {
         let executor = SystemInstallationExecutor::new();
@@ -257,40 +1024,408 @@ mod tests {
 
         let path = result.unwrap();
         assert!(path.exists());
-        assert!(path.to_string_lossy().contains("test-extension.vsix"));
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        assert!(file_name.starts_with("test-extension-"));
+        assert!(file_name.ends_with(".vsix"));
+        assert_eq!(fs::read(&path).unwrap(), test_data);
+
+        // Every install gets its own file, removed once the path is dropped
+        let other = executor
+            .save_vsix_to_temp(extension_id, test_data)
+            .await
+            .unwrap();
+        assert_ne!(*path, *other);
+
+        let kept = path.to_path_buf();
+        drop(path);
+        assert!(!kept.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interpret_install_output() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+
+        let output = |code: i32, stdout: &str, stderr: &str| Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        };
 
-        // Clean up
-        let _ = fs::remove_file(path);
+        assert!(
+            interpret_install_output(
+                "pub.ext",
+                &output(0, "Extension 'ext.vsix' was successfully installed.", "")
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            interpret_install_output(
+                "pub.ext",
+                &output(
+                    0,
+                    "Extension 'pub.ext' v1.0.0 is already installed. Use '--force' option to update to latest version.",
+                    ""
+                )
+            ),
+            Err(DomainError::AlreadyInstalled(_))
+        ));
+        assert!(matches!(
+            interpret_install_output(
+                "pub.ext",
+                &output(
+                    1,
+                    "",
+                    "Can't install 'pub.ext' extension because it is not compatible with the current version of Visual Studio Code (version 1.70.0)."
+                )
+            ),
+            Err(DomainError::Incompatible(_))
+        ));
+        assert!(matches!(
+            interpret_install_output(
+                "pub.ext",
+                &output(
+                    0,
+                    "",
+                    "Failed Installing Extensions: file:///tmp/pub-ext.vsix"
+                )
+            ),
+            Err(DomainError::InstallationFailed(_))
+        ));
+        assert!(matches!(
+            interpret_install_output("pub.ext", &output(1, "", "Corrupt ZIP: end of central directory record signature not found")),
+            Err(DomainError::InstallationFailed(msg)) if msg.contains("Corrupt ZIP")
+        ));
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_extract_vsix_with_valid_zip() {
+    async fn test_cli_timeout_kills_process() {
+        let executor =
+            SystemInstallationExecutor::new().with_cli_timeout(Duration::from_millis(100));
+        let mut command = Command::new("sleep");
+        command.arg("5");
+
+        let started = std::time::Instant::now();
+        let result = executor.run_cli(command).await;
+        assert!(matches!(
+            result,
+            Err(DomainError::InstallationFailed(msg)) if msg.contains("did not finish")
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_install_same_version_requires_force() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        let strategy = InstallationStrategy::new(
+            IdeType::vscode(),
+            InstallationMethod::FileSystem {
+                extensions_dir: temp_dir.path().to_path_buf(),
+            },
+        );
+        let vsix_data = vsix_fixture("pub.ext", "1.0.0", &[]);
+
+        executor
+            .execute(&strategy, "pub.ext", &vsix_data)
+            .await
+            .unwrap();
+        assert!(matches!(
+            executor.execute(&strategy, "pub.ext", &vsix_data).await,
+            Err(DomainError::AlreadyInstalled(_))
+        ));
+
+        let strategy = strategy.with_options(InstallOptions {
+            force: true,
+            ..InstallOptions::default()
+        });
+        executor
+            .execute(&strategy, "pub.ext", &vsix_data)
+            .await
+            .unwrap();
+    }
+
+    /// Builds an in-memory VSIX with a `package.json` for `id` and `version`
+    ///
+    /// `extra_files` are added as they are; an `extension/package.json` among them
+    /// replaces the generated one.
+    fn vsix_fixture(id: &str, version: &str, extra_files: &[(&str, &str)]) -> Vec<u8> {
         use std::io::Write;
         use zip::ZipWriter;
         use zip::write::FileOptions;
 
-        let executor = SystemInstallationExecutor::new();
+        let (publisher, name) = id.split_once('.').unwrap();
+        let manifest =
+            format!(r#"{{"publisher": "{publisher}", "name": "{name}", "version": "{version}"}}"#);
+        let mut files = vec![("extension/package.json", manifest.as_str())];
+        for &(path, content) in extra_files {
+            files.retain(|(existing, _)| *existing != path);
+            files.push((path, content));
+        }
 
-        // Create a valid ZIP archive in memory
         let mut zip_data = Vec::new();
         {
             let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_data));
-            zip.start_file::<_, ()>("package.json", FileOptions::default())
-                .unwrap();
-            zip.write_all(b"{\"name\": \"test\"}").unwrap();
+            for (path, content) in files {
+                zip.start_file::<_, ()>(path, FileOptions::default())
+                    .unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
             zip.finish().unwrap();
         }
+        zip_data
+    }
+
+    #[tokio::test]
+    async fn test_extract_vsix_with_valid_zip() {
+        let zip_data = vsix_fixture(
+            "test.extension",
+            "1.0.0",
+            &[("package.json", r#"{"name": "test"}"#)],
+        );
 
         // Create temporary directory for extraction
         let temp_dir = TempDir::new().unwrap();
         let target_dir = temp_dir.path().join("test_extension");
 
         // Extract the ZIP
-        let result = executor.extract_vsix(&zip_data, &target_dir);
+        let result = extract_vsix(&zip_data, &target_dir);
         assert!(result.is_ok());
 
         // Verify extraction
         let package_json = target_dir.join("package.json");
         assert!(package_json.exists());
     }
+
+    #[tokio::test]
+    async fn test_install_via_filesystem_replaces_previous_version() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        executor
+            .install_via_filesystem(
+                temp_dir.path(),
+                "test.extension",
+                &vsix_fixture("test.extension", "1.0.0", &[]),
+            )
+            .await
+            .unwrap();
+        executor
+            .install_via_filesystem(
+                temp_dir.path(),
+                "test.extension",
+                &vsix_fixture("test.extension", "2.0.0", &[]),
+            )
+            .await
+            .unwrap();
+
+        let package_json = temp_dir
+            .path()
+            .join("test.extension")
+            .join("extension")
+            .join("package.json");
+        assert!(fs::read_to_string(package_json).unwrap().contains("2.0.0"));
+
+        // Only the installed extension is left behind
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_install_via_filesystem_keeps_previous_version_on_corrupt_archive() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        executor
+            .install_via_filesystem(
+                temp_dir.path(),
+                "test.extension",
+                &vsix_fixture("test.extension", "1.0.0", &[]),
+            )
+            .await
+            .unwrap();
+
+        let result = executor
+            .install_via_filesystem(temp_dir.path(), "test.extension", b"not a zip archive")
+            .await;
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+
+        let package_json = temp_dir
+            .path()
+            .join("test.extension")
+            .join("extension")
+            .join("package.json");
+        assert!(fs::read_to_string(package_json).unwrap().contains("1.0.0"));
+        assert!(!temp_dir.path().join(".test.extension.staging").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_via_filesystem_restores_interrupted_backup() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        let backup_dir = temp_dir.path().join(".test.extension.backup");
+        fs::create_dir_all(&backup_dir).unwrap();
+        fs::write(backup_dir.join("package.json"), "{}").unwrap();
+
+        let result = executor
+            .install_via_filesystem(temp_dir.path(), "test.extension", b"not a zip archive")
+            .await;
+        assert!(result.is_err());
+
+        // The version left aside by the interrupted run is back in place
+        assert!(
+            temp_dir
+                .path()
+                .join("test.extension")
+                .join("package.json")
+                .exists()
+        );
+        assert!(!backup_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_remote_server_install_honors_extension_kind() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        let server_dir = temp_dir.path().join(".vscode-server").join("extensions");
+        let strategy = InstallationStrategy::new(
+            IdeType::vscode(),
+            InstallationMethod::RemoteServer {
+                extensions_dir: server_dir.clone(),
+            },
+        );
+
+        let result = executor
+            .execute(
+                &strategy,
+                "pub.keymap",
+                &vsix_fixture("pub.keymap", "1.0.0", &[("extension/package.json", r#"{"publisher": "pub", "name": "keymap", "version": "1.0.0", "extensionKind": ["ui"]}"#)]),
+            )
+            .await;
+        assert!(matches!(result, Err(DomainError::InstallationFailed(_))));
+        assert!(!server_dir.join("pub.keymap").exists());
+
+        executor
+            .execute(
+                &strategy,
+                "pub.linter",
+                &vsix_fixture("pub.linter", "1.0.0", &[("extension/package.json", r#"{"publisher": "pub", "name": "linter", "version": "1.0.0", "main": "./out/extension.js"}"#)]),
+            )
+            .await
+            .unwrap();
+        assert!(server_dir.join("pub.linter").join("extension").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_mismatched_identity() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        let strategy = InstallationStrategy::new(
+            IdeType::vscode(),
+            InstallationMethod::FileSystem {
+                extensions_dir: temp_dir.path().to_path_buf(),
+            },
+        );
+
+        let result = executor
+            .execute(
+                &strategy,
+                "pub.requested",
+                &vsix_fixture("evil.payload", "1.0.0", &[]),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+        assert!(!temp_dir.path().join("pub.requested").exists());
+    }
+
+    #[tokio::test]
+    async fn test_profile_install_list_and_uninstall() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        let extensions_dir = temp_dir.path().join("extensions");
+        let profile = ProfileTarget {
+            name: "Rust".to_string(),
+            registry_path: temp_dir.path().join("profiles/1/extensions.json"),
+        };
+        let strategy = InstallationStrategy::new(
+            IdeType::vscode(),
+            InstallationMethod::FileSystem {
+                extensions_dir: extensions_dir.clone(),
+            },
+        )
+        .with_profile(Some(profile.clone()));
+
+        executor
+            .execute(
+                &strategy,
+                "pub.rust",
+                &vsix_fixture("pub.rust", "1.2.0", &[]),
+            )
+            .await
+            .unwrap();
+        fs::create_dir_all(extensions_dir.join("pub.other-1.0.0")).unwrap();
+
+        let registry = fs::read_to_string(&profile.registry_path).unwrap();
+        assert!(registry.contains("\"pub.rust\""));
+        assert!(registry.contains("\"1.2.0\""));
+
+        let listed = executor.list_installed(&strategy).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "pub.rust");
+
+        executor.uninstall(&strategy, "pub.rust").await.unwrap();
+        assert!(executor.list_installed(&strategy).await.unwrap().is_empty());
+        // The folder is shared with other profiles and stays in place
+        assert!(extensions_dir.join("pub.rust").is_dir());
+
+        assert!(matches!(
+            executor.uninstall(&strategy, "pub.rust").await,
+            Err(DomainError::ExtensionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_via_filesystem_removes_folder_and_registry_entry() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("pub.ext-1.0.0")).unwrap();
+        fs::create_dir(temp_dir.path().join("pub.other-1.0.0")).unwrap();
+        fs::write(
+            temp_dir.path().join("extensions.json"),
+            r#"[{"identifier":{"id":"pub.ext"},"version":"1.0.0","relativeLocation":"pub.ext-1.0.0"},
+                {"identifier":{"id":"pub.other"},"version":"1.0.0","relativeLocation":"pub.other-1.0.0"}]"#,
+        )
+        .unwrap();
+
+        let strategy = InstallationStrategy::new(
+            IdeType::vscode(),
+            InstallationMethod::FileSystem {
+                extensions_dir: temp_dir.path().to_path_buf(),
+            },
+        );
+        executor.uninstall(&strategy, "pub.ext").await.unwrap();
+
+        assert!(!temp_dir.path().join("pub.ext-1.0.0").exists());
+        assert!(temp_dir.path().join("pub.other-1.0.0").exists());
+        let registry = fs::read_to_string(temp_dir.path().join("extensions.json")).unwrap();
+        assert!(!registry.contains("pub.ext\""));
+        assert!(registry.contains("pub.other"));
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_via_filesystem_not_installed() {
+        let executor = SystemInstallationExecutor::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        let result = executor.uninstall_via_filesystem(temp_dir.path(), "pub.missing");
+        assert!(matches!(result, Err(DomainError::ExtensionNotFound(_))));
+    }
 }
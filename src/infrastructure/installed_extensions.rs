@@ -0,0 +1,296 @@
+use super::extensions_registry::{ExtensionsRegistry, entry_id, entry_relative_location};
+use crate::domain::{Architecture, DomainError, InstalledExtension};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File in which editors record extension folders that are pending deletion
+pub const OBSOLETE_FILE_NAME: &str = ".obsolete";
+
+/// Scans an extensions directory for installed extensions
+///
+/// Every folder containing a `package.json` (either directly or in an
+/// `extension/` subfolder) is reported. Version, target platform and install
+/// date are taken from `extensions.json` when the folder is registered there,
+/// and from the manifest and folder metadata otherwise. Folders listed in the
+/// `.obsolete` file are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the directory or its registry cannot be read
+pub fn scan_extensions_directory(
+    extensions_dir: &Path,
+) -> Result<Vec<InstalledExtension>, DomainError> {
+    if !extensions_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let registry = ExtensionsRegistry::load(extensions_dir)?;
+    let registered: HashMap<String, &Value> = registry
+        .entries()
+        .iter()
+        .filter_map(|entry| Some((entry_relative_location(entry)?.to_lowercase(), entry)))
+        .collect();
+    let obsolete = read_obsolete_folders(extensions_dir)?;
+
+    let mut extensions = Vec::new();
+
+    for dir_entry in fs::read_dir(extensions_dir)? {
+        let dir_entry = dir_entry?;
+        let folder_name = dir_entry.file_name().to_string_lossy().to_string();
+
+        if !dir_entry.file_type()?.is_dir()
+            || folder_name.starts_with('.')
+            || obsolete.contains(&folder_name)
+        {
+            continue;
+        }
+
+        let folder = dir_entry.path();
+        let Some(manifest) = read_package_json(&folder) else {
+            continue;
+        };
+        let Some(id) = manifest_id(&manifest) else {
+            continue;
+        };
+
+        let registry_entry = registered.get(&folder_name.to_lowercase()).copied();
+        extensions.push(describe_extension(
+            &folder,
+            &folder_name,
+            id,
+            &manifest,
+            registry_entry,
+        ));
+    }
+
+    extensions.sort_by_key(|ext| ext.id.to_lowercase());
+    Ok(extensions)
+}
+
+/// Parses the output of `code --list-extensions --show-versions`
+#[must_use]
+pub fn parse_cli_listing(output: &str) -> Vec<InstalledExtension> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let (id, version) = line.split_once('@').unwrap_or((line, ""));
+            // Skip informational lines such as "Extensions installed on ..."
+            if id.contains(char::is_whitespace) || !id.contains('.') {
+                return None;
+            }
+
+            Some(InstalledExtension {
+                id: id.to_string(),
+                version: version.to_string(),
+                target_platform: None,
+                size: None,
+                installed_at: None,
+                location: None,
+                disabled: false,
+                pre_release: false,
+            })
+        })
+        .collect()
+}
+
+/// Reads the folder names recorded in the `.obsolete` file
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read
+pub fn read_obsolete_folders(extensions_dir: &Path) -> Result<HashSet<String>, DomainError> {
+    let path = extensions_dir.join(OBSOLETE_FILE_NAME);
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    let folders = serde_json::from_str::<HashMap<String, Value>>(&content)
+        .map(|map| {
+            map.into_iter()
+                .filter(|(_, marked)| marked.as_bool().unwrap_or(false))
+                .map(|(folder, _)| folder)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(folders)
+}
+
+/// Reads the extension manifest of an installed extension folder
+#[must_use]
+pub fn read_package_json(folder: &Path) -> Option<Value> {
+    [
+        folder.join("package.json"),
+        folder.join("extension").join("package.json"),
+    ]
+    .iter()
+    .find_map(|path| {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    })
+}
+
+/// Returns the total size of all files below a directory
+#[must_use]
+pub fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => directory_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+pub(crate) fn manifest_id(manifest: &Value) -> Option<String> {
+    let publisher = manifest["publisher"].as_str()?;
+    let name = manifest["name"].as_str()?;
+    Some(format!("{publisher}.{name}"))
+}
+
+fn describe_extension(
+    folder: &Path,
+    folder_name: &str,
+    id: String,
+    manifest: &Value,
+    registry_entry: Option<&Value>,
+) -> InstalledExtension {
+    let version = registry_entry
+        .and_then(|entry| entry["version"].as_str())
+        .or_else(|| manifest["version"].as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let target_platform = registry_entry
+        .and_then(|entry| entry["metadata"]["targetPlatform"].as_str())
+        .map(ToString::to_string)
+        .or_else(|| platform_from_folder_name(folder_name, &id, &version))
+        .filter(|platform| platform != "undefined" && platform != "universal");
+
+    let installed_at = registry_entry
+        .and_then(|entry| entry["metadata"]["installedTimestamp"].as_u64())
+        .or_else(|| folder_modified_millis(folder));
+
+    let pre_release = registry_entry
+        .and_then(|entry| entry["metadata"]["preRelease"].as_bool())
+        .unwrap_or(false);
+
+    // Prefer the registered ID since it carries the canonical casing
+    let id = registry_entry
+        .and_then(entry_id)
+        .map_or(id, ToString::to_string);
+
+    InstalledExtension {
+        id,
+        version,
+        target_platform,
+        size: Some(directory_size(folder)),
+        installed_at,
+        location: Some(PathBuf::from(folder)),
+        disabled: false,
+        pre_release,
+    }
+}
+
+/// Extracts the platform suffix of a `publisher.name-1.2.3-linux-x64` folder
+fn platform_from_folder_name(folder_name: &str, id: &str, version: &str) -> Option<String> {
+    let prefix = format!("{id}-{version}-").to_lowercase();
+    let suffix = folder_name
+        .to_lowercase()
+        .strip_prefix(&prefix)?
+        .to_string();
+    suffix.parse::<Architecture>().ok().map(|_| suffix)
+}
+
+fn folder_modified_millis(folder: &Path) -> Option<u64> {
+    let modified = fs::metadata(folder).ok()?.modified().ok()?;
+    let millis = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+    u64::try_from(millis).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_extension(dir: &Path, folder: &str, publisher: &str, name: &str, version: &str) {
+        let folder = dir.join(folder);
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(
+            folder.join("package.json"),
+            format!(r#"{{"publisher":"{publisher}","name":"{name}","version":"{version}"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_scan_extensions_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        write_extension(
+            temp_dir.path(),
+            "ms-python.python-2024.1.0",
+            "ms-python",
+            "python",
+            "2024.1.0",
+        );
+        write_extension(
+            temp_dir.path(),
+            "rust-lang.rust-analyzer-0.3.0-linux-x64",
+            "rust-lang",
+            "rust-analyzer",
+            "0.3.0",
+        );
+        write_extension(temp_dir.path(), "old.ext-1.0.0", "old", "ext", "1.0.0");
+        fs::create_dir(temp_dir.path().join("broken.ext-1.0.0")).unwrap();
+        fs::write(
+            temp_dir.path().join("extensions.json"),
+            r#"[{"identifier":{"id":"ms-python.python"},"version":"2024.1.0",
+                 "relativeLocation":"ms-python.python-2024.1.0",
+                 "metadata":{"installedTimestamp":1700000000000,"targetPlatform":"undefined","preRelease":true}}]"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join(OBSOLETE_FILE_NAME),
+            r#"{"old.ext-1.0.0":true}"#,
+        )
+        .unwrap();
+
+        let extensions = scan_extensions_directory(temp_dir.path()).unwrap();
+        assert_eq!(extensions.len(), 2);
+
+        assert_eq!(extensions[0].id, "ms-python.python");
+        assert_eq!(extensions[0].version, "2024.1.0");
+        assert_eq!(extensions[0].target_platform, None);
+        assert_eq!(extensions[0].installed_at, Some(1_700_000_000_000));
+        assert!(extensions[0].size.unwrap() > 0);
+        assert!(extensions[0].pre_release);
+
+        assert_eq!(extensions[1].id, "rust-lang.rust-analyzer");
+        assert_eq!(extensions[1].target_platform.as_deref(), Some("linux-x64"));
+        assert!(!extensions[1].pre_release);
+    }
+
+    #[test]
+    fn test_parse_cli_listing() {
+        let output = "Extensions installed on WSL: Ubuntu:\nms-python.python@2024.1.0\nrust-lang.rust-analyzer@0.3.0\n";
+
+        let extensions = parse_cli_listing(output);
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions[0].id, "ms-python.python");
+        assert_eq!(extensions[0].version, "2024.1.0");
+        assert_eq!(extensions[1].id, "rust-lang.rust-analyzer");
+    }
+}
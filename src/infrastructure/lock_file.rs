@@ -0,0 +1,80 @@
+use crate::domain::{DomainError, ExtensionLock, LOCK_FILE, LOCK_FORMAT_VERSION};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE_HEADER: &str = "# Generated by `vsix lock`. Do not edit by hand.\n\n";
+
+/// Returns the path of the lockfile belonging to a manifest
+#[must_use]
+pub fn lock_file_path(manifest: &Path) -> PathBuf {
+    manifest.with_file_name(LOCK_FILE)
+}
+
+/// Reads a `vsix.lock` file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or was written by a
+/// newer version of vsix
+pub fn read_extension_lock(path: &Path) -> Result<ExtensionLock, DomainError> {
+    let content = fs::read_to_string(path)?;
+    let lock: ExtensionLock = toml::from_str(&content)
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))?;
+
+    if lock.version > LOCK_FORMAT_VERSION {
+        return Err(DomainError::ParseError(format!(
+            "{} has format version {}, this vsix supports up to {LOCK_FORMAT_VERSION}",
+            path.display(),
+            lock.version
+        )));
+    }
+    Ok(lock)
+}
+
+/// Writes a `vsix.lock` file
+///
+/// # Errors
+///
+/// Returns an error if the lock cannot be serialized or the file cannot be
+/// written
+pub fn write_extension_lock(path: &Path, lock: &ExtensionLock) -> Result<(), DomainError> {
+    let content = toml::to_string(lock)
+        .map_err(|e| DomainError::ParseError(format!("Cannot serialize lockfile: {e}")))?;
+    fs::write(path, format!("{LOCK_FILE_HEADER}{content}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::LockedExtension;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_and_read_extension_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = lock_file_path(&temp_dir.path().join("vsix.toml"));
+        assert_eq!(path, temp_dir.path().join("vsix.lock"));
+
+        let lock = ExtensionLock {
+            extensions: vec![LockedExtension {
+                editor: "vscode".to_string(),
+                id: "rust-lang.rust-analyzer".to_string(),
+                version: "0.3.2".to_string(),
+                target_platform: Some("linux-x64".to_string()),
+                url: "https://example.com/rust-analyzer.vsix".to_string(),
+                sha256: "00ff".to_string(),
+            }],
+            ..ExtensionLock::default()
+        };
+        write_extension_lock(&path, &lock).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(LOCK_FILE_HEADER));
+        assert!(content.contains("[[extension]]"));
+        assert_eq!(read_extension_lock(&path).unwrap(), lock);
+
+        fs::write(&path, "version = 99\n").unwrap();
+        assert!(read_extension_lock(&path).is_err());
+    }
+}
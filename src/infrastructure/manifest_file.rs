@@ -0,0 +1,177 @@
+use crate::domain::{
+    DomainError, EditorManifest, ExtensionManifest, ExtensionRequirement, ReleaseChannel,
+    VersionSelector,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A `vsix.toml` manifest as written on disk
+///
+/// ```toml
+/// # Remove unlisted extensions from every editor
+/// prune = false
+///
+/// [editors.vscode]
+/// extensions = [
+///     "rust-lang.rust-analyzer",
+///     { id = "ms-python.python", version = "2024.2.0" },
+///     { id = "golang.go", channel = "pre-release" },
+///     { id = "ms-vscode.cpptools", platform = "linux-x64" },
+/// ]
+///
+/// [editors.cursor]
+/// prune = true
+/// extensions = ["rust-lang.rust-analyzer"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    #[serde(default)]
+    prune: bool,
+    #[serde(default)]
+    editors: BTreeMap<String, EditorSection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EditorSection {
+    prune: Option<bool>,
+    #[serde(default)]
+    extensions: Vec<RequirementEntry>,
+}
+
+/// An extension entry, either a bare ID or a table with constraints
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RequirementEntry {
+    Id(String),
+    Constrained(ConstrainedEntry),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConstrainedEntry {
+    id: String,
+    version: Option<String>,
+    channel: Option<String>,
+    platform: Option<String>,
+}
+
+/// Reads a `vsix.toml` manifest
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or an entry has an
+/// unknown release channel
+pub fn read_extension_manifest(path: &Path) -> Result<ExtensionManifest, DomainError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        DomainError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("{}: {e}", path.display()),
+        ))
+    })?;
+    parse_extension_manifest(&content)
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))
+}
+
+fn parse_extension_manifest(content: &str) -> Result<ExtensionManifest, String> {
+    let file: ManifestFile = toml::from_str(content).map_err(|e| e.to_string())?;
+
+    let editors = file
+        .editors
+        .into_iter()
+        .map(|(editor, section)| {
+            let extensions = section
+                .extensions
+                .into_iter()
+                .map(|entry| match entry {
+                    RequirementEntry::Id(id) => Ok(ExtensionRequirement {
+                        id,
+                        selector: VersionSelector::default(),
+                    }),
+                    RequirementEntry::Constrained(entry) => Ok(ExtensionRequirement {
+                        id: entry.id,
+                        selector: VersionSelector {
+                            version: entry.version,
+                            channel: entry
+                                .channel
+                                .as_deref()
+                                .map(str::parse::<ReleaseChannel>)
+                                .transpose()?
+                                .unwrap_or_default(),
+                            platform: entry.platform,
+                            engine: None,
+                        },
+                    }),
+                })
+                .collect::<Result<_, String>>()?;
+
+            Ok(EditorManifest {
+                editor,
+                extensions,
+                prune: section.prune.unwrap_or(file.prune),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(ExtensionManifest { editors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_extension_manifest() {
+        let manifest = parse_extension_manifest(
+            r#"
+            prune = true
+
+            [editors.vscode]
+            prune = false
+            extensions = [
+                "rust-lang.rust-analyzer",
+                { id = "golang.go", version = "0.41.0", channel = "pre-release", platform = "linux-x64" },
+            ]
+
+            [editors.cursor]
+            extensions = []
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.editors.len(), 2);
+        let cursor = &manifest.editors[0];
+        assert_eq!(cursor.editor, "cursor");
+        assert!(cursor.prune);
+
+        let vscode = &manifest.editors[1];
+        assert!(!vscode.prune);
+        assert_eq!(vscode.extensions[0].selector, VersionSelector::default());
+        assert_eq!(
+            vscode.extensions[1],
+            ExtensionRequirement {
+                id: "golang.go".to_string(),
+                selector: VersionSelector {
+                    version: Some("0.41.0".to_string()),
+                    channel: ReleaseChannel::PreRelease,
+                    platform: Some("linux-x64".to_string()),
+                    engine: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_extension_manifest_rejects_unknown_channel() {
+        let result = parse_extension_manifest(
+            r#"
+            [editors.vscode]
+            extensions = [{ id = "golang.go", channel = "nightly" }]
+            "#,
+        );
+        assert!(result.unwrap_err().contains("nightly"));
+    }
+}
@@ -1,7 +1,38 @@
-use crate::domain::{DomainError, Extension, ExtensionRepository, SearchResult};
+use crate::domain::{
+    Architecture, DomainError, Extension, ExtensionRepository, ReleaseChannel, SearchResult,
+    VersionSelector, engine_accepts,
+};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::json;
+use std::time::{Duration, Instant};
+
+/// Flags of a gallery query (`ExtensionQueryFlags`), selecting what the
+/// answer includes
+pub(crate) mod query_flags {
+    pub const INCLUDE_VERSIONS: u32 = 0x1;
+    pub const INCLUDE_FILES: u32 = 0x2;
+    pub const INCLUDE_VERSION_PROPERTIES: u32 = 0x10;
+    pub const INCLUDE_ASSET_URI: u32 = 0x80;
+    pub const INCLUDE_STATISTICS: u32 = 0x100;
+    /// Only the newest version, whatever its channel or target platform
+    pub const INCLUDE_LATEST_VERSION_ONLY: u32 = 0x200;
+
+    /// Metadata of every version, for picking a stable, pre-release, exact or
+    /// platform-specific one
+    pub const ALL_VERSIONS: u32 = INCLUDE_VERSIONS
+        | INCLUDE_FILES
+        | INCLUDE_VERSION_PROPERTIES
+        | INCLUDE_ASSET_URI
+        | INCLUDE_STATISTICS;
+
+    /// Metadata of the newest version only, enough for search results
+    pub const LATEST_VERSION: u32 = INCLUDE_FILES
+        | INCLUDE_VERSION_PROPERTIES
+        | INCLUDE_ASSET_URI
+        | INCLUDE_STATISTICS
+        | INCLUDE_LATEST_VERSION_ONLY;
+}
 
 pub struct MarketplaceClient {
     client: Client,
@@ -29,39 +60,74 @@ impl MarketplaceClient {
     }
 }
 
-#[async_trait]
-impl ExtensionRepository for MarketplaceClient {
-    async fn search(
+const DEFAULT_MARKETPLACE_URL: &str = "https://marketplace.visualstudio.com";
+
+/// Returns the gallery service URL for a marketplace URL
+///
+/// Accepts both a marketplace host (`https://marketplace.visualstudio.com`) and a
+/// gallery service URL as found in an editor's `product.json`
+/// (`https://open-vsx.org/vscode/gallery`).
+#[must_use]
+pub fn gallery_service_url(marketplace_url: Option<&str>) -> String {
+    let url = marketplace_url
+        .unwrap_or(DEFAULT_MARKETPLACE_URL)
+        .trim_end_matches('/');
+
+    if url.ends_with("/gallery") {
+        url.to_string()
+    } else {
+        format!("{url}/_apis/public/gallery")
+    }
+}
+
+impl MarketplaceClient {
+    /// Sends a one-result query to a gallery and returns how long it took
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the gallery cannot be reached or does not answer
+    /// with a query result
+    pub async fn check_gallery(
         &self,
-        query: &str,
         marketplace_url: Option<&str>,
-    ) -> Result<SearchResult, DomainError> {
-        let base_url = marketplace_url.unwrap_or("https://marketplace.visualstudio.com");
-        let url = format!("{base_url}/_apis/public/gallery/extensionquery");
-
+    ) -> Result<Duration, DomainError> {
         let request_body = json!({
             "filters": [{
-                "criteria": [
-                    {
-                        "filterType": 8,
-                        "value": "Microsoft.VisualStudio.Code"
-                    },
-                    {
-                        "filterType": 10,
-                        "value": query
-                    }
-                ]
+                "criteria": [{
+                    "filterType": 8,
+                    "value": "Microsoft.VisualStudio.Code"
+                }],
+                "pageSize": 1
             }],
             "assetTypes": [],
-            "flags": 914
+            "flags": 0
         });
 
+        let started = Instant::now();
+        let json = self.query(marketplace_url, &request_body).await?;
+        if json["results"].is_array() {
+            Ok(started.elapsed())
+        } else {
+            Err(DomainError::ParseError(
+                "The response is not a gallery query result".to_string(),
+            ))
+        }
+    }
+
+    /// Sends a request to the gallery `extensionquery` endpoint
+    async fn query(
+        &self,
+        marketplace_url: Option<&str>,
+        request_body: &serde_json::Value,
+    ) -> Result<serde_json::Value, DomainError> {
+        let url = format!("{}/extensionquery", gallery_service_url(marketplace_url));
+
         let response = self
             .client
             .post(&url)
             .header("Content-Type", "application/json")
             .header("Accept", "application/json;api-version=7.2-preview.1")
-            .json(&request_body)
+            .json(request_body)
             .send()
             .await
             .map_err(|e| DomainError::NetworkError(e.to_string()))?;
@@ -73,11 +139,38 @@ impl ExtensionRepository for MarketplaceClient {
             )));
         }
 
-        let json: serde_json::Value = response
+        response
             .json()
             .await
-            .map_err(|e| DomainError::ParseError(e.to_string()))?;
+            .map_err(|e| DomainError::ParseError(e.to_string()))
+    }
+}
 
+#[async_trait]
+impl ExtensionRepository for MarketplaceClient {
+    async fn search(
+        &self,
+        query: &str,
+        marketplace_url: Option<&str>,
+    ) -> Result<SearchResult, DomainError> {
+        let request_body = json!({
+            "filters": [{
+                "criteria": [
+                    {
+                        "filterType": 8,
+                        "value": "Microsoft.VisualStudio.Code"
+                    },
+                    {
+                        "filterType": 10,
+                        "value": query
+                    }
+                ]
+            }],
+            "assetTypes": [],
+            "flags": query_flags::LATEST_VERSION
+        });
+
+        let json = self.query(marketplace_url, &request_body).await?;
         let extensions = parse_search_results(&json);
 
         Ok(SearchResult {
@@ -100,16 +193,139 @@ impl ExtensionRepository for MarketplaceClient {
             .ok_or_else(|| DomainError::ExtensionNotFound(id.to_string()))
     }
 
+    async fn get_extensions(
+        &self,
+        ids: &[String],
+        engine: Option<&str>,
+        marketplace_url: Option<&str>,
+    ) -> Result<Vec<Extension>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut criteria = vec![json!({
+            "filterType": 8,
+            "value": "Microsoft.VisualStudio.Code"
+        })];
+        criteria.extend(ids.iter().map(|id| {
+            json!({
+                "filterType": 7,
+                "value": id
+            })
+        }));
+
+        // Every version is requested, not just the latest one, so that a newer
+        // stable release or a build for this platform and editor can be picked
+        // over a newer pre-release, another platform's build or a version that
+        // needs a newer editor
+        let request_body = json!({
+            "filters": [{
+                "criteria": criteria,
+                "pageNumber": 1,
+                "pageSize": ids.len()
+            }],
+            "assetTypes": [],
+            "flags": query_flags::ALL_VERSIONS
+        });
+
+        let json = self.query(marketplace_url, &request_body).await?;
+
+        Ok(parse_results(&json, engine)
+            .into_iter()
+            .filter(|ext| {
+                ids.iter()
+                    .any(|id| id.eq_ignore_ascii_case(&ext.unique_identifier()))
+            })
+            .collect())
+    }
+
+    async fn resolve_extension(
+        &self,
+        id: &str,
+        selector: &VersionSelector,
+        marketplace_url: Option<&str>,
+    ) -> Result<Extension, DomainError> {
+        if selector.is_latest() {
+            return self.get_extension(id, marketplace_url).await;
+        }
+
+        // Unlike a search this query asks for every version, not just the latest
+        let request_body = json!({
+            "filters": [{
+                "criteria": [
+                    {
+                        "filterType": 8,
+                        "value": "Microsoft.VisualStudio.Code"
+                    },
+                    {
+                        "filterType": 7,
+                        "value": id
+                    }
+                ],
+                "pageNumber": 1,
+                "pageSize": 1
+            }],
+            "assetTypes": [],
+            "flags": query_flags::ALL_VERSIONS
+        });
+
+        let json = self.query(marketplace_url, &request_body).await?;
+        let not_found = || {
+            DomainError::ExtensionNotFound(
+                selector
+                    .version
+                    .as_ref()
+                    .map_or_else(|| id.to_string(), |version| format!("{id}@{version}")),
+            )
+        };
+
+        let extension = json["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|result| result["extensions"].as_array())
+            .flatten()
+            .find(|extension| {
+                let publisher = extension["publisher"]["publisherName"].as_str();
+                let name = extension["extensionName"].as_str();
+                publisher.zip(name).is_some_and(|(publisher, name)| {
+                    format!("{publisher}.{name}").eq_ignore_ascii_case(id)
+                })
+            })
+            .ok_or_else(not_found)?;
+        let versions = extension["versions"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let selected_version = select_version(versions, selector).ok_or_else(not_found)?;
+
+        parse_extension_version(extension, Some(selected_version))
+    }
+
     async fn download(
         &self,
         extension: &Extension,
         target_platform: Option<&str>,
     ) -> Result<Vec<u8>, DomainError> {
-        let url = extension.download_url(target_platform);
+        self.download_url(&extension.download_url(target_platform))
+            .await
+    }
+}
 
+impl MarketplaceClient {
+    /// Downloads a VSIX package from an arbitrary URL
+    ///
+    /// Gzip-encoded responses are decompressed, as served by the Visual Studio
+    /// Marketplace CDN.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DomainError::NetworkError` if the request fails or the server does
+    /// not answer with a success status
+    pub async fn download_url(&self, url: &str) -> Result<Vec<u8>, DomainError> {
         let response = self
             .client
-            .get(&url)
+            .get(url)
             .send()
             .await
             .map_err(|e| DomainError::NetworkError(e.to_string()))?;
@@ -144,13 +360,19 @@ impl ExtensionRepository for MarketplaceClient {
 }
 
 fn parse_search_results(json: &serde_json::Value) -> Vec<Extension> {
+    parse_results(json, None)
+}
+
+/// Parses the extensions of a gallery response, picking the newest version
+/// `engine` accepts
+fn parse_results(json: &serde_json::Value, engine: Option<&str>) -> Vec<Extension> {
     let mut extensions = Vec::new();
 
     if let Some(results) = json["results"].as_array() {
         for result in results {
             if let Some(exts) = result["extensions"].as_array() {
                 for ext in exts {
-                    if let Ok(extension) = parse_extension(ext) {
+                    if let Ok(extension) = parse_extension(ext, engine) {
                         extensions.push(extension);
                     }
                 }
@@ -161,7 +383,22 @@ fn parse_search_results(json: &serde_json::Value) -> Vec<Extension> {
     extensions
 }
 
-fn parse_extension(json: &serde_json::Value) -> Result<Extension, DomainError> {
+fn parse_extension(
+    json: &serde_json::Value,
+    engine: Option<&str>,
+) -> Result<Extension, DomainError> {
+    let selected_version = json["versions"]
+        .as_array()
+        .and_then(|versions| select_compatible_version(versions, engine));
+
+    parse_extension_version(json, selected_version)
+}
+
+/// Builds an extension from a gallery entry and the version picked from it
+fn parse_extension_version(
+    json: &serde_json::Value,
+    selected_version: Option<&serde_json::Value>,
+) -> Result<Extension, DomainError> {
     let publisher = json["publisher"]["publisherName"]
         .as_str()
         .ok_or_else(|| DomainError::ParseError("Missing publisher name".to_string()))?;
@@ -176,13 +413,52 @@ fn parse_extension(json: &serde_json::Value) -> Result<Extension, DomainError> {
         .as_str()
         .map(std::string::ToString::to_string);
 
-    let version = json["versions"]
-        .as_array()
-        .and_then(|v| v.first())
+    let version = selected_version
         .and_then(|v| v["version"].as_str())
         .unwrap_or("latest")
         .to_string();
 
+    // Galleries other than the VS Code marketplace only serve packages from the
+    // asset URLs they report
+    let package_url = selected_version
+        .and_then(|v| v["files"].as_array())
+        .and_then(|files| {
+            files.iter().find(|file| {
+                file["assetType"].as_str() == Some("Microsoft.VisualStudio.Services.VSIXPackage")
+            })
+        })
+        .and_then(|file| file["source"].as_str())
+        .map(ToString::to_string);
+
+    let target_platform = selected_version
+        .and_then(|v| v["targetPlatform"].as_str())
+        .filter(|target| !matches!(*target, "universal" | "undefined"))
+        .map(ToString::to_string);
+
+    // Dependencies and pack members are both installed alongside the extension
+    let dependencies = selected_version
+        .and_then(|v| v["properties"].as_array())
+        .map(|properties| {
+            properties
+                .iter()
+                .filter(|property| {
+                    matches!(
+                        property["key"].as_str(),
+                        Some(
+                            "Microsoft.VisualStudio.Code.ExtensionDependencies"
+                                | "Microsoft.VisualStudio.Code.ExtensionPack"
+                        )
+                    )
+                })
+                .filter_map(|property| property["value"].as_str())
+                .flat_map(|value| value.split(','))
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(ToString::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
     let downloads = json["statistics"]
         .as_array()
         .and_then(|stats| {
@@ -201,5 +477,247 @@ fn parse_extension(json: &serde_json::Value) -> Result<Extension, DomainError> {
         display_name: display_name.to_string(),
         description,
         downloads,
+        package_url,
+        target_platform,
+        dependencies,
+    })
+}
+
+/// Picks the newest version that runs on this machine and in the editor
+///
+/// Versions built for another target platform or whose engine range rejects
+/// `engine` are skipped, and stable releases are preferred over pre-releases.
+/// Falls back to the first listed version unless an engine is given, since an
+/// editor too old for every version has nothing to install.
+fn select_compatible_version<'a>(
+    versions: &'a [serde_json::Value],
+    engine: Option<&str>,
+) -> Option<&'a serde_json::Value> {
+    let platform = Architecture::detect();
+    let platform = platform.to_platform_string();
+    let is_compatible = |version: &&serde_json::Value| is_compatible(version, platform, engine);
+
+    versions
+        .iter()
+        .filter(is_compatible)
+        .find(|version| !is_pre_release(version))
+        .or_else(|| versions.iter().find(is_compatible))
+        .or_else(|| versions.first().filter(|_| engine.is_none()))
+}
+
+/// Picks the version a selector asks for, newest first
+///
+/// Versions built for another target platform than the selected one, or whose
+/// engine range rejects the selected editor, are skipped. Without an exact version the newest stable release is picked, or the
+/// newest release of any kind on the pre-release channel.
+fn select_version<'a>(
+    versions: &'a [serde_json::Value],
+    selector: &VersionSelector,
+) -> Option<&'a serde_json::Value> {
+    let detected = Architecture::detect();
+    let platform = selector
+        .platform
+        .as_deref()
+        .or_else(|| detected.to_platform_string());
+
+    versions.iter().find(|version| {
+        is_compatible(version, platform, selector.engine.as_deref())
+            && match &selector.version {
+                Some(wanted) => version["version"].as_str() == Some(wanted.as_str()),
+                None => selector.channel == ReleaseChannel::PreRelease || !is_pre_release(version),
+            }
     })
 }
+
+fn is_compatible(
+    version: &serde_json::Value,
+    platform: Option<&str>,
+    engine: Option<&str>,
+) -> bool {
+    let platform_matches = match version["targetPlatform"].as_str() {
+        None | Some("universal" | "undefined") => true,
+        Some(target) => Some(target) == platform,
+    };
+    let engine_matches = engine.is_none_or(|engine| {
+        version_property(version, "Microsoft.VisualStudio.Code.Engine")
+            .is_none_or(|range| engine_accepts(range, engine))
+    });
+
+    platform_matches && engine_matches
+}
+
+fn is_pre_release(version: &serde_json::Value) -> bool {
+    version_property(version, "Microsoft.VisualStudio.Code.PreRelease") == Some("true")
+}
+
+fn version_property<'a>(version: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    version["properties"]
+        .as_array()?
+        .iter()
+        .find(|property| property["key"] == key)
+        .and_then(|property| property["value"].as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_flags() {
+        assert_eq!(query_flags::ALL_VERSIONS, 403);
+        assert_eq!(query_flags::LATEST_VERSION, 914);
+    }
+
+    #[test]
+    fn test_gallery_service_url() {
+        assert_eq!(
+            gallery_service_url(None),
+            "https://marketplace.visualstudio.com/_apis/public/gallery"
+        );
+        assert_eq!(
+            gallery_service_url(Some("https://open-vsx.org/vscode/gallery/")),
+            "https://open-vsx.org/vscode/gallery"
+        );
+        assert_eq!(
+            gallery_service_url(Some("http://localhost:8080")),
+            "http://localhost:8080/_apis/public/gallery"
+        );
+    }
+
+    #[test]
+    fn test_parse_extension_uses_package_asset() {
+        let json = json!({
+            "publisher": { "publisherName": "redhat" },
+            "extensionName": "java",
+            "versions": [{
+                "version": "1.30.0",
+                "files": [
+                    {
+                        "assetType": "Microsoft.VisualStudio.Services.Icons.Default",
+                        "source": "https://open-vsx.org/api/redhat/java/1.30.0/file/icon.png"
+                    },
+                    {
+                        "assetType": "Microsoft.VisualStudio.Services.VSIXPackage",
+                        "source": "https://open-vsx.org/api/redhat/java/1.30.0/file/redhat.java-1.30.0.vsix"
+                    }
+                ]
+            }]
+        });
+
+        let extension = parse_extension(&json, None).unwrap();
+        assert_eq!(
+            extension.download_url(None),
+            "https://open-vsx.org/api/redhat/java/1.30.0/file/redhat.java-1.30.0.vsix"
+        );
+    }
+
+    #[test]
+    fn test_parse_extension_dependencies() {
+        let json = json!({
+            "publisher": { "publisherName": "vscjava" },
+            "extensionName": "vscode-java-pack",
+            "versions": [{
+                "version": "0.29.0",
+                "properties": [
+                    {
+                        "key": "Microsoft.VisualStudio.Code.ExtensionDependencies",
+                        "value": "redhat.java"
+                    },
+                    {
+                        "key": "Microsoft.VisualStudio.Code.ExtensionPack",
+                        "value": "vscjava.vscode-java-debug, vscjava.vscode-maven"
+                    }
+                ]
+            }]
+        });
+
+        let extension = parse_extension(&json, None).unwrap();
+        assert_eq!(
+            extension.dependencies,
+            vec![
+                "redhat.java",
+                "vscjava.vscode-java-debug",
+                "vscjava.vscode-maven"
+            ]
+        );
+        assert_eq!(extension.target_platform, None);
+    }
+
+    #[test]
+    fn test_select_version() {
+        let pre_release = json!([{
+            "key": "Microsoft.VisualStudio.Code.PreRelease",
+            "value": "true"
+        }]);
+        let versions = vec![
+            json!({ "version": "2.1.0", "properties": pre_release }),
+            json!({ "version": "2.0.0", "targetPlatform": "linux-x64" }),
+            json!({ "version": "2.0.0", "targetPlatform": "win32-x64" }),
+            json!({ "version": "1.0.0" }),
+        ];
+        let select = |version: Option<&str>, channel, platform: &str| {
+            let selector = VersionSelector {
+                version: version.map(ToString::to_string),
+                channel,
+                platform: Some(platform.to_string()),
+                engine: None,
+            };
+            select_version(&versions, &selector).map(|v| {
+                format!(
+                    "{}{}",
+                    v["version"].as_str().unwrap(),
+                    v["targetPlatform"].as_str().unwrap_or_default()
+                )
+            })
+        };
+
+        assert_eq!(
+            select(None, ReleaseChannel::Stable, "win32-x64"),
+            Some("2.0.0win32-x64".to_string())
+        );
+        assert_eq!(
+            select(None, ReleaseChannel::PreRelease, "linux-x64"),
+            Some("2.1.0".to_string())
+        );
+        assert_eq!(
+            select(Some("1.0.0"), ReleaseChannel::Stable, "linux-x64"),
+            Some("1.0.0".to_string())
+        );
+        assert_eq!(
+            select(Some("2.0.0"), ReleaseChannel::Stable, "darwin-arm64"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_version_skips_newer_engines() {
+        let engine = |range: &str| {
+            json!([{
+                "key": "Microsoft.VisualStudio.Code.Engine",
+                "value": range
+            }])
+        };
+        let versions = vec![
+            json!({ "version": "3.0.0", "properties": engine("^1.96.0") }),
+            json!({ "version": "2.5.0", "properties": engine("^1.90.0") }),
+        ];
+
+        let selected = |engine: Option<&str>| {
+            select_compatible_version(&versions, engine).map(|v| v["version"].as_str().unwrap())
+        };
+        assert_eq!(selected(None), Some("3.0.0"));
+        assert_eq!(selected(Some("1.96.2")), Some("3.0.0"));
+        assert_eq!(selected(Some("1.93.1")), Some("2.5.0"));
+        assert_eq!(selected(Some("1.85.0")), None);
+
+        let selector = VersionSelector {
+            channel: ReleaseChannel::PreRelease,
+            engine: Some("1.93.1".to_string()),
+            ..VersionSelector::default()
+        };
+        assert_eq!(
+            select_version(&versions, &selector).map(|v| v["version"].as_str().unwrap()),
+            Some("2.5.0")
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use super::super::marketplace_client::MarketplaceClient;
+    use super::super::marketplace_client::{MarketplaceClient, query_flags};
     use crate::domain::ExtensionRepository;
 
     #[tokio::test]
@@ -61,4 +61,73 @@ mod tests {
             "Should be a valid VSIX/ZIP or gzipped file"
         );
     }
+
+    #[tokio::test]
+    async fn test_get_extensions_batches_ids_and_selects_compatible_version() {
+        use mockito::Matcher;
+        use serde_json::json;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/_apis/public/gallery/extensionquery")
+            .match_body(Matcher::PartialJson(json!({
+                "filters": [{
+                    "criteria": [
+                        { "filterType": 8, "value": "Microsoft.VisualStudio.Code" },
+                        { "filterType": 7, "value": "ms-python.python" },
+                        { "filterType": 7, "value": "rust-lang.rust-analyzer" }
+                    ]
+                }],
+                // IncludeVersions without IncludeLatestVersionOnly, so the
+                // gallery answers with every version as below
+                "flags": query_flags::ALL_VERSIONS
+            })))
+            .with_body(
+                json!({
+                    "results": [{
+                        "extensions": [
+                            {
+                                "publisher": { "publisherName": "ms-python" },
+                                "extensionName": "python",
+                                "versions": [
+                                    {
+                                        "version": "2024.3.0",
+                                        "properties": [{
+                                            "key": "Microsoft.VisualStudio.Code.PreRelease",
+                                            "value": "true"
+                                        }]
+                                    },
+                                    { "version": "2024.2.5", "targetPlatform": "web" },
+                                    { "version": "2024.2.0" }
+                                ]
+                            },
+                            {
+                                "publisher": { "publisherName": "other" },
+                                "extensionName": "unrelated",
+                                "versions": [{ "version": "1.0.0" }]
+                            }
+                        ]
+                    }]
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = MarketplaceClient::new();
+        let ids = vec![
+            "ms-python.python".to_string(),
+            "rust-lang.rust-analyzer".to_string(),
+        ];
+        let extensions = client
+            .get_extensions(&ids, None, Some(&server.url()))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(extensions.len(), 1);
+        assert_eq!(extensions[0].unique_identifier(), "ms-python.python");
+        assert_eq!(extensions[0].version, "2024.2.0");
+    }
 }
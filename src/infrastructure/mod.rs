@@ -1,9 +1,35 @@
+pub mod code_profile_file;
+pub mod config;
+pub mod environment;
+pub mod extensions_registry;
 pub mod file_system;
+pub mod global_state;
 pub mod installation_service;
+pub mod installed_extensions;
+pub mod lock_file;
+pub mod manifest_file;
 pub mod marketplace_client;
 #[cfg(test)]
 mod marketplace_tests;
+pub mod profiles;
+pub mod stale_extensions;
+pub mod vsix_archive;
+pub mod vsix_packer;
+pub mod workspace_config;
 
+pub use code_profile_file::*;
+pub use config::*;
+pub use environment::*;
+pub use extensions_registry::*;
 pub use file_system::*;
+pub use global_state::*;
 pub use installation_service::*;
+pub use installed_extensions::*;
+pub use lock_file::*;
+pub use manifest_file::*;
 pub use marketplace_client::*;
+pub use profiles::*;
+pub use stale_extensions::*;
+pub use vsix_archive::*;
+pub use vsix_packer::*;
+pub use workspace_config::*;
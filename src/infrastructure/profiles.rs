@@ -0,0 +1,111 @@
+use super::extensions_registry::REGISTRY_FILE_NAME;
+use crate::domain::DomainError;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name VS Code uses for the profile that is active when none is selected
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// A profile listed in an editor's global storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserDataProfile {
+    pub name: String,
+    /// Folder of the profile below `User/profiles`
+    pub location: String,
+}
+
+impl UserDataProfile {
+    /// Returns the profile's `extensions.json`
+    #[must_use]
+    pub fn registry_path(&self, user_data_dir: &Path) -> PathBuf {
+        user_data_dir
+            .join("User")
+            .join("profiles")
+            .join(&self.location)
+            .join(REGISTRY_FILE_NAME)
+    }
+}
+
+/// Reads the profiles of an editor from `User/globalStorage/storage.json`
+///
+/// A missing storage file means no profiles have been created yet.
+///
+/// # Errors
+///
+/// Returns an error if the storage file cannot be read or parsed
+pub fn read_profiles(user_data_dir: &Path) -> Result<Vec<UserDataProfile>, DomainError> {
+    let path = user_data_dir
+        .join("User")
+        .join("globalStorage")
+        .join("storage.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let storage: Value = serde_json::from_str(&content)
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))?;
+
+    Ok(storage["userDataProfiles"]
+        .as_array()
+        .map(|profiles| profiles.iter().filter_map(parse_profile).collect())
+        .unwrap_or_default())
+}
+
+fn parse_profile(profile: &Value) -> Option<UserDataProfile> {
+    let name = profile["name"].as_str()?;
+    // Older releases stored the location as a URI instead of a folder name
+    let location = profile["location"]
+        .as_str()
+        .or_else(|| profile["location"]["path"].as_str())?;
+    let location = location.rsplit('/').next()?;
+
+    // The location is joined to a path; never let it escape the profiles folder
+    if location.is_empty() || location == "." || location == ".." || location.contains('\\') {
+        return None;
+    }
+
+    Some(UserDataProfile {
+        name: name.to_string(),
+        location: location.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_profiles() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage_dir = temp_dir.path().join("User").join("globalStorage");
+        fs::create_dir_all(&storage_dir).unwrap();
+        fs::write(
+            storage_dir.join("storage.json"),
+            r#"{
+                "userDataProfiles": [
+                    { "location": "-6d7e9a3", "name": "Rust" },
+                    { "location": { "path": "/home/user/.config/Code/User/profiles/4f1b2c" }, "name": "Python" },
+                    { "location": "..", "name": "Evil" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let profiles = read_profiles(temp_dir.path()).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Rust");
+        assert_eq!(
+            profiles[1].registry_path(temp_dir.path()),
+            temp_dir.path().join("User/profiles/4f1b2c/extensions.json")
+        );
+    }
+
+    #[test]
+    fn test_read_profiles_without_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(read_profiles(temp_dir.path()).unwrap().is_empty());
+    }
+}
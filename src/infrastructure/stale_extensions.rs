@@ -0,0 +1,383 @@
+use super::extensions_registry::{
+    ExtensionsRegistry, REGISTRY_FILE_NAME, entry_id, entry_relative_location,
+};
+use super::installed_extensions::{
+    OBSOLETE_FILE_NAME, directory_size, manifest_id, read_obsolete_folders, read_package_json,
+};
+use crate::domain::{DomainError, StaleExtension, StaleReason, compare_versions};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An extension folder with a readable manifest
+struct ExtensionFolder {
+    name: String,
+    path: PathBuf,
+    id: String,
+    version: String,
+}
+
+/// Finds the folders of an extensions directory that can be removed
+///
+/// Folders listed in `.obsolete` are pending deletion by the editor, and
+/// folders without a `package.json` are broken, as are `extensions.json`
+/// entries whose folder is gone. When the directory has an `extensions.json`,
+/// folders neither it nor one of `profile_registries` references are old
+/// versions if another folder of the same extension is registered, and orphans
+/// otherwise. Without a registry, every version but the newest of an extension
+/// is old.
+///
+/// # Errors
+///
+/// Returns an error if the directory, its registry or `.obsolete` file, or a
+/// profile registry cannot be read
+pub fn find_stale_extensions(
+    extensions_dir: &Path,
+    profile_registries: &[PathBuf],
+) -> Result<Vec<StaleExtension>, DomainError> {
+    if !extensions_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let registry = ExtensionsRegistry::load(extensions_dir)?;
+    let has_registry = extensions_dir.join(REGISTRY_FILE_NAME).is_file();
+    let mut referenced = registered_folders(&registry);
+    for path in profile_registries {
+        referenced.extend(registered_folders(&ExtensionsRegistry::load_file(
+            path.clone(),
+        )?));
+    }
+    let obsolete = read_obsolete_folders(extensions_dir)?;
+
+    let mut stale = Vec::new();
+    let mut folders = Vec::new();
+    let mut present = HashSet::new();
+
+    for dir_entry in fs::read_dir(extensions_dir)? {
+        let dir_entry = dir_entry?;
+        let name = dir_entry.file_name().to_string_lossy().to_string();
+        // Dot folders are staging areas of installs that may still be running
+        if !dir_entry.file_type()?.is_dir() || name.starts_with('.') {
+            continue;
+        }
+        present.insert(name.to_lowercase());
+
+        let path = dir_entry.path();
+        let manifest = read_package_json(&path);
+        let id = manifest.as_ref().and_then(manifest_id);
+        let version = manifest
+            .as_ref()
+            .and_then(|manifest| manifest["version"].as_str())
+            .map(ToString::to_string);
+
+        match (obsolete.contains(&name), id) {
+            (false, Some(id)) => folders.push(ExtensionFolder {
+                name,
+                path,
+                id,
+                version: version.unwrap_or_default(),
+            }),
+            (is_obsolete, id) => stale.push(StaleExtension {
+                size: directory_size(&path),
+                folder: path,
+                reason: if is_obsolete {
+                    StaleReason::Obsolete
+                } else {
+                    StaleReason::Broken
+                },
+                id,
+                version,
+            }),
+        }
+    }
+
+    for entry in registry.entries() {
+        if let Some(location) = entry_relative_location(entry)
+            && !present.contains(&location.to_lowercase())
+        {
+            stale.push(StaleExtension {
+                folder: extensions_dir.join(location),
+                reason: StaleReason::Broken,
+                id: entry_id(entry).map(ToString::to_string),
+                version: entry["version"].as_str().map(ToString::to_string),
+                size: 0,
+            });
+        }
+    }
+
+    let registered_ids: HashSet<String> = folders
+        .iter()
+        .filter(|folder| referenced.contains(&folder.name.to_lowercase()))
+        .map(|folder| folder.id.to_lowercase())
+        .collect();
+
+    for folder in &folders {
+        let reason = if has_registry {
+            if referenced.contains(&folder.name.to_lowercase()) {
+                continue;
+            }
+            if registered_ids.contains(&folder.id.to_lowercase()) {
+                StaleReason::OldVersion
+            } else {
+                StaleReason::Orphaned
+            }
+        } else {
+            let has_newer = folders.iter().any(|other| {
+                other.id.eq_ignore_ascii_case(&folder.id)
+                    && compare_versions(&other.version, &folder.version) == Ordering::Greater
+            });
+            if !has_newer {
+                continue;
+            }
+            StaleReason::OldVersion
+        };
+
+        stale.push(StaleExtension {
+            folder: folder.path.clone(),
+            reason,
+            id: Some(folder.id.clone()),
+            version: Some(folder.version.clone()),
+            size: directory_size(&folder.path),
+        });
+    }
+
+    stale.sort_by(|a, b| a.folder.cmp(&b.folder));
+    Ok(stale)
+}
+
+/// Removes stale extension folders found by [`find_stale_extensions`]
+///
+/// Registry entries pointing at the removed folders are dropped, and removed
+/// obsolete folders are taken off the `.obsolete` file like the editor does
+/// once it has deleted them.
+///
+/// # Returns
+///
+/// Returns the number of bytes freed.
+///
+/// # Errors
+///
+/// Returns an error if a folder is not directly inside `extensions_dir`, or a
+/// folder, the registry or the `.obsolete` file cannot be written
+pub fn remove_stale_extensions(
+    extensions_dir: &Path,
+    stale: &[StaleExtension],
+) -> Result<u64, DomainError> {
+    let mut registry = ExtensionsRegistry::load(extensions_dir)?;
+    let mut registry_changed = false;
+    let mut removed_obsolete = Vec::new();
+    let mut reclaimed = 0;
+
+    for extension in stale {
+        // Never follow a path outside the extensions directory
+        let folder_name = extension
+            .folder
+            .file_name()
+            .filter(|_| extension.folder.parent() == Some(extensions_dir))
+            .ok_or_else(|| {
+                DomainError::DirectoryNotFound(format!(
+                    "{} is not inside {}",
+                    extension.folder.display(),
+                    extensions_dir.display()
+                ))
+            })?
+            .to_string_lossy()
+            .to_string();
+
+        if extension.folder.is_dir() {
+            fs::remove_dir_all(&extension.folder)?;
+            reclaimed += extension.size;
+        }
+        registry_changed |= !registry.remove_location(&folder_name).is_empty();
+        if extension.reason == StaleReason::Obsolete {
+            removed_obsolete.push(folder_name);
+        }
+    }
+
+    if registry_changed {
+        registry.save()?;
+    }
+    if !removed_obsolete.is_empty() {
+        forget_obsolete_folders(extensions_dir, &removed_obsolete)?;
+    }
+    Ok(reclaimed)
+}
+
+/// Returns the lowercase folder names an `extensions.json` references
+fn registered_folders(registry: &ExtensionsRegistry) -> HashSet<String> {
+    registry
+        .entries()
+        .iter()
+        .filter_map(entry_relative_location)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Takes folders off the `.obsolete` file, deleting it once it is empty
+fn forget_obsolete_folders(extensions_dir: &Path, folders: &[String]) -> Result<(), DomainError> {
+    let path = extensions_dir.join(OBSOLETE_FILE_NAME);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut marked: Map<String, Value> = serde_json::from_str(&content).unwrap_or_default();
+    marked.retain(|folder, _| !folders.contains(folder));
+
+    if marked.is_empty() {
+        fs::remove_file(&path)?;
+    } else {
+        let content =
+            serde_json::to_string(&marked).map_err(|e| DomainError::ParseError(e.to_string()))?;
+        fs::write(&path, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn create_extension(extensions_dir: &Path, folder: &str, name: &str, version: &str) {
+        let dir = extensions_dir.join(folder);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("package.json"),
+            json!({ "publisher": "acme", "name": name, "version": version }).to_string(),
+        )
+        .unwrap();
+    }
+
+    fn registry_entry(id: &str, version: &str, folder: &str) -> Value {
+        json!({
+            "identifier": { "id": id },
+            "version": version,
+            "relativeLocation": folder
+        })
+    }
+
+    fn reasons(stale: &[StaleExtension]) -> Vec<(String, StaleReason)> {
+        stale
+            .iter()
+            .map(|ext| {
+                (
+                    ext.folder
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    ext.reason,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_and_remove_stale_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_extension(dir, "acme.tools-2.0.0", "tools", "2.0.0");
+        create_extension(dir, "acme.tools-1.0.0", "tools", "1.0.0");
+        create_extension(dir, "acme.lint-1.0.0", "lint", "1.0.0");
+        create_extension(dir, "acme.themes-1.0.0", "themes", "1.0.0");
+        create_extension(dir, "acme.old-1.0.0", "old", "1.0.0");
+        create_extension(dir, "acme.profiled-1.0.0", "profiled", "1.0.0");
+        fs::create_dir(dir.join("acme.broken-1.0.0")).unwrap();
+        fs::create_dir(dir.join(".acme.tools.staging")).unwrap();
+        fs::write(
+            dir.join(REGISTRY_FILE_NAME),
+            json!([
+                registry_entry("acme.tools", "2.0.0", "acme.tools-2.0.0"),
+                registry_entry("acme.themes", "1.0.0", "acme.themes-1.0.0"),
+                registry_entry("acme.gone", "1.0.0", "acme.gone-1.0.0"),
+            ])
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(OBSOLETE_FILE_NAME),
+            json!({ "acme.themes-1.0.0": true, "acme.other-1.0.0": true }).to_string(),
+        )
+        .unwrap();
+        let profile_registry = temp_dir.path().join("profile-extensions.json");
+        fs::write(
+            &profile_registry,
+            json!([registry_entry(
+                "acme.profiled",
+                "1.0.0",
+                "acme.profiled-1.0.0"
+            )])
+            .to_string(),
+        )
+        .unwrap();
+
+        let stale = find_stale_extensions(dir, std::slice::from_ref(&profile_registry)).unwrap();
+        assert_eq!(
+            reasons(&stale),
+            [
+                ("acme.broken-1.0.0".to_string(), StaleReason::Broken),
+                ("acme.gone-1.0.0".to_string(), StaleReason::Broken),
+                ("acme.lint-1.0.0".to_string(), StaleReason::Orphaned),
+                ("acme.old-1.0.0".to_string(), StaleReason::Orphaned),
+                ("acme.themes-1.0.0".to_string(), StaleReason::Obsolete),
+                ("acme.tools-1.0.0".to_string(), StaleReason::OldVersion),
+            ]
+        );
+        assert!(
+            stale
+                .iter()
+                .all(|ext| ext.reason == StaleReason::Broken || ext.size > 0)
+        );
+
+        let reclaimed = remove_stale_extensions(dir, &stale).unwrap();
+        assert_eq!(reclaimed, stale.iter().map(|ext| ext.size).sum::<u64>());
+        assert!(dir.join("acme.tools-2.0.0").is_dir());
+        assert!(dir.join("acme.profiled-1.0.0").is_dir());
+        assert!(dir.join(".acme.tools.staging").is_dir());
+        assert!(!dir.join("acme.tools-1.0.0").exists());
+        assert!(!dir.join("acme.themes-1.0.0").exists());
+
+        let registry = ExtensionsRegistry::load(dir).unwrap();
+        assert_eq!(registry.entries().len(), 1);
+        let obsolete = read_obsolete_folders(dir).unwrap();
+        assert_eq!(obsolete, HashSet::from(["acme.other-1.0.0".to_string()]));
+        assert!(
+            find_stale_extensions(dir, &[profile_registry])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_find_stale_extensions_without_registry() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        create_extension(dir, "acme.tools-1.10.0", "tools", "1.10.0");
+        create_extension(dir, "acme.tools-1.9.0", "tools", "1.9.0");
+        create_extension(dir, "acme.lint", "lint", "1.0.0");
+
+        let stale = find_stale_extensions(dir, &[]).unwrap();
+        assert_eq!(
+            reasons(&stale),
+            [("acme.tools-1.9.0".to_string(), StaleReason::OldVersion)]
+        );
+    }
+
+    #[test]
+    fn test_remove_stale_extensions_rejects_outside_folders() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let stale = [StaleExtension {
+            folder: outside.path().to_path_buf(),
+            reason: StaleReason::Orphaned,
+            id: None,
+            version: None,
+            size: 0,
+        }];
+
+        assert!(remove_stale_extensions(temp_dir.path(), &stale).is_err());
+        assert!(outside.path().is_dir());
+    }
+}
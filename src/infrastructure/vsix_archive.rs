@@ -0,0 +1,537 @@
+use crate::domain::{ArchiveFile, DomainError, VsixInspection, VsixPackage};
+use serde_json::Value;
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Maximum number of entries accepted in a single VSIX archive
+pub const MAX_ARCHIVE_ENTRIES: usize = 50_000;
+
+/// Maximum total size of all extracted files (1 GiB)
+pub const MAX_UNCOMPRESSED_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Maximum ratio between the uncompressed and compressed size of an entry
+pub const MAX_COMPRESSION_RATIO: u64 = 200;
+
+/// Entries smaller than this are exempt from the compression ratio check,
+/// since tiny repetitive files legitimately compress very well
+const COMPRESSION_RATIO_THRESHOLD: u64 = 1024 * 1024;
+
+/// Location of the extension manifest inside a VSIX archive
+const MANIFEST_ENTRY: &str = "extension/package.json";
+
+/// Location of the marketplace manifest inside a VSIX archive
+const VSIXMANIFEST_ENTRY: &str = "extension.vsixmanifest";
+
+/// Location of the default localized strings inside a VSIX archive
+const NLS_ENTRY: &str = "extension/package.nls.json";
+
+/// Maximum size of a manifest read from an archive (10 MiB)
+const MAX_MANIFEST_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Extracts a VSIX archive into the target directory
+///
+/// Every entry is validated before anything is written for it: paths must stay
+/// inside `target_dir`, symbolic links are rejected, and the entry count, total
+/// uncompressed size and per-entry compression ratio are capped. Unix permission
+/// bits are preserved (minus group/other write and setuid bits) so bundled
+/// executables stay runnable.
+///
+/// # Errors
+///
+/// Returns `DomainError::InvalidExtensionFormat` if the archive is malformed, an
+/// entry is corrupt or any of the limits above is violated, and
+/// `DomainError::IoError` if writing to `target_dir` fails.
+pub fn extract_vsix(vsix_data: &[u8], target_dir: &Path) -> Result<(), DomainError> {
+    let cursor = std::io::Cursor::new(vsix_data);
+    let mut archive =
+        ZipArchive::new(cursor).map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))?;
+
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(DomainError::InvalidExtensionFormat(format!(
+            "Archive contains {} entries (limit is {MAX_ARCHIVE_ENTRIES})",
+            archive.len()
+        )));
+    }
+
+    let mut total_written: u64 = 0;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))?;
+
+        let name = file.name().to_string();
+
+        if file.is_symlink() {
+            return Err(DomainError::InvalidExtensionFormat(format!(
+                "Archive entry '{name}' is a symbolic link"
+            )));
+        }
+
+        if is_absolute_entry_name(&name) {
+            return Err(DomainError::InvalidExtensionFormat(format!(
+                "Archive entry '{name}' has an absolute path"
+            )));
+        }
+
+        let relative_path = file.enclosed_name().ok_or_else(|| {
+            DomainError::InvalidExtensionFormat(format!(
+                "Archive entry '{name}' points outside the extension directory"
+            ))
+        })?;
+        let outpath = target_dir.join(relative_path);
+
+        if file.is_dir() {
+            fs::create_dir_all(&outpath)?;
+            continue;
+        }
+
+        let declared_size = file.size();
+        if declared_size > COMPRESSION_RATIO_THRESHOLD
+            && declared_size / file.compressed_size().max(1) > MAX_COMPRESSION_RATIO
+        {
+            return Err(DomainError::InvalidExtensionFormat(format!(
+                "Archive entry '{name}' exceeds the maximum compression ratio of {MAX_COMPRESSION_RATIO}:1"
+            )));
+        }
+
+        let remaining = MAX_UNCOMPRESSED_SIZE - total_written;
+        if declared_size > remaining {
+            return Err(total_size_exceeded());
+        }
+
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut outfile = fs::File::create(&outpath)?;
+
+        // Don't trust the declared size; stop as soon as the real data exceeds the limit
+        let copied = copy_entry(&name, &mut (&mut file).take(remaining + 1), &mut outfile)?;
+        if copied > remaining {
+            return Err(total_size_exceeded());
+        }
+        total_written += copied;
+
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode & 0o755))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the extension manifest (`extension/package.json`) from a VSIX archive
+///
+/// # Errors
+///
+/// Returns `DomainError::InvalidExtensionFormat` if the archive has no readable
+/// manifest or the manifest is not valid JSON
+pub fn read_vsix_manifest(vsix_data: &[u8]) -> Result<Value, DomainError> {
+    let mut archive = open_archive(vsix_data)?;
+    let content = read_entry(&mut archive, MANIFEST_ENTRY)?.ok_or_else(missing_manifest)?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| DomainError::InvalidExtensionFormat(format!("Invalid {MANIFEST_ENTRY}: {e}")))
+}
+
+/// Reads the package metadata from a VSIX archive
+///
+/// Parses `extension/package.json` together with `extension.vsixmanifest` and
+/// `extension/package.nls.json` when the archive contains them.
+///
+/// # Errors
+///
+/// Returns `DomainError::InvalidExtensionFormat` if the archive has no
+/// `package.json`, a manifest is malformed, or the package declares no identity
+pub fn read_vsix_package(vsix_data: &[u8]) -> Result<VsixPackage, DomainError> {
+    let mut archive = open_archive(vsix_data)?;
+    let package_json = read_entry(&mut archive, MANIFEST_ENTRY)?.ok_or_else(missing_manifest)?;
+    let vsixmanifest = read_entry(&mut archive, VSIXMANIFEST_ENTRY)?;
+    let nls = read_entry(&mut archive, NLS_ENTRY)?;
+
+    VsixPackage::from_manifests(&package_json, vsixmanifest.as_deref(), nls.as_deref())
+}
+
+/// Reads the package metadata and the list of files of a VSIX archive
+///
+/// Nothing is extracted. Native executables and libraries are recognized by
+/// their file extension or by their ELF, PE or Mach-O header.
+///
+/// # Errors
+///
+/// Returns `DomainError::InvalidExtensionFormat` if the archive or its manifests
+/// cannot be read
+pub fn inspect_vsix(vsix_data: &[u8]) -> Result<VsixInspection, DomainError> {
+    let package = read_vsix_package(vsix_data)?;
+    let mut archive = open_archive(vsix_data)?;
+
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(DomainError::InvalidExtensionFormat(format!(
+            "Archive contains {} entries (limit is {MAX_ARCHIVE_ENTRIES})",
+            archive.len()
+        )));
+    }
+
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let path = file.name().to_string();
+        let size = file.size();
+        let mut header = Vec::with_capacity(4);
+        (&mut file)
+            .take(4)
+            .read_to_end(&mut header)
+            .map_err(|e| corrupt_entry(&path, &e))?;
+
+        files.push(ArchiveFile {
+            native: is_native_binary(&path, &header),
+            path,
+            size,
+        });
+    }
+
+    Ok(VsixInspection { package, files })
+}
+
+/// Detects native executables and libraries by name or header
+fn is_native_binary(path: &str, header: &[u8]) -> bool {
+    const NATIVE_EXTENSIONS: [&str; 5] = [".node", ".exe", ".dll", ".so", ".dylib"];
+    const MAGIC_NUMBERS: [&[u8]; 6] = [
+        b"\x7fELF",
+        b"MZ",
+        &[0xfe, 0xed, 0xfa, 0xce],
+        &[0xfe, 0xed, 0xfa, 0xcf],
+        &[0xce, 0xfa, 0xed, 0xfe],
+        &[0xcf, 0xfa, 0xed, 0xfe],
+    ];
+
+    let name = path.to_ascii_lowercase();
+    NATIVE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+        // Versioned shared libraries such as `libssl.so.3`
+        || name.contains(".so.")
+        || MAGIC_NUMBERS.iter().any(|magic| header.starts_with(magic))
+}
+
+fn open_archive(vsix_data: &[u8]) -> Result<ZipArchive<Cursor<&[u8]>>, DomainError> {
+    ZipArchive::new(Cursor::new(vsix_data))
+        .map_err(|e| DomainError::InvalidExtensionFormat(e.to_string()))
+}
+
+/// Reads a text entry of at most `MAX_MANIFEST_SIZE` bytes, if present
+fn read_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> Result<Option<String>, DomainError> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(corrupt_entry(name, &e)),
+    };
+
+    let mut content = Vec::new();
+    file.take(MAX_MANIFEST_SIZE + 1)
+        .read_to_end(&mut content)
+        .map_err(|e| corrupt_entry(name, &e))?;
+    if content.len() as u64 > MAX_MANIFEST_SIZE {
+        return Err(DomainError::InvalidExtensionFormat(format!(
+            "{name} exceeds {MAX_MANIFEST_SIZE} bytes"
+        )));
+    }
+
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|_| DomainError::InvalidExtensionFormat(format!("{name} is not valid UTF-8")))
+}
+
+/// Copies an entry into `out`, reporting read failures as a corrupt archive and
+/// write failures as I/O errors
+fn copy_entry(name: &str, entry: &mut impl Read, out: &mut impl Write) -> Result<u64, DomainError> {
+    let mut buffer = [0u8; 64 * 1024];
+    let mut copied = 0;
+    loop {
+        let read = match entry.read(&mut buffer) {
+            Ok(0) => return Ok(copied),
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(corrupt_entry(name, &e)),
+        };
+        out.write_all(&buffer[..read])?;
+        copied += read as u64;
+    }
+}
+
+/// A damaged entry, e.g. a failed decompression or checksum
+fn corrupt_entry(name: &str, error: &dyn std::fmt::Display) -> DomainError {
+    DomainError::InvalidExtensionFormat(format!("Archive entry '{name}' is corrupt: {error}"))
+}
+
+fn missing_manifest() -> DomainError {
+    DomainError::InvalidExtensionFormat(format!("VSIX archive does not contain {MANIFEST_ENTRY}"))
+}
+
+/// Detects absolute Unix, UNC and drive-letter paths regardless of the host platform
+fn is_absolute_entry_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    name.starts_with('/')
+        || name.starts_with('\\')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
+
+fn total_size_exceeded() -> DomainError {
+    DomainError::InvalidExtensionFormat(format!(
+        "Archive exceeds the maximum uncompressed size of {MAX_UNCOMPRESSED_SIZE} bytes"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ExtensionKind;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    fn build_archive(build: impl FnOnce(&mut ZipWriter<std::io::Cursor<&mut Vec<u8>>>)) -> Vec<u8> {
+        let mut zip_data = Vec::new();
+        {
+            let mut zip = ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+            build(&mut zip);
+            zip.finish().unwrap();
+        }
+        zip_data
+    }
+
+    fn assert_rejected(zip_data: &[u8]) {
+        let temp_dir = TempDir::new().unwrap();
+        let result = extract_vsix(zip_data, &temp_dir.path().join("target"));
+        assert!(
+            matches!(result, Err(DomainError::InvalidExtensionFormat(_))),
+            "Expected InvalidExtensionFormat, got {result:?}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("../../.bashrc", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"echo pwned").unwrap();
+        });
+
+        assert_rejected(&zip_data);
+    }
+
+    #[test]
+    fn test_rejects_absolute_paths() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("/tmp/evil.txt", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"evil").unwrap();
+        });
+
+        assert_rejected(&zip_data);
+        assert!(is_absolute_entry_name("C:\\Windows\\evil.dll"));
+        assert!(!is_absolute_entry_name("extension/package.json"));
+    }
+
+    #[test]
+    fn test_rejects_symlinks() {
+        let zip_data = build_archive(|zip| {
+            zip.add_symlink(
+                "extension/link",
+                "/etc/passwd",
+                SimpleFileOptions::default(),
+            )
+            .unwrap();
+        });
+
+        assert_rejected(&zip_data);
+    }
+
+    #[test]
+    fn test_rejects_excessive_compression_ratio() {
+        let zip_data = build_archive(|zip| {
+            let options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+            zip.start_file("extension/bomb.bin", options).unwrap();
+            zip.write_all(&vec![0u8; 8 * 1024 * 1024]).unwrap();
+        });
+
+        assert_rejected(&zip_data);
+    }
+
+    #[test]
+    fn test_rejects_corrupt_entries() {
+        let content = b"module.exports = {};";
+        let mut zip_data = build_archive(|zip| {
+            zip.start_file("extension/package.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"publisher": "acme", "name": "tools", "version": "2.1.0"}"#)
+                .unwrap();
+            let options =
+                SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            zip.start_file("extension/out/extension.js", options)
+                .unwrap();
+            zip.write_all(content).unwrap();
+        });
+
+        // Flip a byte of the stored data so that its checksum no longer matches
+        let offset = zip_data
+            .windows(content.len())
+            .position(|window| window == content)
+            .unwrap();
+        zip_data[offset] ^= 0xff;
+
+        let temp_dir = TempDir::new().unwrap();
+        let result = extract_vsix(&zip_data, &temp_dir.path().join("target"));
+        assert!(
+            matches!(&result, Err(DomainError::InvalidExtensionFormat(msg)) if msg.contains("extension/out/extension.js")),
+            "Expected InvalidExtensionFormat, got {result:?}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preserves_executable_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let zip_data = build_archive(|zip| {
+            let options = SimpleFileOptions::default().unix_permissions(0o4777);
+            zip.start_file("extension/bin/server", options).unwrap();
+            zip.write_all(b"#!/bin/sh\n").unwrap();
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        extract_vsix(&zip_data, temp_dir.path()).unwrap();
+
+        let mode = fs::metadata(temp_dir.path().join("extension/bin/server"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o7777, 0o755);
+    }
+
+    #[test]
+    fn test_read_manifest_and_extension_kinds() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("extension/package.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"name": "theme", "extensionKind": "ui"}"#)
+                .unwrap();
+        });
+
+        let manifest = read_vsix_manifest(&zip_data).unwrap();
+        assert_eq!(
+            ExtensionKind::from_manifest(&manifest),
+            vec![ExtensionKind::Ui]
+        );
+
+        let manifest = serde_json::json!({ "main": "./out/extension.js" });
+        assert_eq!(
+            ExtensionKind::from_manifest(&manifest),
+            vec![ExtensionKind::Workspace]
+        );
+
+        let manifest = serde_json::json!({ "extensionKind": ["ui", "workspace"] });
+        assert_eq!(
+            ExtensionKind::from_manifest(&manifest),
+            vec![ExtensionKind::Ui, ExtensionKind::Workspace]
+        );
+    }
+
+    #[test]
+    fn test_read_manifest_requires_package_json() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("extension/README.md", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"readme").unwrap();
+        });
+
+        assert!(matches!(
+            read_vsix_manifest(&zip_data),
+            Err(DomainError::InvalidExtensionFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_inspect_vsix() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("extension/package.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"publisher": "acme", "name": "tools", "version": "2.1.0"}"#)
+                .unwrap();
+            zip.start_file("extension/out/extension.js", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"module.exports = {};").unwrap();
+            zip.start_file("extension/bin/server", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"\x7fELF\x02\x01\x01").unwrap();
+            zip.start_file("extension/lib/addon.node", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"addon").unwrap();
+        });
+
+        let inspection = inspect_vsix(&zip_data).unwrap();
+        assert_eq!(inspection.package.id(), "acme.tools");
+        assert_eq!(inspection.files.len(), 4);
+        assert_eq!(inspection.files[1].size, 20);
+
+        let native: Vec<&str> = inspection
+            .native_binaries()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(
+            native,
+            vec!["extension/bin/server", "extension/lib/addon.node"]
+        );
+    }
+
+    #[test]
+    fn test_read_vsix_package() {
+        let zip_data = build_archive(|zip| {
+            zip.start_file("extension.vsixmanifest", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(
+                br#"<PackageManifest><Metadata>
+                    <Identity Id="tools" Version="2.1.0" Publisher="acme" TargetPlatform="darwin-arm64"/>
+                </Metadata></PackageManifest>"#,
+            )
+            .unwrap();
+            zip.start_file("extension/package.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"publisher": "acme", "name": "tools", "version": "2.1.0", "displayName": "%name%"}"#)
+                .unwrap();
+            zip.start_file("extension/package.nls.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"name": "Acme Tools"}"#).unwrap();
+        });
+
+        let package = read_vsix_package(&zip_data).unwrap();
+        assert_eq!(package.id(), "acme.tools");
+        assert_eq!(package.version, "2.1.0");
+        assert_eq!(package.target_platform.as_deref(), Some("darwin-arm64"));
+        assert_eq!(package.display_name.as_deref(), Some("Acme Tools"));
+
+        let zip_data = build_archive(|zip| {
+            zip.start_file("extension/package.json", SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(br#"{"name": "tools", "version": "2.1.0"}"#)
+                .unwrap();
+        });
+        assert!(matches!(
+            read_vsix_package(&zip_data),
+            Err(DomainError::InvalidExtensionFormat(msg)) if msg.contains("publisher")
+        ));
+    }
+}
@@ -0,0 +1,547 @@
+use super::vsix_archive::{MAX_ARCHIVE_ENTRIES, read_vsix_package};
+use crate::domain::{DomainError, VsixPackage};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// File listing the paths to leave out of a package, as used by `vsce`
+pub const VSCODEIGNORE_FILE: &str = ".vscodeignore";
+
+/// Paths left out of every package, as in `vsce`
+const DEFAULT_IGNORE: [&str; 32] = [
+    ".vscodeignore",
+    "package-lock.json",
+    "npm-debug.log",
+    "yarn.lock",
+    "yarn-error.log",
+    "npm-shrinkwrap.json",
+    ".editorconfig",
+    ".npmrc",
+    ".yarnrc",
+    ".gitattributes",
+    "*.todo",
+    "tslint.yaml",
+    ".eslintrc*",
+    ".babelrc*",
+    ".prettierrc*",
+    ".cz-config.js",
+    ".commitlintrc*",
+    "webpack.config.js",
+    "ISSUE_TEMPLATE.md",
+    "CONTRIBUTING.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "CODE_OF_CONDUCT.md",
+    ".github",
+    ".travis.yml",
+    "appveyor.yml",
+    "**/.git",
+    "**/.git/**",
+    "**/*.vsix",
+    "**/.DS_Store",
+    "**/*.vsixmanifest",
+    "**/.vscode-test/**",
+    "**/.vscode-test-web/**",
+];
+
+/// Content types of common extension files, for `[Content_Types].xml`
+const CONTENT_TYPES: [(&str, &str); 14] = [
+    ("css", "text/css"),
+    ("html", "text/html"),
+    ("jpg", "image/jpeg"),
+    ("js", "application/javascript"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("md", "text/markdown"),
+    ("mjs", "application/javascript"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("txt", "text/plain"),
+    ("vsixmanifest", "text/xml"),
+    ("wasm", "application/wasm"),
+    ("xml", "text/xml"),
+];
+
+/// Packages an extension folder into a VSIX archive
+///
+/// Reads `package.json`, leaves out the files matched by `vsce`'s default ignore
+/// list and `.vscodeignore`, and generates `extension.vsixmanifest` and
+/// `[Content_Types].xml`. `target` makes a platform-specific package (e.g.
+/// `linux-x64`).
+///
+/// Returns the metadata of the written package and the archive bytes.
+///
+/// # Errors
+///
+/// Returns `DomainError::InvalidExtensionFormat` if `package.json` is missing or
+/// incomplete or `.vscodeignore` has an invalid pattern, and
+/// `DomainError::IoError` if a file cannot be read
+pub fn pack_extension(
+    extension_dir: &Path,
+    target: Option<&str>,
+) -> Result<(VsixPackage, Vec<u8>), DomainError> {
+    let package_json = fs::read_to_string(extension_dir.join("package.json")).map_err(|_| {
+        DomainError::InvalidExtensionFormat(format!(
+            "{} has no package.json",
+            extension_dir.display()
+        ))
+    })?;
+    let nls = fs::read_to_string(extension_dir.join("package.nls.json")).ok();
+    let package = VsixPackage::from_manifests(&package_json, None, nls.as_deref())?;
+    if package.engine.is_none() {
+        return Err(DomainError::InvalidExtensionFormat(
+            "package.json has no \"engines.vscode\"".to_string(),
+        ));
+    }
+    let manifest: Value = serde_json::from_str(&package_json)
+        .map_err(|e| DomainError::InvalidExtensionFormat(format!("Invalid package.json: {e}")))?;
+
+    let ignore = IgnoreRules::load(extension_dir)?;
+    let mut files = Vec::new();
+    collect_files(extension_dir, "", &ignore, &mut files)?;
+    files.sort();
+
+    if files.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(DomainError::InvalidExtensionFormat(format!(
+            "Package would contain {} files (limit is {MAX_ARCHIVE_ENTRIES})",
+            files.len()
+        )));
+    }
+
+    let vsixmanifest = vsixmanifest(&package, &manifest, &files, target);
+    let content_types = content_types(&files);
+
+    let mut zip_data = Vec::new();
+    {
+        let mut zip = ZipWriter::new(Cursor::new(&mut zip_data));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let zip_error = |e: zip::result::ZipError| DomainError::IoError(std::io::Error::other(e));
+
+        zip.start_file("extension.vsixmanifest", options)
+            .map_err(zip_error)?;
+        zip.write_all(vsixmanifest.as_bytes())?;
+        zip.start_file("[Content_Types].xml", options)
+            .map_err(zip_error)?;
+        zip.write_all(content_types.as_bytes())?;
+
+        for file in &files {
+            let path = extension_dir.join(file);
+            zip.start_file(
+                format!("extension/{file}"),
+                options.unix_permissions(file_mode(&path)?),
+            )
+            .map_err(zip_error)?;
+            zip.write_all(&fs::read(&path)?)?;
+        }
+
+        zip.finish().map_err(zip_error)?;
+    }
+
+    // Read the result back, so a package we could not install is never written
+    let package = read_vsix_package(&zip_data)?;
+    Ok((package, zip_data))
+}
+
+/// Patterns from `DEFAULT_IGNORE` and `.vscodeignore`
+///
+/// Like `vsce`, a path is left out if it matches an ignore pattern and no `!`
+/// pattern. A path inside an ignored directory is ignored too.
+struct IgnoreRules {
+    ignore: Vec<GlobMatcher>,
+    negate: Vec<GlobMatcher>,
+}
+
+impl IgnoreRules {
+    fn load(extension_dir: &Path) -> Result<Self, DomainError> {
+        let custom = fs::read_to_string(extension_dir.join(VSCODEIGNORE_FILE)).unwrap_or_default();
+        let mut rules = Self {
+            ignore: Vec::new(),
+            negate: Vec::new(),
+        };
+
+        let patterns = DEFAULT_IGNORE
+            .iter()
+            .copied()
+            .chain(custom.lines().map(str::trim))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for pattern in patterns {
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern),
+            };
+            let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+            let pattern = match pattern.strip_suffix('/') {
+                Some(dir) => format!("{dir}/**"),
+                None => pattern.to_string(),
+            };
+
+            let matcher = GlobBuilder::new(&pattern)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| {
+                    DomainError::InvalidExtensionFormat(format!(
+                        "Invalid {VSCODEIGNORE_FILE} pattern '{pattern}': {e}"
+                    ))
+                })?
+                .compile_matcher();
+
+            if negated {
+                rules.negate.push(matcher);
+            } else {
+                rules.ignore.push(matcher);
+            }
+        }
+
+        Ok(rules)
+    }
+
+    fn is_ignored(&self, relative_path: &str) -> bool {
+        if self.negate.iter().any(|glob| glob.is_match(relative_path)) {
+            return false;
+        }
+
+        let mut path = relative_path;
+        loop {
+            if self.ignore.iter().any(|glob| glob.is_match(path)) {
+                return true;
+            }
+            match path.rsplit_once('/') {
+                Some((parent, _)) => path = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+/// Collects the files to package as `/`-separated paths relative to the root
+fn collect_files(
+    dir: &Path,
+    prefix: &str,
+    ignore: &IgnoreRules,
+    files: &mut Vec<String>,
+) -> Result<(), DomainError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative_path = format!("{prefix}{name}");
+
+        // Follows symbolic links, since the archive stores their targets
+        let metadata = fs::metadata(entry.path())?;
+        if metadata.is_dir() {
+            if !entry.file_type()?.is_symlink() {
+                collect_files(&entry.path(), &format!("{relative_path}/"), ignore, files)?;
+            }
+        } else if !ignore.is_ignored(&relative_path) {
+            files.push(relative_path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> Result<u32, DomainError> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(fs::metadata(path)?.permissions().mode() & 0o755)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> Result<u32, DomainError> {
+    Ok(0o644)
+}
+
+/// Generates `extension.vsixmanifest` the way `vsce` does
+fn vsixmanifest(
+    package: &VsixPackage,
+    manifest: &Value,
+    files: &[String],
+    target: Option<&str>,
+) -> String {
+    let joined = |key: &str| {
+        manifest[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default()
+    };
+    let find = |names: &[&str]| {
+        files
+            .iter()
+            .find(|file| names.iter().any(|name| file.eq_ignore_ascii_case(name)))
+            .map(|file| format!("extension/{file}"))
+    };
+    let kinds: Vec<String> = package
+        .extension_kind
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    let readme = find(&["README.md"]);
+    let changelog = find(&["CHANGELOG.md"]);
+    let license = find(&["LICENSE", "LICENSE.md", "LICENSE.txt"]);
+    let icon = manifest["icon"]
+        .as_str()
+        .map(|icon| icon.trim_start_matches("./"))
+        .filter(|icon| files.iter().any(|file| file == icon))
+        .map(|icon| format!("extension/{icon}"));
+
+    let mut identity = format!(
+        r#"<Identity Language="en-US" Id="{}" Version="{}" Publisher="{}""#,
+        escape_xml(&package.name),
+        escape_xml(&package.version),
+        escape_xml(&package.publisher)
+    );
+    if let Some(target) = target {
+        identity.push_str(&format!(r#" TargetPlatform="{}""#, escape_xml(target)));
+    }
+    identity.push_str(" />");
+
+    let properties = [
+        ("Microsoft.VisualStudio.Code.Engine", package.engine.clone()),
+        (
+            "Microsoft.VisualStudio.Code.ExtensionDependencies",
+            Some(package.dependencies.join(",")),
+        ),
+        (
+            "Microsoft.VisualStudio.Code.ExtensionPack",
+            Some(package.pack.join(",")),
+        ),
+        (
+            "Microsoft.VisualStudio.Code.ExtensionKind",
+            Some(kinds.join(",")),
+        ),
+        (
+            "Microsoft.VisualStudio.Code.LocalizedLanguages",
+            Some(String::new()),
+        ),
+    ]
+    .iter()
+    .map(|(id, value)| {
+        format!(
+            r#"      <Property Id="{id}" Value="{}" />"#,
+            escape_xml(value.as_deref().unwrap_or_default())
+        )
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let mut metadata_extras = String::new();
+    if let Some(license) = &license {
+        metadata_extras.push_str(&format!("    <License>{}</License>\n", escape_xml(license)));
+    }
+    if let Some(icon) = &icon {
+        metadata_extras.push_str(&format!("    <Icon>{}</Icon>\n", escape_xml(icon)));
+    }
+
+    let mut assets = vec![(
+        "Microsoft.VisualStudio.Code.Manifest",
+        "extension/package.json".to_string(),
+    )];
+    for (asset_type, path) in [
+        ("Microsoft.VisualStudio.Services.Content.Details", readme),
+        (
+            "Microsoft.VisualStudio.Services.Content.Changelog",
+            changelog,
+        ),
+        ("Microsoft.VisualStudio.Services.Content.License", license),
+        ("Microsoft.VisualStudio.Services.Icons.Default", icon),
+    ] {
+        if let Some(path) = path {
+            assets.push((asset_type, path));
+        }
+    }
+    let assets = assets
+        .iter()
+        .map(|(asset_type, path)| {
+            format!(
+                r#"    <Asset Type="{asset_type}" Path="{}" Addressable="true" />"#,
+                escape_xml(path)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<PackageManifest Version="2.0.0" xmlns="http://schemas.microsoft.com/developer/vsx-schema/2011" xmlns:d="http://schemas.microsoft.com/developer/vsx-schema-design/2011">
+  <Metadata>
+    {identity}
+    <DisplayName>{display_name}</DisplayName>
+    <Description xml:space="preserve">{description}</Description>
+    <Tags>{tags}</Tags>
+    <Categories>{categories}</Categories>
+    <GalleryFlags>Public</GalleryFlags>
+    <Properties>
+{properties}
+    </Properties>
+{metadata_extras}  </Metadata>
+  <Installation>
+    <InstallationTarget Id="Microsoft.VisualStudio.Code" />
+  </Installation>
+  <Dependencies />
+  <Assets>
+{assets}
+  </Assets>
+</PackageManifest>
+"#,
+        display_name = escape_xml(package.display_name.as_deref().unwrap_or(&package.name)),
+        description = escape_xml(package.description.as_deref().unwrap_or_default()),
+        tags = escape_xml(&joined("keywords")),
+        categories = escape_xml(&joined("categories")),
+    )
+}
+
+/// Generates `[Content_Types].xml` from the extensions of the packaged files
+fn content_types(files: &[String]) -> String {
+    let extensions: BTreeSet<String> = files
+        .iter()
+        .filter_map(|file| {
+            let name = file.rsplit('/').next()?;
+            let (stem, extension) = name.rsplit_once('.')?;
+            (!stem.is_empty()).then(|| extension.to_ascii_lowercase())
+        })
+        .chain(std::iter::once("vsixmanifest".to_string()))
+        .collect();
+
+    let defaults: String = extensions
+        .iter()
+        .map(|extension| {
+            let content_type = CONTENT_TYPES
+                .iter()
+                .find(|(known, _)| known == extension)
+                .map_or("application/octet-stream", |(_, content_type)| content_type);
+            format!(
+                r#"<Default Extension=".{}" ContentType="{content_type}" />"#,
+                escape_xml(extension)
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">{defaults}</Types>
+"#
+    )
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{extract_vsix, inspect_vsix};
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn create_extension() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{
+                "publisher": "acme",
+                "name": "tools",
+                "displayName": "Acme <Tools>",
+                "version": "1.0.0",
+                "engines": { "vscode": "^1.80.0" },
+                "main": "./out/extension.js",
+                "icon": "images/icon.png",
+                "keywords": ["acme", "tools"]
+            }"#,
+        );
+        write(root, "out/extension.js", "exports.activate = () => {};");
+        write(root, "out/extension.js.map", "{}");
+        write(root, "src/extension.ts", "export function activate() {}");
+        write(root, "images/icon.png", "png");
+        write(root, "README.md", "# Acme Tools");
+        write(root, ".git/HEAD", "ref: refs/heads/main");
+        write(root, ".vscode/settings.json", "{}");
+        write(root, ".vscode-test/user-data/state", "{}");
+        write(root, "old-0.9.0.vsix", "zip");
+        write(
+            root,
+            ".vscodeignore",
+            "# sources\n.vscode/\nsrc/**\n**/*.map\n!out/keep.js.map\n",
+        );
+        write(root, "out/keep.js.map", "{}");
+        temp_dir
+    }
+
+    #[test]
+    fn test_pack_extension() {
+        let extension = create_extension();
+
+        let (package, zip_data) = pack_extension(extension.path(), None).unwrap();
+        assert_eq!(package.id(), "acme.tools");
+        assert_eq!(package.display_name.as_deref(), Some("Acme <Tools>"));
+
+        let inspection = inspect_vsix(&zip_data).unwrap();
+        let files: Vec<&str> = inspection.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            files,
+            vec![
+                "extension.vsixmanifest",
+                "[Content_Types].xml",
+                "extension/README.md",
+                "extension/images/icon.png",
+                "extension/out/extension.js",
+                "extension/out/keep.js.map",
+                "extension/package.json",
+            ]
+        );
+
+        let target = TempDir::new().unwrap();
+        extract_vsix(&zip_data, target.path()).unwrap();
+        let manifest = fs::read_to_string(target.path().join("extension.vsixmanifest")).unwrap();
+        assert!(manifest.contains(r#"<Icon>extension/images/icon.png</Icon>"#));
+        assert!(manifest.contains("<Tags>acme,tools</Tags>"));
+        let content_types = fs::read_to_string(target.path().join("[Content_Types].xml")).unwrap();
+        assert!(content_types.contains(r#"Extension=".js" ContentType="application/javascript""#));
+    }
+
+    #[test]
+    fn test_pack_platform_specific_extension() {
+        let extension = create_extension();
+
+        let (package, _) = pack_extension(extension.path(), Some("linux-x64")).unwrap();
+        assert_eq!(package.target_platform.as_deref(), Some("linux-x64"));
+    }
+
+    #[test]
+    fn test_pack_requires_engine() {
+        let temp_dir = TempDir::new().unwrap();
+        write(
+            temp_dir.path(),
+            "package.json",
+            r#"{"publisher": "acme", "name": "tools", "version": "1.0.0"}"#,
+        );
+
+        assert!(matches!(
+            pack_extension(temp_dir.path(), None),
+            Err(DomainError::InvalidExtensionFormat(msg)) if msg.contains("engines.vscode")
+        ));
+    }
+}
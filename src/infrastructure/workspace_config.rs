@@ -0,0 +1,328 @@
+use crate::domain::{DomainError, WorkspaceRecommendations};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Extension of multi-root workspace files
+pub const WORKSPACE_FILE_EXTENSION: &str = "code-workspace";
+
+/// Where `devcontainer.json` is looked up inside a project folder, in order
+pub const DEVCONTAINER_FILES: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Reads the extension recommendations of a workspace
+///
+/// `path` is either a workspace folder or a single file. A folder's
+/// `.vscode/extensions.json` and every `*.code-workspace` file directly inside it
+/// are read and merged. A `.code-workspace` file contributes its `extensions`
+/// section, any other file is read like `extensions.json`. Both formats are JSON
+/// with comments and trailing commas.
+///
+/// # Errors
+///
+/// Returns an error if no recommendations file is found or a file cannot be
+/// read or parsed
+pub fn read_workspace_recommendations(
+    path: &Path,
+) -> Result<WorkspaceRecommendations, DomainError> {
+    if path.is_file() {
+        return read_recommendations_file(path);
+    }
+
+    let mut files: Vec<_> = fs::read_dir(path)
+        .map_err(|e| {
+            DomainError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("{}: {e}", path.display()),
+            ))
+        })?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|file| {
+            file.is_file()
+                && file
+                    .extension()
+                    .is_some_and(|extension| extension == WORKSPACE_FILE_EXTENSION)
+        })
+        .collect();
+    files.sort();
+
+    let extensions_json = path.join(".vscode").join("extensions.json");
+    if extensions_json.is_file() {
+        files.insert(0, extensions_json);
+    }
+    if files.is_empty() {
+        return Err(DomainError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "no .vscode/extensions.json or .{WORKSPACE_FILE_EXTENSION} file in {}",
+                path.display()
+            ),
+        )));
+    }
+
+    let mut recommendations = WorkspaceRecommendations::default();
+    for file in files {
+        recommendations.merge(read_recommendations_file(&file)?);
+    }
+    Ok(recommendations)
+}
+
+fn read_recommendations_file(path: &Path) -> Result<WorkspaceRecommendations, DomainError> {
+    let content = fs::read_to_string(path)?;
+    let json: Value = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", path.display())))?;
+
+    let section = if path
+        .extension()
+        .is_some_and(|extension| extension == WORKSPACE_FILE_EXTENSION)
+    {
+        &json["extensions"]
+    } else {
+        &json
+    };
+    let ids = |key: &str| -> Vec<String> {
+        section[key]
+            .as_array()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    Ok(WorkspaceRecommendations {
+        recommendations: ids("recommendations"),
+        unwanted: ids("unwantedRecommendations"),
+    })
+}
+
+/// Reads the extensions a dev container configuration installs
+///
+/// `path` is a `devcontainer.json` file or a folder containing one (see
+/// [`DEVCONTAINER_FILES`]; a `.devcontainer` folder itself works too). The IDs
+/// come from `customizations.vscode.extensions`, or the top-level `extensions`
+/// of older configurations. Entries written as `-publisher.name` exclude an
+/// extension, e.g. one added by a dev container feature, and are returned as
+/// unwanted.
+///
+/// # Errors
+///
+/// Returns an error if no `devcontainer.json` is found or it cannot be read or
+/// parsed
+pub fn read_devcontainer_extensions(path: &Path) -> Result<WorkspaceRecommendations, DomainError> {
+    let file = if path.is_dir() {
+        DEVCONTAINER_FILES
+            .iter()
+            .chain(&["devcontainer.json"])
+            .map(|name| path.join(name))
+            .find(|file| file.is_file())
+            .ok_or_else(|| {
+                DomainError::IoError(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no devcontainer.json in {}", path.display()),
+                ))
+            })?
+    } else {
+        path.to_path_buf()
+    };
+
+    let content = fs::read_to_string(&file).map_err(|e| {
+        DomainError::IoError(std::io::Error::new(
+            e.kind(),
+            format!("{}: {e}", file.display()),
+        ))
+    })?;
+    let json: Value = serde_json::from_str(&strip_jsonc(&content))
+        .map_err(|e| DomainError::ParseError(format!("Invalid {}: {e}", file.display())))?;
+
+    let entries = json["customizations"]["vscode"]["extensions"]
+        .as_array()
+        .or_else(|| json["extensions"].as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut extensions = WorkspaceRecommendations::default();
+    for entry in entries.iter().filter_map(Value::as_str).map(str::trim) {
+        let (list, id) = match entry.strip_prefix('-') {
+            Some(id) => (&mut extensions.unwanted, id.trim()),
+            None => (&mut extensions.recommendations, entry),
+        };
+        if !id.is_empty() && !list.iter().any(|known| known.eq_ignore_ascii_case(id)) {
+            list.push(id.to_string());
+        }
+    }
+    Ok(extensions)
+}
+
+/// Turns JSON with comments into plain JSON
+///
+/// Line and block comments are removed and trailing commas before `}` or `]`
+/// are dropped; string contents are left untouched.
+#[must_use]
+pub fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+    // Index in `output` of a comma that may turn out to be trailing
+    let mut pending_comma: Option<usize> = None;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = '\0';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '}' | ']' => {
+                if let Some(index) = pending_comma.take() {
+                    output.replace_range(index..=index, " ");
+                }
+                output.push(c);
+            }
+            ',' => {
+                pending_comma = Some(output.len());
+                output.push(c);
+            }
+            c if c.is_whitespace() => output.push(c),
+            c => {
+                pending_comma = None;
+                in_string = c == '"';
+                output.push(c);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = r#"{
+            // Recommended for this repository
+            "recommendations": [
+                "rust-lang.rust-analyzer", /* language server */
+                "https://example.com/a//b",
+                "quote \" // not a comment",
+            ],
+        }"#;
+
+        let json: Value = serde_json::from_str(&strip_jsonc(jsonc)).unwrap();
+        assert_eq!(
+            json["recommendations"],
+            serde_json::json!([
+                "rust-lang.rust-analyzer",
+                "https://example.com/a//b",
+                "quote \" // not a comment"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_workspace_recommendations() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".vscode")).unwrap();
+        fs::write(
+            temp_dir.path().join(".vscode").join("extensions.json"),
+            r#"{
+                // See https://go.microsoft.com/fwlink/?LinkId=827846
+                "recommendations": ["rust-lang.rust-analyzer", "ms-python.python",],
+                "unwantedRecommendations": ["ms-python.python"]
+            }"#,
+        )
+        .unwrap();
+        let workspace_file = temp_dir.path().join("project.code-workspace");
+        fs::write(
+            &workspace_file,
+            r#"{
+                "folders": [{ "path": "." }],
+                "extensions": { "recommendations": ["tamasfe.even-better-toml"] },
+            }"#,
+        )
+        .unwrap();
+
+        let recommendations = read_workspace_recommendations(temp_dir.path()).unwrap();
+        assert_eq!(
+            recommendations.recommendations,
+            vec![
+                "rust-lang.rust-analyzer",
+                "ms-python.python",
+                "tamasfe.even-better-toml"
+            ]
+        );
+        assert_eq!(recommendations.unwanted, vec!["ms-python.python"]);
+
+        let recommendations = read_workspace_recommendations(&workspace_file).unwrap();
+        assert_eq!(
+            recommendations.recommendations,
+            vec!["tamasfe.even-better-toml"]
+        );
+
+        let empty_dir = TempDir::new().unwrap();
+        assert!(read_workspace_recommendations(empty_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_read_devcontainer_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let devcontainer_dir = temp_dir.path().join(".devcontainer");
+        fs::create_dir(&devcontainer_dir).unwrap();
+        fs::write(
+            devcontainer_dir.join("devcontainer.json"),
+            r#"{
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "customizations": {
+                    "vscode": {
+                        "extensions": [
+                            "rust-lang.rust-analyzer",
+                            // Added by the Rust feature, not wanted here
+                            "-vadimcn.vscode-lldb",
+                            "Rust-Lang.Rust-Analyzer",
+                        ]
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        for path in [temp_dir.path(), devcontainer_dir.as_path()] {
+            let extensions = read_devcontainer_extensions(path).unwrap();
+            assert_eq!(extensions.recommendations, vec!["rust-lang.rust-analyzer"]);
+            assert_eq!(extensions.unwanted, vec!["vadimcn.vscode-lldb"]);
+        }
+
+        let empty_dir = TempDir::new().unwrap();
+        assert!(read_devcontainer_extensions(empty_dir.path()).is_err());
+    }
+}
@@ -1,13 +1,15 @@
 //! # vsix
 //!
-//! A command-line utility that downloads and installs .vsix extensions into Visual Studio Code and Cursor.
+//! A command-line utility that downloads and installs .vsix extensions into Visual Studio Code, Cursor
+//! and other VS Code-based editors.
 //!
 //! ## Features
 //!
 //! - Search for extensions in the Visual Studio Code marketplace
-//! - Install extensions to VSCode or Cursor
+//! - Install extensions to VSCode, Cursor, VSCodium, Windsurf and other editors, or ones defined
+//!   in the user configuration file
 //! - Download extensions without installing
-//! - Automatic CLI detection for `code` and `cursor` commands
+//! - Automatic CLI detection for `code`, `cursor`, `codium` and other editor commands
 //! - Cross-platform support (Windows, macOS, Linux)
 //! - Architecture-aware installation (x86_64, ARM64)
 //!
@@ -34,7 +36,7 @@
 //!     println!("Found {} extensions", results.total_count);
 //!     
 //!     // Install an extension
-//!     service.install_extension("rust-lang.rust-analyzer", false, None).await?;
+//!     service.install_extension("rust-lang.rust-analyzer", "vscode", None, None).await?;
 //!     
 //!     Ok(())
 //! }
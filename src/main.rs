@@ -1,13 +1,141 @@
 use clap::Parser;
 use vsix::application::ApplicationService;
-use vsix::domain::{SearchResult, SortField};
-use vsix::presentation::{Cli, Commands, DisplayManager};
+use vsix::domain::{
+    DomainError, IdeType, InstallOptions, ManifestChange, PackageSource, PlannedAction,
+    SearchResult, SortField,
+};
+use vsix::presentation::{
+    Cli, Commands, DisplayManager, InstallRecord, InstallStatus, OutputFormat, ProfileCommands,
+    select_ide,
+};
+
+/// Resolves the selected IDE, exiting with an error message if it is unknown
+fn resolve_ide(service: &ApplicationService, display: &DisplayManager, name: &str) -> IdeType {
+    match service.resolve_ide(name) {
+        Ok(ide) => ide,
+        Err(e) => {
+            display.show_error(&e.to_string());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Exits with an error if printing the results failed
+///
+/// A reader that closed the pipe early, e.g. `head`, is not an error.
+fn check_output(display: &DisplayManager, result: std::io::Result<()>) {
+    if let Err(e) = result
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        display.show_error(&format!("Writing output failed: {e}"));
+        std::process::exit(1);
+    }
+}
+
+/// Applies changes planned from a manifest or profile, or prints them for
+/// `--dry-run`, returning whether any of them failed
+async fn apply_changes(
+    service: &ApplicationService,
+    display: &DisplayManager,
+    changes: &[ManifestChange],
+    dry_run: bool,
+    marketplace_url: Option<&str>,
+    up_to_date: &str,
+) -> bool {
+    let mut failed = false;
+    if dry_run {
+        let mut plans = Vec::new();
+        for change in changes {
+            match service.plan_manifest_change(change, marketplace_url).await {
+                Ok(plan) => plans.push(plan),
+                Err(e) => {
+                    display.show_error(&format!(
+                        "Planning {} of {} failed: {e}",
+                        change.action, change.extension_id
+                    ));
+                    failed = true;
+                }
+            }
+        }
+        check_output(display, display.show_plan(&plans));
+    } else {
+        if changes.is_empty() {
+            display.show_success(up_to_date);
+        }
+
+        let mut results = Vec::new();
+
+        for change in changes {
+            let editor = resolve_ide(service, display, &change.editor);
+            let id = &change.extension_id;
+            let pb = if change.action == PlannedAction::Uninstall {
+                display.show_uninstalling(id)
+            } else {
+                display.show_installing(id)
+            };
+            let result = service.apply_manifest_change(change, marketplace_url).await;
+            pb.finish_and_clear();
+
+            let installed = change.installed_version.as_deref().unwrap_or_default();
+            let version = change.version.as_deref().unwrap_or_default();
+            let mut record =
+                InstallRecord::new(id, change.version.clone(), &change.editor, &result);
+            if change.action == PlannedAction::Uninstall && result.is_ok() {
+                record.status = InstallStatus::Removed;
+            }
+            failed |= record.status == InstallStatus::Failed;
+            let message = match result {
+                Ok(()) => match change.action {
+                    PlannedAction::Install => {
+                        format!("Installed {id} {version} to {}", editor.display_name())
+                    }
+                    PlannedAction::Update => format!(
+                        "Upgraded {id} {installed} -> {version} in {}",
+                        editor.display_name()
+                    ),
+                    PlannedAction::Downgrade => format!(
+                        "Downgraded {id} {installed} -> {version} in {}",
+                        editor.display_name()
+                    ),
+                    PlannedAction::Uninstall => {
+                        format!("Removed {id} from {}", editor.display_name())
+                    }
+                },
+                Err(e) => format!(
+                    "Could not {} {id} in {}: {e}",
+                    change.action,
+                    editor.display_name()
+                ),
+            };
+            display.show_install_result(&record, &message);
+            results.push(record);
+        }
+        check_output(display, display.show_install_results(&results));
+    }
+    failed
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    let service = ApplicationService::new();
-    let display = DisplayManager::new();
+    // Dev container extensions belong to the server inside the container, which
+    // is not running yet while the image is built
+    let devcontainer = matches!(
+        cli.command,
+        Commands::Install {
+            devcontainer: Some(_),
+            ..
+        }
+    );
+    let service = ApplicationService::new()
+        .with_server_target(cli.server || (devcontainer && cli.extensions_dir.is_none()))
+        .with_extensions_dir(cli.extensions_dir.clone());
+    // `list --json` predates `--output`
+    let output = match cli.command {
+        Commands::List { json: true, .. } => OutputFormat::Json,
+        _ => cli.output,
+    };
+    let display = DisplayManager::new().with_output(output);
 
     match cli.command {
         Commands::Search {
@@ -38,7 +166,7 @@ async fn main() -> anyhow::Result<()> {
                         total_count: results.total_count,
                     };
 
-                    display.show_search_results(&limited_results);
+                    check_output(&display, display.show_search_results(&limited_results));
                 }
                 Err(e) => {
                     display.show_error(&format!("Search failed: {e}"));
@@ -46,21 +174,724 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
-        Commands::Install { id, cursor, .. } => {
+        Commands::Install {
+            id,
+            workspace,
+            devcontainer,
+            cursor,
+            profile,
+            force,
+            pre_release,
+            dry_run,
+            ..
+        } => {
+            let ide = resolve_ide(&service, &display, select_ide(cli.ide.as_deref(), cursor));
+            let options = InstallOptions { force, pre_release };
+
+            let recommended = match (workspace, devcontainer) {
+                (Some(workspace), _) => Some((
+                    "workspace recommendations",
+                    "recommended extensions",
+                    service
+                        .missing_workspace_recommendations(
+                            &workspace,
+                            &ide.name,
+                            profile.as_deref(),
+                        )
+                        .await,
+                )),
+                (None, Some(devcontainer)) => Some((
+                    "devcontainer.json",
+                    "devcontainer.json extensions",
+                    service
+                        .missing_devcontainer_extensions(
+                            &devcontainer,
+                            &ide.name,
+                            profile.as_deref(),
+                        )
+                        .await,
+                )),
+                (None, None) => None,
+            };
+            if let Some((source, label, missing)) = recommended {
+                let missing = match missing {
+                    Ok(missing) => missing,
+                    Err(e) => {
+                        display.show_error(&format!("Reading {source} failed: {e}"));
+                        std::process::exit(1);
+                    }
+                };
+
+                let mut failed = false;
+                if dry_run {
+                    let mut changes = Vec::new();
+                    for id in &missing {
+                        match service
+                            .plan_install(
+                                id,
+                                &ide.name,
+                                profile.as_deref(),
+                                cli.marketplace.as_deref(),
+                                options,
+                            )
+                            .await
+                        {
+                            Ok(change) => changes.push(change),
+                            Err(e) => {
+                                display.show_error(&format!(
+                                    "Planning installation of {id} failed: {e}"
+                                ));
+                                failed = true;
+                            }
+                        }
+                    }
+                    check_output(&display, display.show_plan(&changes));
+                } else if missing.is_empty() {
+                    display.show_success(&format!(
+                        "All {label} are installed in {}",
+                        ide.display_name()
+                    ));
+                }
+
+                let mut results = Vec::new();
+                for id in missing.iter().filter(|_| !dry_run) {
+                    let pb = display.show_installing(id);
+                    let result = service
+                        .install_extension_with_options(
+                            id,
+                            &ide.name,
+                            profile.as_deref(),
+                            cli.marketplace.as_deref(),
+                            options,
+                        )
+                        .await;
+                    pb.finish_and_clear();
+                    let message = match &result {
+                        Ok(()) => format!("Installed {id} to {}", ide.display_name()),
+                        Err(e) => format!("Installing {id} failed: {e}"),
+                    };
+                    let record = InstallRecord::new(id, None, &ide.name, &result);
+                    failed |= record.status == InstallStatus::Failed;
+                    display.show_install_result(&record, &message);
+                    results.push(record);
+                }
+                if !dry_run {
+                    check_output(&display, display.show_install_results(&results));
+                }
+
+                if failed {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+            let Some(id) = id else {
+                unreachable!(
+                    "clap requires an extension ID unless --workspace or --devcontainer is given"
+                );
+            };
+
+            if dry_run {
+                let plan = match PackageSource::parse(&id) {
+                    PackageSource::Marketplace(_) => {
+                        service
+                            .plan_install(
+                                &id,
+                                &ide.name,
+                                profile.as_deref(),
+                                cli.marketplace.as_deref(),
+                                options,
+                            )
+                            .await
+                    }
+                    source => {
+                        service
+                            .plan_install_package(&source, &ide.name, profile.as_deref(), options)
+                            .await
+                    }
+                };
+                match plan {
+                    Ok(change) => check_output(&display, display.show_plan(&[change])),
+                    Err(e) => {
+                        display.show_error(&format!("Planning installation failed: {e}"));
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+
             let pb = display.show_installing(&id);
 
+            let result = match PackageSource::parse(&id) {
+                PackageSource::Marketplace(_) => service
+                    .install_extension_with_options(
+                        &id,
+                        &ide.name,
+                        profile.as_deref(),
+                        cli.marketplace.as_deref(),
+                        options,
+                    )
+                    .await
+                    .map(|()| (id.clone(), None)),
+                source => service
+                    .install_package(&source, &ide.name, profile.as_deref(), options)
+                    .await
+                    .map(|package| (package.id(), Some(package.version))),
+            };
+            pb.finish_and_clear();
+
+            let (record, message) = match result {
+                Ok((installed, version)) => {
+                    let message = format!(
+                        "Successfully installed {installed}{} to {}",
+                        version
+                            .as_ref()
+                            .map(|version| format!(" {version}"))
+                            .unwrap_or_default(),
+                        ide.display_name()
+                    );
+                    (
+                        InstallRecord::new(&installed, version, &ide.name, &Ok(())),
+                        message,
+                    )
+                }
+                Err(e) => {
+                    let message = match &e {
+                        DomainError::AlreadyInstalled(installed) => format!(
+                            "{installed} is already installed in {}; use --force to reinstall",
+                            ide.display_name()
+                        ),
+                        e => format!("Installation failed: {e}"),
+                    };
+                    (InstallRecord::new(&id, None, &ide.name, &Err(e)), message)
+                }
+            };
+            display.show_install_result(&record, &message);
+            check_output(
+                &display,
+                display.show_install_results(std::slice::from_ref(&record)),
+            );
+            if record.status == InstallStatus::Failed {
+                std::process::exit(1);
+            }
+        }
+        Commands::Uninstall {
+            id,
+            cursor,
+            profile,
+            dry_run,
+        } => {
+            let ide = resolve_ide(&service, &display, select_ide(cli.ide.as_deref(), cursor));
+
+            if dry_run {
+                match service
+                    .plan_uninstall(&id, &ide.name, profile.as_deref())
+                    .await
+                {
+                    Ok(change) => check_output(&display, display.show_plan(&[change])),
+                    Err(e) => {
+                        display.show_error(&format!("Planning uninstallation failed: {e}"));
+                        std::process::exit(1);
+                    }
+                }
+                return Ok(());
+            }
+
+            let pb = display.show_uninstalling(&id);
+
             match service
-                .install_extension(&id, cursor, cli.marketplace.as_deref())
+                .uninstall_extension(&id, &ide.name, profile.as_deref())
                 .await
             {
                 Ok(()) => {
                     pb.finish_and_clear();
-                    let target = if cursor { "Cursor" } else { "VSCode" };
-                    display.show_success(&format!("Successfully installed {id} to {target}"));
+                    display.show_success(&format!(
+                        "Successfully uninstalled {id} from {}",
+                        ide.display_name()
+                    ));
                 }
                 Err(e) => {
                     pb.finish_and_clear();
-                    display.show_error(&format!("Installation failed: {e}"));
+                    display.show_error(&format!("Uninstallation failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Enable {
+            ref id,
+            cursor,
+            ref profile,
+            force,
+        }
+        | Commands::Disable {
+            ref id,
+            cursor,
+            ref profile,
+            force,
+        } => {
+            let enabled = matches!(cli.command, Commands::Enable { .. });
+            let state = if enabled { "enabled" } else { "disabled" };
+            let ide = resolve_ide(&service, &display, select_ide(cli.ide.as_deref(), cursor));
+
+            match service
+                .set_extension_enabled(id, &ide.name, profile.as_deref(), enabled, force)
+                .await
+            {
+                Ok(true) => {
+                    display.show_success(&format!("{id} is now {state} in {}", ide.display_name()))
+                }
+                Ok(false) => display.show_warning(&format!(
+                    "{id} is already {state} in {}",
+                    ide.display_name()
+                )),
+                Err(e) => {
+                    display.show_error(&format!("Changing the state of {id} failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::List {
+            cursor,
+            versions,
+            profile,
+            ..
+        } => match service
+            .list_extensions(select_ide(cli.ide.as_deref(), cursor), profile.as_deref())
+            .await
+        {
+            Ok(extensions) => check_output(
+                &display,
+                display.show_installed_extensions(&extensions, versions),
+            ),
+            Err(e) => {
+                display.show_error(&format!("Listing extensions failed: {e}"));
+                std::process::exit(1);
+            }
+        },
+        Commands::Outdated { cursor, profile } => {
+            match service
+                .outdated_extensions(
+                    select_ide(cli.ide.as_deref(), cursor),
+                    profile.as_deref(),
+                    cli.marketplace.as_deref(),
+                )
+                .await
+            {
+                Ok(outdated) => check_output(&display, display.show_outdated_extensions(&outdated)),
+                Err(e) => {
+                    display.show_error(&format!("Checking for updates failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Update {
+            ids,
+            all,
+            cursor,
+            profile,
+            dry_run,
+        } => {
+            let ide = resolve_ide(&service, &display, select_ide(cli.ide.as_deref(), cursor));
+            let outdated = match service
+                .outdated_extensions(&ide.name, profile.as_deref(), cli.marketplace.as_deref())
+                .await
+            {
+                Ok(outdated) => outdated,
+                Err(e) => {
+                    display.show_error(&format!("Checking for updates failed: {e}"));
+                    std::process::exit(1);
+                }
+            };
+
+            let selected: Vec<_> = if all {
+                outdated
+            } else {
+                for id in &ids {
+                    if !outdated.iter().any(|o| o.id.eq_ignore_ascii_case(id)) {
+                        display.show_warning(&format!("{id} has no update available"));
+                    }
+                }
+                outdated
+                    .into_iter()
+                    .filter(|o| ids.iter().any(|id| id.eq_ignore_ascii_case(&o.id)))
+                    .collect()
+            };
+            let changes: Vec<ManifestChange> = selected
+                .iter()
+                .map(|update| ManifestChange::update(&ide.name, update, profile.as_deref()))
+                .collect();
+
+            let failed = apply_changes(
+                &service,
+                &display,
+                &changes,
+                dry_run,
+                cli.marketplace.as_deref(),
+                "All extensions are up to date",
+            )
+            .await;
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Commands::Gc {
+            cursor,
+            yes,
+            dry_run,
+        } => {
+            let ide = resolve_ide(&service, &display, select_ide(cli.ide.as_deref(), cursor));
+            let stale = match service.find_stale_extensions(&ide.name) {
+                Ok(stale) => stale,
+                Err(e) => {
+                    display.show_error(&format!("Scanning extensions failed: {e}"));
+                    std::process::exit(1);
+                }
+            };
+            check_output(&display, display.show_stale_extensions(&stale));
+
+            if stale.is_empty() || dry_run {
+                return Ok(());
+            }
+            if !yes && !display.confirm(&format!("Remove them from {}?", ide.display_name())) {
+                display.show_warning("Nothing was removed");
+                return Ok(());
+            }
+
+            match service.remove_stale_extensions(&ide.name, &stale) {
+                Ok(reclaimed) => display.show_reclaimed(stale.len(), reclaimed),
+                Err(e) => {
+                    display.show_error(&format!("Removing stale extensions failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Doctor => {
+            let diagnosis = match service
+                .diagnose(cli.ide.as_deref(), cli.marketplace.as_deref())
+                .await
+            {
+                Ok(diagnosis) => diagnosis,
+                Err(e) => {
+                    display.show_error(&format!("Diagnosis failed: {e}"));
+                    std::process::exit(1);
+                }
+            };
+
+            if display.show_diagnosis(&diagnosis) > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Sync {
+            from,
+            to,
+            prune,
+            dry_run,
+        } => {
+            let source = resolve_ide(&service, &display, &from);
+            let target = resolve_ide(&service, &display, &to);
+            let sync = match service
+                .diff_extensions(&source.name, &target.name, cli.marketplace.as_deref())
+                .await
+            {
+                Ok(sync) => sync,
+                Err(e) => {
+                    display.show_error(&format!("Comparing extensions failed: {e}"));
+                    std::process::exit(1);
+                }
+            };
+
+            let mut failed = false;
+            if dry_run {
+                let mut changes = Vec::new();
+                for extension in &sync.to_install {
+                    let plan = service
+                        .plan_install(
+                            &extension.unique_identifier(),
+                            &target.name,
+                            None,
+                            cli.marketplace.as_deref(),
+                            InstallOptions::default(),
+                        )
+                        .await;
+                    match plan {
+                        Ok(change) => changes.push(change),
+                        Err(e) => {
+                            display.show_error(&format!(
+                                "Planning installation of {} failed: {e}",
+                                extension.unique_identifier()
+                            ));
+                            failed = true;
+                        }
+                    }
+                }
+                for extra in sync.extras.iter().filter(|_| prune) {
+                    match service.plan_uninstall(&extra.id, &target.name, None).await {
+                        Ok(change) => changes.push(change),
+                        Err(e) => {
+                            display.show_error(&format!(
+                                "Planning removal of {} failed: {e}",
+                                extra.id
+                            ));
+                            failed = true;
+                        }
+                    }
+                }
+                check_output(&display, display.show_plan(&changes));
+            } else {
+                if sync.to_install.is_empty() && (!prune || sync.extras.is_empty()) {
+                    display.show_success(&format!(
+                        "{} already has every extension of {}",
+                        target.display_name(),
+                        source.display_name()
+                    ));
+                }
+
+                for extension in &sync.to_install {
+                    let id = extension.unique_identifier();
+                    let pb = display.show_installing(&id);
+                    let result = service
+                        .install_extension(&id, &target.name, None, cli.marketplace.as_deref())
+                        .await;
+                    pb.finish_and_clear();
+                    match result {
+                        Ok(()) => display.show_success(&format!(
+                            "Installed {id} {} to {}",
+                            extension.version,
+                            target.display_name()
+                        )),
+                        Err(e) => {
+                            display.show_error(&format!("Installing {id} failed: {e}"));
+                            failed = true;
+                        }
+                    }
+                }
+
+                for extra in sync.extras.iter().filter(|_| prune) {
+                    let pb = display.show_uninstalling(&extra.id);
+                    let result = service
+                        .uninstall_extension(&extra.id, &target.name, None)
+                        .await;
+                    pb.finish_and_clear();
+                    match result {
+                        Ok(()) => display.show_success(&format!(
+                            "Removed {} from {}",
+                            extra.id,
+                            target.display_name()
+                        )),
+                        Err(e) => {
+                            display.show_error(&format!("Removing {} failed: {e}", extra.id));
+                            failed = true;
+                        }
+                    }
+                }
+            }
+
+            if !prune && !sync.extras.is_empty() {
+                let extras: Vec<&str> = sync.extras.iter().map(|ext| ext.id.as_str()).collect();
+                display.show_warning(&format!(
+                    "Only installed in {} (use --prune to remove): {}",
+                    target.display_name(),
+                    extras.join(", ")
+                ));
+            }
+            if !sync.unavailable.is_empty() {
+                display.show_warning(&format!(
+                    "Not available in the marketplace of {}: {}",
+                    target.display_name(),
+                    sync.unavailable.join(", ")
+                ));
+            }
+
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Commands::Apply {
+            manifest,
+            prune,
+            dry_run,
+        } => {
+            let changes = match service
+                .plan_manifest(
+                    &manifest,
+                    cli.ide.as_deref(),
+                    prune,
+                    cli.marketplace.as_deref(),
+                )
+                .await
+            {
+                Ok(changes) => changes,
+                Err(e) => {
+                    display.show_error(&format!("Resolving {} failed: {e}", manifest.display()));
+                    std::process::exit(1);
+                }
+            };
+
+            let up_to_date = format!("Extensions already match {}", manifest.display());
+            let failed = apply_changes(
+                &service,
+                &display,
+                &changes,
+                dry_run,
+                cli.marketplace.as_deref(),
+                &up_to_date,
+            )
+            .await;
+
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Commands::Lock { manifest } => {
+            let pb = display.show_locking(&manifest.display().to_string());
+            let result = service
+                .lock_manifest(&manifest, cli.marketplace.as_deref())
+                .await;
+            pb.finish_and_clear();
+
+            match result {
+                Ok((path, lock)) => display.show_success(&format!(
+                    "Locked {} extension(s) in {}",
+                    lock.extensions.len(),
+                    path.display()
+                )),
+                Err(e) => {
+                    display.show_error(&format!("Locking {} failed: {e}", manifest.display()));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Profile { command } => {
+            let ide = resolve_ide(
+                &service,
+                &display,
+                select_ide(cli.ide.as_deref(), command.cursor()),
+            );
+            match command {
+                ProfileCommands::Export {
+                    file,
+                    name,
+                    no_versions,
+                    profile,
+                    ..
+                } => {
+                    let name = name.unwrap_or_else(|| {
+                        file.file_stem()
+                            .map(|stem| stem.to_string_lossy().into_owned())
+                            .unwrap_or_default()
+                    });
+                    match service
+                        .export_profile(&file, &ide.name, profile.as_deref(), &name, !no_versions)
+                        .await
+                    {
+                        Ok(exported) => display.show_success(&format!(
+                            "Exported {} extension(s) from {} to {}",
+                            exported.extensions.len(),
+                            ide.display_name(),
+                            file.display()
+                        )),
+                        Err(e) => {
+                            display
+                                .show_error(&format!("Exporting {} failed: {e}", file.display()));
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                ProfileCommands::Import {
+                    file,
+                    profile,
+                    dry_run,
+                    ..
+                } => {
+                    let (code_profile, changes) = match service
+                        .plan_profile_import(
+                            &file,
+                            &ide.name,
+                            profile.as_deref(),
+                            cli.marketplace.as_deref(),
+                        )
+                        .await
+                    {
+                        Ok(planned) => planned,
+                        Err(e) => {
+                            display
+                                .show_error(&format!("Resolving {} failed: {e}", file.display()));
+                            std::process::exit(1);
+                        }
+                    };
+
+                    let up_to_date = format!(
+                        "All extensions of {} are installed in {}",
+                        file.display(),
+                        ide.display_name()
+                    );
+                    let failed = apply_changes(
+                        &service,
+                        &display,
+                        &changes,
+                        dry_run,
+                        cli.marketplace.as_deref(),
+                        &up_to_date,
+                    )
+                    .await;
+
+                    if !dry_run {
+                        for ext in code_profile.extensions.iter().filter(|ext| ext.disabled) {
+                            match service
+                                .set_extension_enabled(
+                                    &ext.id,
+                                    &ide.name,
+                                    profile.as_deref(),
+                                    false,
+                                    false,
+                                )
+                                .await
+                            {
+                                Ok(true) => display.show_success(&format!(
+                                    "{} is now disabled in {}",
+                                    ext.id,
+                                    ide.display_name()
+                                )),
+                                Ok(false) => {}
+                                Err(e) => display
+                                    .show_warning(&format!("Could not disable {}: {e}", ext.id)),
+                            }
+                        }
+                    }
+
+                    if failed {
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Commands::Inspect { source } => {
+            let marketplace_url = match (&cli.marketplace, &cli.ide) {
+                (Some(url), _) => Some(url.clone()),
+                (None, Some(ide)) => resolve_ide(&service, &display, ide).gallery_url,
+                (None, None) => None,
+            };
+
+            match service
+                .inspect_package(&PackageSource::parse(&source), marketplace_url.as_deref())
+                .await
+            {
+                Ok(inspection) => check_output(&display, display.show_inspection(&inspection)),
+                Err(e) => {
+                    display.show_error(&format!("Inspecting {source} failed: {e}"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Pack { dir, target, out } => {
+            match service.pack_extension(&dir, target.as_ref(), out.as_deref()) {
+                Ok((package, path)) => display.show_success(&format!(
+                    "Packaged {} {} to {}",
+                    package.id(),
+                    package.version,
+                    path.display()
+                )),
+                Err(e) => {
+                    display.show_error(&format!("Packaging failed: {e}"));
                     std::process::exit(1);
                 }
             }
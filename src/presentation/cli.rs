@@ -1,14 +1,60 @@
+use crate::domain::Architecture;
+use crate::presentation::OutputFormat;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "vsix")]
-#[command(author, version, about = "Downloads and installs .vsix extensions into Visual Studio Code and Cursor", long_about = None)]
+#[command(author, version, about = "Downloads and installs .vsix extensions into Visual Studio Code, Cursor and other VS Code-based editors", long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
     #[arg(short, long, global = true, help = "Custom marketplace URL")]
     pub marketplace: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Target IDE (vscode, insiders, cursor, vscodium, windsurf, code-server, positron, trae or one from the config file)"
+    )]
+    pub ide: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Target the IDE's remote server (e.g. ~/.vscode-server/extensions) instead of the local installation"
+    )]
+    pub server: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Use a custom extensions directory (also read from VSCODE_EXTENSIONS)"
+    )]
+    pub extensions_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = OutputFormat::Table,
+        help = "Output format for search, inspect, list, outdated, install, update, gc and --dry-run results"
+    )]
+    pub output: OutputFormat,
+}
+
+/// Returns the IDE selected with `--ide`, falling back to the `--cursor` shorthand
+#[must_use]
+pub fn select_ide(ide: Option<&str>, cursor: bool) -> &str {
+    match ide {
+        Some(name) => name,
+        None if cursor => "cursor",
+        None => "vscode",
+    }
 }
 
 #[derive(Subcommand)]
@@ -35,8 +81,31 @@ pub enum Commands {
 
     #[command(about = "Install an extension")]
     Install {
-        #[arg(help = "Extension ID (format: publisher.name)")]
-        id: String,
+        #[arg(
+            help = "Extension ID (format: publisher.name), .vsix file or URL",
+            required_unless_present_any = ["workspace", "devcontainer"]
+        )]
+        id: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            num_args = 0..=1,
+            default_missing_value = ".",
+            conflicts_with = "id",
+            help = "Install the recommendations of a workspace folder or .code-workspace file"
+        )]
+        workspace: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            num_args = 0..=1,
+            default_missing_value = ".devcontainer/devcontainer.json",
+            conflicts_with_all = ["id", "workspace"],
+            help = "Install the extensions of a devcontainer.json into the editor's server extensions directory (unless --extensions-dir is given)"
+        )]
+        devcontainer: Option<PathBuf>,
 
         #[arg(
             long,
@@ -46,7 +115,293 @@ pub enum Commands {
         )]
         vscode: bool,
 
-        #[arg(long, help = "Install to Cursor")]
+        #[arg(long, help = "Install to Cursor (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Install into a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(long, help = "Reinstall even if this version is already installed")]
+        force: bool,
+
+        #[arg(long, help = "Track pre-release versions of the extension")]
+        pre_release: bool,
+
+        #[arg(
+            long,
+            help = "Print what would be downloaded and installed without doing it"
+        )]
+        dry_run: bool,
+    },
+
+    #[command(about = "Uninstall an extension")]
+    Uninstall {
+        #[arg(help = "Extension ID (format: publisher.name)")]
+        id: String,
+
+        #[arg(long, help = "Uninstall from Cursor (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Uninstall from a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(long, help = "Print what would be removed without doing it")]
+        dry_run: bool,
+    },
+
+    #[command(about = "Enable an extension that was disabled")]
+    Enable {
+        #[arg(help = "Extension ID (format: publisher.name)")]
+        id: String,
+
+        #[arg(long, help = "Enable in Cursor (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Enable in a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(long, help = "Write the editor state even while the editor is running")]
+        force: bool,
+    },
+
+    #[command(about = "Disable an extension without uninstalling it")]
+    Disable {
+        #[arg(help = "Extension ID (format: publisher.name)")]
+        id: String,
+
+        #[arg(long, help = "Disable in Cursor (shorthand for --ide cursor)")]
         cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Disable in a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(long, help = "Write the editor state even while the editor is running")]
+        force: bool,
     },
+
+    #[command(about = "List installed extensions")]
+    List {
+        #[arg(long, help = "List Cursor extensions (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, help = "Show extension versions")]
+        versions: bool,
+
+        #[arg(long, help = "Print the list as JSON (same as --output json)")]
+        json: bool,
+
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "List the extensions of a VS Code profile"
+        )]
+        profile: Option<String>,
+    },
+
+    #[command(about = "List installed extensions with newer marketplace versions")]
+    Outdated {
+        #[arg(long, help = "Check Cursor extensions (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Check a VS Code profile")]
+        profile: Option<String>,
+    },
+
+    #[command(about = "Update installed extensions to their latest versions")]
+    Update {
+        #[arg(
+            help = "Extension IDs to update (format: publisher.name)",
+            required_unless_present = "all"
+        )]
+        ids: Vec<String>,
+
+        #[arg(long, help = "Update all outdated extensions", conflicts_with = "ids")]
+        all: bool,
+
+        #[arg(long, help = "Update Cursor extensions (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Update a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(
+            long,
+            help = "Print what would be downloaded and installed without doing it"
+        )]
+        dry_run: bool,
+    },
+
+    #[command(
+        about = "Remove obsolete versions, orphaned folders and broken installs from the extensions directory"
+    )]
+    Gc {
+        #[arg(long, help = "Clean up Cursor extensions (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(short, long, help = "Remove without asking for confirmation")]
+        yes: bool,
+
+        #[arg(long, help = "Only report what would be removed")]
+        dry_run: bool,
+    },
+
+    #[command(
+        about = "Check editor CLIs, extensions directories, galleries and the system for problems"
+    )]
+    Doctor,
+
+    #[command(about = "Install the extensions of one editor into another")]
+    Sync {
+        #[arg(long, value_name = "NAME", help = "Editor to copy the extensions from")]
+        from: String,
+
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Editor to install the extensions into"
+        )]
+        to: String,
+
+        #[arg(
+            long,
+            help = "Uninstall extensions that are only installed in the target"
+        )]
+        prune: bool,
+
+        #[arg(
+            long,
+            help = "Print what would be installed and removed without doing it"
+        )]
+        dry_run: bool,
+    },
+
+    #[command(
+        about = "Install, upgrade, downgrade and remove extensions to match a vsix.toml manifest"
+    )]
+    Apply {
+        #[arg(
+            value_name = "FILE",
+            default_value = crate::domain::MANIFEST_FILE,
+            help = "Manifest listing the extensions of each editor"
+        )]
+        manifest: PathBuf,
+
+        #[arg(long, help = "Uninstall extensions the manifest does not list")]
+        prune: bool,
+
+        #[arg(
+            long,
+            help = "Print what would be installed and removed without doing it"
+        )]
+        dry_run: bool,
+    },
+
+    #[command(
+        about = "Pin the extensions of a vsix.toml manifest to exact packages and hashes in vsix.lock"
+    )]
+    Lock {
+        #[arg(
+            value_name = "FILE",
+            default_value = crate::domain::MANIFEST_FILE,
+            help = "Manifest listing the extensions of each editor"
+        )]
+        manifest: PathBuf,
+    },
+
+    #[command(about = "Export and import VS Code .code-profile files")]
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
+    #[command(about = "Show the contents and metadata of an extension without installing it")]
+    Inspect {
+        #[arg(help = ".vsix file, URL or extension ID (format: publisher.name)")]
+        source: String,
+    },
+
+    #[command(about = "Package an extension folder into a .vsix file")]
+    Pack {
+        #[arg(help = "Extension folder containing package.json", default_value = ".")]
+        dir: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "PLATFORM",
+            help = "Target platform for a platform-specific package (e.g. linux-x64)"
+        )]
+        target: Option<Architecture>,
+
+        #[arg(short, long, value_name = "FILE", help = "Output file")]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommands {
+    #[command(about = "Write installed extensions to a .code-profile file")]
+    Export {
+        #[arg(value_name = "FILE", help = ".code-profile file to write")]
+        file: PathBuf,
+
+        #[arg(long, help = "Profile name (defaults to the file name)")]
+        name: Option<String>,
+
+        #[arg(long, help = "Leave out versions so imports install the latest ones")]
+        no_versions: bool,
+
+        #[arg(long, help = "Export Cursor extensions (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Export a VS Code profile")]
+        profile: Option<String>,
+    },
+
+    #[command(about = "Install the extensions listed in a .code-profile file")]
+    Import {
+        #[arg(value_name = "FILE", help = ".code-profile file to read")]
+        file: PathBuf,
+
+        #[arg(long, help = "Import into Cursor (shorthand for --ide cursor)")]
+        cursor: bool,
+
+        #[arg(long, value_name = "NAME", help = "Import into a VS Code profile")]
+        profile: Option<String>,
+
+        #[arg(long, help = "Print what would be installed without doing it")]
+        dry_run: bool,
+    },
+}
+
+impl ProfileCommands {
+    /// Whether `--cursor` was given
+    #[must_use]
+    pub fn cursor(&self) -> bool {
+        match self {
+            Self::Export { cursor, .. } | Self::Import { cursor, .. } => *cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_ide() {
+        assert_eq!(select_ide(None, false), "vscode");
+        assert_eq!(select_ide(None, true), "cursor");
+        assert_eq!(select_ide(Some("vscodium"), false), "vscodium");
+    }
+
+    #[test]
+    fn test_profile_commands_accept_cursor() {
+        let cli =
+            Cli::try_parse_from(["vsix", "profile", "import", "team.code-profile", "--cursor"])
+                .unwrap();
+        let Commands::Profile { command } = cli.command else {
+            panic!("expected the profile command");
+        };
+        assert!(command.cursor());
+    }
 }
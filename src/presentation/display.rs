@@ -1,9 +1,20 @@
-use crate::domain::SearchResult;
+use crate::domain::{
+    EnvironmentDiagnosis, InstallationMethod, InstalledExtension, OutdatedExtension, PlannedAction,
+    PlannedChange, SearchResult, StaleExtension, VsixInspection,
+};
+use crate::presentation::{
+    ErrorRecord, FileRecord, InspectionRecord, InstallRecord, InstallStatus, OutputFormat,
+    PlanRecord, SearchRecord, write_document, write_records,
+};
 use colored::Colorize;
 use comfy_table::{ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::io::Write;
 
-pub struct DisplayManager;
+pub struct DisplayManager {
+    output: OutputFormat,
+}
 
 impl Default for DisplayManager {
     fn default() -> Self {
@@ -14,13 +25,36 @@ impl Default for DisplayManager {
 impl DisplayManager {
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self {
+            output: OutputFormat::Table,
+        }
     }
 
-    pub fn show_search_results(&self, results: &SearchResult) {
+    /// Prints results in a machine-readable format instead of tables
+    ///
+    /// Success and warning messages then go to stderr, and errors are printed to
+    /// stderr as an `{"error": ...}` document, so stdout only holds the results.
+    #[must_use]
+    pub fn with_output(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Prints the extensions found by a marketplace search
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_search_results(&self, results: &SearchResult) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            let records: Vec<SearchRecord> =
+                results.extensions.iter().map(SearchRecord::from).collect();
+            return self.print_records(&records);
+        }
+
         if results.extensions.is_empty() {
             println!("{}", "No extensions found.".yellow());
-            return;
+            return Ok(());
         }
 
         let mut table = Table::new();
@@ -45,6 +79,510 @@ impl DisplayManager {
             format!("Found {} extensions:", results.total_count).green()
         );
         println!("{table}");
+        Ok(())
+    }
+
+    /// Prints the installed extensions
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_installed_extensions(
+        &self,
+        extensions: &[InstalledExtension],
+        show_versions: bool,
+    ) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            return self.print_records(extensions);
+        }
+
+        if extensions.is_empty() {
+            println!("{}", "No extensions installed.".yellow());
+            return Ok(());
+        }
+
+        let mut header = vec!["ID"];
+        if show_versions {
+            header.push("Version");
+        }
+        header.extend(["Platform", "Size", "Installed", "State"]);
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(120)
+            .set_header(header);
+
+        for ext in extensions {
+            let mut row = vec![ext.id.clone()];
+            if show_versions {
+                row.push(ext.version.clone());
+            }
+            row.extend([
+                ext.target_platform
+                    .clone()
+                    .unwrap_or_else(|| "universal".to_string()),
+                ext.size.map_or_else(|| "-".to_string(), format_size),
+                ext.installed_at
+                    .map_or_else(|| "-".to_string(), format_date),
+                if ext.disabled {
+                    "disabled".to_string()
+                } else {
+                    "enabled".to_string()
+                },
+            ]);
+            table.add_row(row);
+        }
+
+        println!(
+            "\n{}",
+            format!("{} extensions installed:", extensions.len()).green()
+        );
+        println!("{table}");
+        Ok(())
+    }
+
+    /// Prints the extensions that have a newer version
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_outdated_extensions(
+        &self,
+        extensions: &[OutdatedExtension],
+    ) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            return self.print_records(extensions);
+        }
+
+        if extensions.is_empty() {
+            println!("{}", "All extensions are up to date.".green());
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(120)
+            .set_header(vec!["ID", "Installed", "Latest"]);
+
+        for ext in extensions {
+            table.add_row(vec![
+                ext.id.clone(),
+                ext.installed_version.clone(),
+                ext.latest_version.clone(),
+            ]);
+        }
+
+        println!(
+            "\n{}",
+            format!("{} extensions can be updated:", extensions.len()).yellow()
+        );
+        println!("{table}");
+        Ok(())
+    }
+
+    /// Prints the extension folders `gc` would remove
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_stale_extensions(&self, stale: &[StaleExtension]) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            return self.print_records(stale);
+        }
+
+        if stale.is_empty() {
+            println!("{}", "Nothing to clean up.".green());
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(120)
+            .set_header(vec!["Folder", "ID", "Version", "Reason", "Size"]);
+
+        for ext in stale {
+            table.add_row(vec![
+                ext.folder
+                    .file_name()
+                    .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+                ext.id.clone().unwrap_or_else(|| "-".to_string()),
+                ext.version.clone().unwrap_or_else(|| "-".to_string()),
+                ext.reason.to_string(),
+                format_size(ext.size),
+            ]);
+        }
+
+        let reclaimable: u64 = stale.iter().map(|ext| ext.size).sum();
+        println!(
+            "\n{}",
+            format!(
+                "{} stale extension folders, {} can be reclaimed:",
+                stale.len(),
+                format_size(reclaimable)
+            )
+            .yellow()
+        );
+        println!("{table}");
+        Ok(())
+    }
+
+    /// Reports the result of `vsix gc`
+    pub fn show_reclaimed(&self, folders: usize, bytes: u64) {
+        self.show_success(&format!(
+            "Removed {folders} stale extension folder(s), freed {}",
+            format_size(bytes)
+        ));
+    }
+
+    /// Asks a yes/no question on stdin, answering no unless the user types y or yes
+    #[must_use]
+    pub fn confirm(&self, question: &str) -> bool {
+        if self.output.is_machine_readable() {
+            eprint!("? {question} [y/N] ");
+        } else {
+            print!("{} {question} [y/N] ", "?".cyan());
+            let _ = std::io::stdout().flush();
+        }
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err() {
+            return false;
+        }
+        matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+
+    /// Prints the metadata and files of a package
+    ///
+    /// CSV output only lists the files, since the metadata holds lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_inspection(&self, inspection: &VsixInspection) -> std::io::Result<()> {
+        let record = InspectionRecord::from(inspection);
+        match self.output {
+            OutputFormat::Table => {}
+            OutputFormat::Csv => return self.print_records::<FileRecord>(&record.files),
+            OutputFormat::Json | OutputFormat::Yaml => return self.print_document(&record),
+        }
+
+        let package = &inspection.package;
+        let contributes = &package.contributes;
+
+        println!(
+            "\n{} {}",
+            package.id().bold(),
+            package.version.bright_black()
+        );
+        if let Some(display_name) = &package.display_name {
+            println!("{display_name}");
+        }
+        if let Some(description) = &package.description {
+            println!("{}", description.bright_black());
+        }
+        println!();
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(120);
+
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                "-".to_string()
+            } else {
+                items.join("\n")
+            }
+        };
+        let kinds: Vec<String> = package
+            .extension_kind
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        table.add_row(vec![
+            "Target platform".to_string(),
+            package
+                .target_platform
+                .clone()
+                .unwrap_or_else(|| "universal".to_string()),
+        ]);
+        table.add_row(vec![
+            "Engine".to_string(),
+            package.engine.clone().unwrap_or_else(|| "-".to_string()),
+        ]);
+        table.add_row(vec![
+            "Pre-release".to_string(),
+            package.pre_release.to_string(),
+        ]);
+        table.add_row(vec!["Extension kind".to_string(), kinds.join(", ")]);
+        table.add_row(vec![
+            "Activation events".to_string(),
+            list(&package.activation_events),
+        ]);
+        table.add_row(vec![
+            "Dependencies".to_string(),
+            list(&package.dependencies),
+        ]);
+        table.add_row(vec!["Extension pack".to_string(), list(&package.pack)]);
+        table.add_row(vec!["Commands".to_string(), list(&contributes.commands)]);
+        table.add_row(vec!["Languages".to_string(), list(&contributes.languages)]);
+        table.add_row(vec!["Debuggers".to_string(), list(&contributes.debuggers)]);
+        table.add_row(vec!["Settings".to_string(), list(&contributes.settings)]);
+        println!("{table}");
+
+        let native: Vec<&str> = inspection
+            .native_binaries()
+            .map(|file| file.path.as_str())
+            .collect();
+        if !native.is_empty() {
+            self.show_warning(&format!("{} native binaries bundled:", native.len()));
+            for path in native {
+                println!("  {path}");
+            }
+        }
+
+        let mut files = Table::new();
+        files
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(120)
+            .set_header(vec!["File", "Size"]);
+        for file in &inspection.files {
+            files.add_row(vec![file.path.clone(), format_size(file.size)]);
+        }
+
+        println!(
+            "\n{}",
+            format!(
+                "{} files, {} uncompressed:",
+                inspection.files.len(),
+                format_size(inspection.total_size())
+            )
+            .green()
+        );
+        println!("{files}");
+        Ok(())
+    }
+
+    /// Prints the findings of `vsix doctor` and returns the number of problems
+    ///
+    /// Editors that are not installed are listed on a single line.
+    pub fn show_diagnosis(&self, diagnosis: &EnvironmentDiagnosis) -> usize {
+        println!("{}", "System".bold());
+        println!(
+            "  platform:   {}",
+            diagnosis.platform.as_deref().unwrap_or("unsupported")
+        );
+        println!(
+            "  libc:       {}",
+            diagnosis.libc.as_deref().unwrap_or("unknown")
+        );
+        if diagnosis.proxies.is_empty() {
+            println!("  proxy:      none");
+        }
+        for (name, value) in &diagnosis.proxies {
+            println!("  proxy:      {name}={value}");
+        }
+        if let Some(path) = &diagnosis.config_path {
+            let state = if diagnosis.config_exists {
+                "found"
+            } else {
+                "not found"
+            };
+            println!("  config:     {} ({state})", path.display());
+        }
+        let mut problems = self.show_problems(&diagnosis.warnings());
+
+        let (present, missing): (Vec<_>, Vec<_>) = diagnosis
+            .editors
+            .iter()
+            .partition(|editor| editor.is_present() || diagnosis.editors.len() == 1);
+        for editor in present {
+            println!("\n{} ({})", editor.display_name.bold(), editor.name);
+            match (&editor.cli_path, &editor.cli_version) {
+                (Some(path), Ok(version)) => {
+                    println!("  cli:        {} ({version})", path.display());
+                }
+                (Some(path), Err(_)) => println!("  cli:        {}", path.display()),
+                (None, _) => println!("  cli:        not in PATH"),
+            }
+            if let Ok(method) = &editor.install_method {
+                println!("  installs:   via {method}");
+            }
+            if let Ok(dir) = &editor.extensions_dir {
+                let source = editor
+                    .extensions_dir_source
+                    .map(|source| format!("{source}, "))
+                    .unwrap_or_default();
+                println!(
+                    "  extensions: {} ({source}{}, {} installed)",
+                    dir.display(),
+                    editor.extensions_dir_access,
+                    editor.installed_count
+                );
+            }
+            match &editor.gallery_latency {
+                Ok(millis) => println!("  gallery:    {} ({millis} ms)", editor.gallery_url),
+                Err(_) => println!("  gallery:    {}", editor.gallery_url),
+            }
+            problems += self.show_problems(&editor.warnings());
+        }
+
+        if !missing.is_empty() {
+            let names: Vec<String> = missing
+                .iter()
+                .map(|editor| format!("{} ({})", editor.display_name, editor.name))
+                .collect();
+            println!("\nNot found: {}", names.join(", "));
+        }
+
+        println!();
+        if problems == 0 {
+            self.show_success("No problems found");
+        } else {
+            self.show_warning(&format!("{problems} problem(s) found"));
+        }
+        problems
+    }
+
+    fn show_problems(&self, warnings: &[String]) -> usize {
+        for warning in warnings {
+            println!("  {} {}", "!".yellow(), warning.yellow());
+        }
+        warnings.len()
+    }
+
+    /// Prints the changes a `--dry-run` would make, one block per extension
+    ///
+    /// The output is plain text so that it can be pasted into a code review.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_plan(&self, changes: &[PlannedChange]) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            let records: Vec<PlanRecord> = changes.iter().map(PlanRecord::from).collect();
+            return self.print_records(&records);
+        }
+
+        if changes.is_empty() {
+            println!("{}", "Dry run: nothing to do.".green());
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            format!(
+                "Dry run: {} change(s) planned, nothing was downloaded or written.",
+                changes.len()
+            )
+            .yellow()
+        );
+
+        for change in changes {
+            let version = match (&change.installed_version, &change.version) {
+                (Some(installed), Some(version)) => format!(" {installed} -> {version}"),
+                (None, Some(version)) | (Some(version), None) => format!(" {version}"),
+                (None, None) => String::new(),
+            };
+            let platform = change
+                .target_platform
+                .as_ref()
+                .map(|platform| format!(" ({platform})"))
+                .unwrap_or_default();
+            println!(
+                "\n{} {}{version}{platform}",
+                change.action.to_string().bold(),
+                change.extension_id.bold()
+            );
+
+            let strategy = &change.strategy;
+            let method = match &strategy.method {
+                InstallationMethod::CliCommand { command_path, .. } => {
+                    format!(
+                        "{} CLI ({})",
+                        strategy.ide_type.display_name(),
+                        command_path.display()
+                    )
+                }
+                InstallationMethod::FileSystem { .. } => "extensions directory".to_string(),
+                InstallationMethod::RemoteServer { .. } => "remote server".to_string(),
+            };
+            println!("  via:       {method}");
+            if let Some(profile) = &strategy.profile {
+                println!(
+                    "  profile:   {} ({})",
+                    profile.name,
+                    profile.registry_path.display()
+                );
+            }
+            if let Some(url) = &change.download_url {
+                println!("  download:  {url}");
+            }
+            if let Some(destination) = &change.destination {
+                let verb = if change.action == PlannedAction::Uninstall {
+                    "removes"
+                } else {
+                    "writes"
+                };
+                println!("  {:<11}{}", format!("{verb}:"), destination.display());
+            }
+            if !change.dependencies.is_empty() {
+                println!("  requires:  {}", change.dependencies.join(", "));
+            }
+            let options: Vec<&str> = [
+                (strategy.options.force, "--force"),
+                (strategy.options.pre_release, "--pre-release"),
+            ]
+            .into_iter()
+            .filter_map(|(set, flag)| set.then_some(flag))
+            .collect();
+            if !options.is_empty() {
+                println!("  options:   {}", options.join(" "));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports the result of installing or updating an extension as it finishes
+    ///
+    /// Machine-readable formats print all results at once with
+    /// [`Self::show_install_results`] instead.
+    pub fn show_install_result(&self, result: &InstallRecord, message: &str) {
+        if self.output.is_machine_readable() {
+            return;
+        }
+        match result.status {
+            InstallStatus::Installed | InstallStatus::Removed => self.show_success(message),
+            InstallStatus::AlreadyInstalled => self.show_warning(message),
+            InstallStatus::Failed => self.show_error(message),
+        }
+    }
+
+    /// Prints the results of an install or update in a machine-readable format
+    ///
+    /// Tables already showed each result with [`Self::show_install_result`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of writing machine-readable output to stdout
+    pub fn show_install_results(&self, results: &[InstallRecord]) -> std::io::Result<()> {
+        if self.output.is_machine_readable() {
+            return self.print_records(results);
+        }
+        Ok(())
     }
 
     /// Shows a progress spinner for installation
@@ -54,6 +592,36 @@ impl DisplayManager {
     /// Panics if the progress bar template cannot be parsed
     #[must_use]
     pub fn show_installing(&self, extension_id: &str) -> ProgressBar {
+        self.show_spinner(&format!("Installing {extension_id}..."))
+    }
+
+    /// Shows a progress spinner for uninstallation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the progress bar template cannot be parsed
+    #[must_use]
+    pub fn show_uninstalling(&self, extension_id: &str) -> ProgressBar {
+        self.show_spinner(&format!("Uninstalling {extension_id}..."))
+    }
+
+    /// Shows a progress spinner while a manifest's packages are resolved and hashed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the progress bar template cannot be parsed
+    #[must_use]
+    pub fn show_locking(&self, manifest: &str) -> ProgressBar {
+        self.show_spinner(&format!(
+            "Resolving and hashing the extensions of {manifest}..."
+        ))
+    }
+
+    fn show_spinner(&self, message: &str) -> ProgressBar {
+        if self.output.is_machine_readable() {
+            return ProgressBar::hidden();
+        }
+
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::default_spinner()
@@ -61,17 +629,48 @@ impl DisplayManager {
                 .unwrap()
                 .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
         );
-        pb.set_message(format!("Installing {extension_id}..."));
+        pb.set_message(message.to_string());
         pb.enable_steady_tick(std::time::Duration::from_millis(80));
         pb
     }
 
     pub fn show_success(&self, message: &str) {
-        println!("{} {}", "✓".green(), message.green());
+        if self.output.is_machine_readable() {
+            eprintln!("{message}");
+        } else {
+            println!("{} {}", "✓".green(), message.green());
+        }
     }
 
+    pub fn show_warning(&self, message: &str) {
+        if self.output.is_machine_readable() {
+            eprintln!("{message}");
+        } else {
+            println!("{} {}", "!".yellow(), message.yellow());
+        }
+    }
+
+    /// Prints an error to stderr, as an `{"error": ...}` document in
+    /// machine-readable formats
     pub fn show_error(&self, error: &str) {
-        eprintln!("{} {}", "✗".red(), error.red());
+        if self.output.is_machine_readable() {
+            let record = ErrorRecord {
+                error: error.to_string(),
+            };
+            let _ = write_document(self.output, &mut std::io::stderr().lock(), &record);
+        } else {
+            eprintln!("{} {}", "✗".red(), error.red());
+        }
+    }
+
+    /// Prints records to stdout in the selected machine-readable format
+    fn print_records<T: Serialize>(&self, records: &[T]) -> std::io::Result<()> {
+        write_records(self.output, &mut std::io::stdout().lock(), records)
+    }
+
+    /// Prints a document to stdout in the selected machine-readable format
+    fn print_document<T: Serialize>(&self, document: &T) -> std::io::Result<()> {
+        write_document(self.output, &mut std::io::stdout().lock(), document)
     }
 }
 
@@ -86,6 +685,40 @@ fn format_downloads(count: u64) -> String {
     }
 }
 
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 * 1024 {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+/// Formats milliseconds since the Unix epoch as a UTC `YYYY-MM-DD` date
+fn format_date(timestamp_millis: u64) -> String {
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let days = i64::try_from(timestamp_millis / 86_400_000).unwrap_or(0) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +729,18 @@ mod tests {
         assert_eq!(format_downloads(1_500), "1.5K");
         assert_eq!(format_downloads(1_500_000), "1.5M");
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1_536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000_000), "2023-11-14");
+        assert_eq!(format_date(951_782_400_000), "2000-02-29");
+    }
 }
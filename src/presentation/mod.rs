@@ -1,5 +1,7 @@
 pub mod cli;
 pub mod display;
+pub mod output;
 
 pub use cli::*;
 pub use display::*;
+pub use output::*;
@@ -0,0 +1,372 @@
+use crate::domain::{DomainError, Extension, InstallationMethod, PlannedChange, VsixInspection};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// How results are printed, selected with `--output`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored tables and messages for humans
+    #[default]
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl OutputFormat {
+    /// Whether the format is meant for scripts rather than humans
+    #[must_use]
+    pub fn is_machine_readable(self) -> bool {
+        self != Self::Table
+    }
+}
+
+/// A marketplace search result
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SearchRecord {
+    /// Extension ID in format "publisher.name"
+    pub id: String,
+    pub display_name: String,
+    pub publisher: String,
+    pub version: String,
+    pub downloads: u64,
+    pub description: Option<String>,
+}
+
+impl From<&Extension> for SearchRecord {
+    fn from(ext: &Extension) -> Self {
+        Self {
+            id: ext.unique_identifier(),
+            display_name: ext.display_name.clone(),
+            publisher: ext.publisher.clone(),
+            version: ext.version.clone(),
+            downloads: ext.downloads,
+            description: ext.description.clone(),
+        }
+    }
+}
+
+/// The metadata and contents of an inspected package
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct InspectionRecord {
+    pub id: String,
+    pub version: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    /// `None` for universal packages
+    pub target_platform: Option<String>,
+    pub engine: Option<String>,
+    pub pre_release: bool,
+    pub extension_kind: Vec<String>,
+    pub activation_events: Vec<String>,
+    pub dependencies: Vec<String>,
+    pub pack: Vec<String>,
+    pub commands: Vec<String>,
+    pub languages: Vec<String>,
+    pub debuggers: Vec<String>,
+    pub settings: Vec<String>,
+    pub files: Vec<FileRecord>,
+}
+
+/// A file inside an inspected package
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct FileRecord {
+    pub path: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+    /// Whether the file is a native executable or library
+    pub native: bool,
+}
+
+impl From<&VsixInspection> for InspectionRecord {
+    fn from(inspection: &VsixInspection) -> Self {
+        let package = &inspection.package;
+        let contributes = &package.contributes;
+        Self {
+            id: package.id(),
+            version: package.version.clone(),
+            display_name: package.display_name.clone(),
+            description: package.description.clone(),
+            target_platform: package.target_platform.clone(),
+            engine: package.engine.clone(),
+            pre_release: package.pre_release,
+            extension_kind: package
+                .extension_kind
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            activation_events: package.activation_events.clone(),
+            dependencies: package.dependencies.clone(),
+            pack: package.pack.clone(),
+            commands: contributes.commands.clone(),
+            languages: contributes.languages.clone(),
+            debuggers: contributes.debuggers.clone(),
+            settings: contributes.settings.clone(),
+            files: inspection
+                .files
+                .iter()
+                .map(|file| FileRecord {
+                    path: file.path.clone(),
+                    size: file.size,
+                    native: file.native,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A change a `--dry-run` would make
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PlanRecord {
+    /// "install", "update", "downgrade" or "uninstall"
+    pub action: String,
+    pub id: String,
+    pub version: Option<String>,
+    pub installed_version: Option<String>,
+    pub target_platform: Option<String>,
+    /// "cli", "extensions-directory" or "remote-server"
+    pub method: String,
+    pub profile: Option<String>,
+    pub download_url: Option<String>,
+    /// Folder that would be written or removed
+    pub destination: Option<PathBuf>,
+}
+
+impl From<&PlannedChange> for PlanRecord {
+    fn from(change: &PlannedChange) -> Self {
+        let strategy = &change.strategy;
+        Self {
+            action: change.action.to_string(),
+            id: change.extension_id.clone(),
+            version: change.version.clone(),
+            installed_version: change.installed_version.clone(),
+            target_platform: change.target_platform.clone(),
+            method: match strategy.method {
+                InstallationMethod::CliCommand { .. } => "cli",
+                InstallationMethod::FileSystem { .. } => "extensions-directory",
+                InstallationMethod::RemoteServer { .. } => "remote-server",
+            }
+            .to_string(),
+            profile: strategy
+                .profile
+                .as_ref()
+                .map(|profile| profile.name.clone()),
+            download_url: change.download_url.clone(),
+            destination: change.destination.clone(),
+        }
+    }
+}
+
+/// Outcome of installing, updating or removing one extension
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallStatus {
+    Installed,
+    AlreadyInstalled,
+    /// Uninstalled by `apply --prune`
+    Removed,
+    Failed,
+}
+
+/// The result of installing, updating or removing one extension
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct InstallRecord {
+    /// Extension ID, or the package path or URL if the ID is unknown
+    pub id: String,
+    /// Installed version, if known
+    pub version: Option<String>,
+    /// Name of the editor, e.g. "vscode"
+    pub editor: String,
+    pub status: InstallStatus,
+    /// Why the installation failed
+    pub error: Option<String>,
+}
+
+impl InstallRecord {
+    /// Builds the record of installing `id` into `editor` from the result
+    #[must_use]
+    pub fn new(
+        id: &str,
+        version: Option<String>,
+        editor: &str,
+        result: &Result<(), DomainError>,
+    ) -> Self {
+        let (status, error) = match result {
+            Ok(()) => (InstallStatus::Installed, None),
+            Err(DomainError::AlreadyInstalled(_)) => (InstallStatus::AlreadyInstalled, None),
+            Err(e) => (InstallStatus::Failed, Some(e.to_string())),
+        };
+        Self {
+            id: id.to_string(),
+            version: version.filter(|_| status == InstallStatus::Installed),
+            editor: editor.to_string(),
+            status,
+            error,
+        }
+    }
+}
+
+/// An error that ended a command
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ErrorRecord {
+    pub error: String,
+}
+
+/// Writes records as a JSON or YAML list, or as CSV rows with a header
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error if a record cannot be serialized, e.g. a CSV
+/// field holding a list, or the error of writing the output, e.g. `BrokenPipe`
+/// when the reader of a pipe has exited
+pub fn write_records<T: Serialize>(
+    format: OutputFormat,
+    out: &mut impl Write,
+    records: &[T],
+) -> io::Result<()> {
+    let rendered = match format {
+        OutputFormat::Json => render_json(records)?,
+        OutputFormat::Yaml => render_yaml(records)?,
+        OutputFormat::Csv | OutputFormat::Table => render_csv(records.iter())?,
+    };
+    out.write_all(&rendered)?;
+    out.flush()
+}
+
+/// Writes a single document as JSON or YAML
+///
+/// CSV writes the document as a one-row table, so it must not contain lists.
+///
+/// # Errors
+///
+/// Returns an error like [`write_records`]
+pub fn write_document<T: Serialize>(
+    format: OutputFormat,
+    out: &mut impl Write,
+    document: &T,
+) -> io::Result<()> {
+    let rendered = match format {
+        OutputFormat::Json => render_json(document)?,
+        OutputFormat::Yaml => render_yaml(document)?,
+        OutputFormat::Csv | OutputFormat::Table => render_csv(std::iter::once(document))?,
+    };
+    out.write_all(&rendered)?;
+    out.flush()
+}
+
+fn invalid_data(error: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+fn render_json<T: Serialize + ?Sized>(value: &T) -> io::Result<Vec<u8>> {
+    let mut rendered = serde_json::to_vec_pretty(value).map_err(invalid_data)?;
+    rendered.push(b'\n');
+    Ok(rendered)
+}
+
+fn render_yaml<T: Serialize + ?Sized>(value: &T) -> io::Result<Vec<u8>> {
+    serde_yaml::to_string(value)
+        .map(String::into_bytes)
+        .map_err(invalid_data)
+}
+
+fn render_csv<'a, T: Serialize + 'a>(records: impl Iterator<Item = &'a T>) -> io::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.serialize(record).map_err(invalid_data)?;
+    }
+    writer.into_inner().map_err(invalid_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, status: InstallStatus, error: Option<&str>) -> InstallRecord {
+        InstallRecord {
+            id: id.to_string(),
+            version: (status == InstallStatus::Installed).then(|| "1.2.0".to_string()),
+            editor: "vscode".to_string(),
+            status,
+            error: error.map(ToString::to_string),
+        }
+    }
+
+    fn render<T: Serialize>(format: OutputFormat, records: &[T]) -> String {
+        let mut out = Vec::new();
+        write_records(format, &mut out, records).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_records() {
+        let records = [
+            record("acme.tools", InstallStatus::Installed, None),
+            record(
+                "acme.gone",
+                InstallStatus::Failed,
+                Some("not found, \"acme\""),
+            ),
+        ];
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(OutputFormat::Json, &records)).unwrap();
+        assert_eq!(json[0]["status"], "installed");
+        assert_eq!(json[1]["version"], serde_json::Value::Null);
+        assert_eq!(json[1]["error"], "not found, \"acme\"");
+
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(&render(OutputFormat::Yaml, &records)).unwrap();
+        assert_eq!(yaml[0]["id"].as_str(), Some("acme.tools"));
+
+        assert_eq!(
+            render(OutputFormat::Csv, &records),
+            "id,version,editor,status,error\n\
+             acme.tools,1.2.0,vscode,installed,\n\
+             acme.gone,,vscode,failed,\"not found, \"\"acme\"\"\"\n"
+        );
+        assert_eq!(render::<InstallRecord>(OutputFormat::Csv, &[]), "");
+    }
+
+    #[test]
+    fn test_write_document() {
+        let error = ErrorRecord {
+            error: "Search failed".to_string(),
+        };
+        let mut out = Vec::new();
+        write_document(OutputFormat::Yaml, &mut out, &error).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "error: Search failed\n");
+
+        let mut out = Vec::new();
+        write_document(OutputFormat::Csv, &mut out, &error).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "error\nSearch failed\n");
+    }
+
+    /// A pipe whose reader has exited, e.g. `vsix --output json list | head -1`
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_errors_are_returned() {
+        let records = [record("acme.tools", InstallStatus::Installed, None)];
+        for format in [OutputFormat::Json, OutputFormat::Yaml, OutputFormat::Csv] {
+            let error = write_records(format, &mut ClosedPipe, &records).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        }
+
+        let nested = [serde_json::json!({"files": ["a", "b"]})];
+        let error = write_records(OutputFormat::Csv, &mut Vec::new(), &nested).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}